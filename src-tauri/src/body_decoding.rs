@@ -0,0 +1,399 @@
+use std::io::Read;
+
+use base64::engine::general_purpose;
+use base64::Engine;
+
+use crate::models::{ParseNode, ParseResult};
+use crate::multipart::format_size;
+use crate::parse_utils;
+
+/// 解压后 body 的最大字节数，防止压缩炸弹
+const MAX_DECODED_SIZE: u64 = 16 * 1024 * 1024;
+
+/// body 传输层后处理：按 `Transfer-Encoding: chunked` 去分块并收集 trailer，
+/// 再按 `Transfer-Encoding` / `Content-Encoding` 逆序解压（gzip / deflate / br / zstd）。
+/// 压缩数据以 base64 文本粘贴时先做 base64 解码。每一步记录在 `body_provenance` 中，
+/// 原始 body 保留在 `wire_body`；任一步失败时停在上一步的结果。
+pub fn apply_body_decoding(result: &mut ParseResult) {
+    let Some(wire) = result.body.clone() else {
+        return;
+    };
+
+    let transfer_codings = codings(parse_utils::header_value(
+        &result.headers,
+        "transfer-encoding",
+    ));
+    let content_codings = codings(parse_utils::header_value(
+        &result.headers,
+        "content-encoding",
+    ));
+
+    let mut steps: Vec<ParseNode> = Vec::new();
+    let mut current = wire.clone().into_bytes();
+
+    if transfer_codings.iter().any(|c| c == "chunked") {
+        if let Some((data, trailers)) = dechunk(&wire) {
+            steps.push(step_node("chunked", current.len(), data.len()));
+            current = data.into_bytes();
+            if !trailers.is_empty() {
+                result.trailers = Some(trailers);
+            }
+        }
+    }
+
+    // 发送方先做 Content-Encoding 再做 Transfer-Encoding，解码时倒序处理
+    let pending: Vec<&String> = content_codings
+        .iter()
+        .chain(transfer_codings.iter().filter(|c| *c != "chunked"))
+        .rev()
+        .collect();
+
+    let mut base64_done = false;
+    for coding in pending {
+        if let Some(decoded) = decompress(coding, &current) {
+            steps.push(step_node(coding, current.len(), decoded.len()));
+            current = decoded;
+            continue;
+        }
+        // 压缩数据常以 base64 形式出现（HAR、代理日志）
+        if base64_done {
+            break;
+        }
+        let Some(raw) = decode_base64(&current) else {
+            break;
+        };
+        let Some(decoded) = decompress(coding, &raw) else {
+            break;
+        };
+        steps.push(step_node("base64", current.len(), raw.len()));
+        steps.push(step_node(coding, raw.len(), decoded.len()));
+        base64_done = true;
+        current = decoded;
+    }
+
+    // 没有声明编码，但 body 是 gzip / zstd 数据的 base64
+    if content_codings.is_empty() && steps.iter().all(|s| s.key == "chunked") {
+        if let Some(raw) = decode_base64(&current) {
+            if let Some(coding) = sniff_compression(&raw) {
+                if let Some(decoded) = decompress(coding, &raw) {
+                    steps.push(step_node("base64", current.len(), raw.len()));
+                    steps.push(step_node(coding, raw.len(), decoded.len()));
+                    current = decoded;
+                }
+            }
+        }
+    }
+
+    if steps.is_empty() {
+        return;
+    }
+    result.body = Some(String::from_utf8_lossy(&current).into_owned());
+    result.wire_body = Some(wire);
+    result.body_provenance = Some(steps);
+}
+
+/// 拆分 `gzip, br` 形式的编码列表，忽略 identity。
+fn codings(header: Option<&str>) -> Vec<String> {
+    header
+        .map(|value| {
+            value
+                .split(',')
+                .map(|c| c.trim().to_lowercase())
+                .filter(|c| !c.is_empty() && c != "identity")
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn step_node(coding: &str, before: usize, after: usize) -> ParseNode {
+    ParseNode {
+        key: coding.to_string(),
+        value: format!("{} → {}", format_size(before), format_size(after)),
+        ..Default::default()
+    }
+}
+
+/// 去除 chunked 分块，返回（数据, trailer）。
+/// 粘贴文本中 CRLF 常被换成 LF，块长度对不上时退化为按行读取，直到下一行块长度。
+fn dechunk(body: &str) -> Option<(String, Vec<ParseNode>)> {
+    let mut rest = body;
+    let mut data = String::new();
+    let mut trailers = Vec::new();
+
+    while !rest.is_empty() {
+        let (line, after) = split_line(rest);
+        let size = parse_chunk_size(line)?;
+
+        if size == 0 {
+            let mut remaining = after;
+            while !remaining.is_empty() {
+                let (trailer, next) = split_line(remaining);
+                if trailer.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = trailer.split_once(':') {
+                    let (name, value) = (name.trim(), value.trim());
+                    trailers.push(ParseNode {
+                        key: name.to_string(),
+                        value: value.to_string(),
+                        children: parse_utils::parse_header_value_children(name, value),
+                        ..Default::default()
+                    });
+                }
+                remaining = next;
+            }
+            return Some((data, trailers));
+        }
+
+        // 长度准确时，块数据之后紧跟换行和下一个块长度行
+        let exact = after
+            .get(..size)
+            .zip(after.get(size..))
+            .filter(|(_, tail)| {
+                (tail.starts_with('\n') || tail.starts_with("\r\n"))
+                    && parse_chunk_size(split_line(split_line(tail).1).0).is_some()
+            });
+        if let Some((chunk, tail)) = exact {
+            data.push_str(chunk);
+            rest = split_line(tail).1;
+            continue;
+        }
+
+        let mut lines = Vec::new();
+        let mut cursor = after;
+        while !cursor.is_empty() {
+            let (next_line, next) = split_line(cursor);
+            if !lines.is_empty() && parse_chunk_size(next_line).is_some() {
+                break;
+            }
+            lines.push(next_line);
+            cursor = next;
+        }
+        data.push_str(&lines.join("\n"));
+        rest = cursor;
+    }
+
+    // 截断的 chunked body（缺少结尾的 0 块）
+    if data.is_empty() {
+        None
+    } else {
+        Some((data, trailers))
+    }
+}
+
+/// 返回（去掉换行的当前行, 剩余文本）。
+fn split_line(text: &str) -> (&str, &str) {
+    match text.find('\n') {
+        Some(pos) => (text[..pos].trim_end_matches('\r'), &text[pos + 1..]),
+        None => (text.trim_end_matches('\r'), ""),
+    }
+}
+
+/// 解析块长度行（十六进制，可带 `;ext` 扩展）。
+fn parse_chunk_size(line: &str) -> Option<usize> {
+    let size = line.split(';').next()?.trim();
+    if size.is_empty() || size.len() > 8 {
+        return None;
+    }
+    usize::from_str_radix(size, 16).ok()
+}
+
+fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+    let text: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    if text.len() < 8 {
+        return None;
+    }
+    general_purpose::STANDARD
+        .decode(&text)
+        .or_else(|_| general_purpose::URL_SAFE.decode(&text))
+        .ok()
+}
+
+/// 按魔数识别 gzip / zstd（br、deflate 没有可靠的魔数）。
+fn sniff_compression(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        Some("gzip")
+    } else if data.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Some("zstd")
+    } else {
+        None
+    }
+}
+
+/// 按编码名解压，失败或结果为空时返回 None。
+fn decompress(coding: &str, data: &[u8]) -> Option<Vec<u8>> {
+    let reader: Box<dyn Read + '_> = match coding {
+        "gzip" | "x-gzip" => Box::new(flate2::read::MultiGzDecoder::new(data)),
+        "deflate" => {
+            // 规范要求 zlib 封装，但不少服务端直接发送 raw deflate
+            if let Some(out) = read_limited(flate2::read::ZlibDecoder::new(data)) {
+                return Some(out);
+            }
+            Box::new(flate2::read::DeflateDecoder::new(data))
+        }
+        "br" => Box::new(brotli_decompressor::Decompressor::new(data, 4096)),
+        "zstd" => Box::new(ruzstd::decoding::StreamingDecoder::new(data).ok()?),
+        _ => return None,
+    };
+    read_limited(reader)
+}
+
+fn read_limited(reader: impl Read) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    reader.take(MAX_DECODED_SIZE).read_to_end(&mut out).ok()?;
+    if out.is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    use crate::parser::parse_http_text;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_dechunk_with_trailers() {
+        let input = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nTrailer: Expires\r\n\r\n7\r\nMozilla\r\n9\r\nDeveloper\r\n7\r\nNetwork\r\n0\r\nExpires: Wed, 21 Oct 2015 07:28:00 GMT\r\n\r\n";
+        let mut result = parse_http_text(input);
+        apply_body_decoding(&mut result);
+
+        assert_eq!(result.body.as_deref(), Some("MozillaDeveloperNetwork"));
+        let trailers = result.trailers.unwrap();
+        assert_eq!(trailers[0].key, "Expires");
+        assert_eq!(trailers[0].value, "Wed, 21 Oct 2015 07:28:00 GMT");
+
+        let steps = result.body_provenance.unwrap();
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].key, "chunked");
+        assert!(result.wire_body.unwrap().starts_with("7\n"));
+    }
+
+    #[test]
+    fn test_dechunk_tolerates_lost_crlf() {
+        // 块长度按 CRLF 计算，但粘贴后只剩 LF
+        let body = "d\nline1\r\nline2\n0\n";
+        let (data, _) = dechunk(&body.replace("\r\n", "\n")).unwrap();
+        assert_eq!(data, "line1\nline2");
+    }
+
+    #[test]
+    fn test_dechunk_multiline_json() {
+        let body = "1a\n{\"a\":1,\n\"b\":\"hello world\"}\n0\n\n";
+        let (data, trailers) = dechunk(body).unwrap();
+        assert_eq!(data, "{\"a\":1,\n\"b\":\"hello world\"}");
+        assert!(trailers.is_empty());
+    }
+
+    #[test]
+    fn test_not_chunked_left_untouched() {
+        let input = "HTTP/1.1 200 OK\nTransfer-Encoding: chunked\n\nnot a chunk";
+        let mut result = parse_http_text(input);
+        apply_body_decoding(&mut result);
+        assert_eq!(result.body.as_deref(), Some("not a chunk"));
+        assert!(result.wire_body.is_none());
+        assert!(result.body_provenance.is_none());
+    }
+
+    #[test]
+    fn test_base64_gzip_with_content_encoding() {
+        let encoded = general_purpose::STANDARD.encode(gzip(b"{\"ok\":true}"));
+        let input = format!(
+            "HTTP/1.1 200 OK\nContent-Encoding: gzip\nContent-Type: application/json\n\n{encoded}"
+        );
+        let mut result = parse_http_text(&input);
+        apply_body_decoding(&mut result);
+
+        assert_eq!(result.body.as_deref(), Some("{\"ok\":true}"));
+        assert_eq!(result.wire_body.as_deref(), Some(encoded.as_str()));
+        let steps = result.body_provenance.unwrap();
+        assert_eq!(steps[0].key, "base64");
+        assert_eq!(steps[1].key, "gzip");
+    }
+
+    #[test]
+    fn test_base64_gzip_sniffed_without_header() {
+        let encoded = general_purpose::STANDARD.encode(gzip(b"hello"));
+        let input = format!("HTTP/1.1 200 OK\n\n{encoded}");
+        let mut result = parse_http_text(&input);
+        apply_body_decoding(&mut result);
+        assert_eq!(result.body.as_deref(), Some("hello"));
+    }
+
+    #[test]
+    fn test_chunked_then_gzip() {
+        let encoded = general_purpose::STANDARD.encode(gzip(b"chunked and zipped"));
+        let body = format!("{:x}\n{encoded}\n0\n", encoded.len());
+        let input = format!(
+            "HTTP/1.1 200 OK\nTransfer-Encoding: chunked\nContent-Encoding: gzip\n\n{body}"
+        );
+        let mut result = parse_http_text(&input);
+        apply_body_decoding(&mut result);
+
+        assert_eq!(result.body.as_deref(), Some("chunked and zipped"));
+        let keys: Vec<String> = result
+            .body_provenance
+            .unwrap()
+            .into_iter()
+            .map(|s| s.key)
+            .collect();
+        assert_eq!(keys, vec!["chunked", "base64", "gzip"]);
+    }
+
+    #[test]
+    fn test_decompress_deflate_variants() {
+        let mut zlib = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        zlib.write_all(b"zlib data").unwrap();
+        assert_eq!(
+            decompress("deflate", &zlib.finish().unwrap()).unwrap(),
+            b"zlib data"
+        );
+
+        let mut raw =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        raw.write_all(b"raw data").unwrap();
+        assert_eq!(
+            decompress("deflate", &raw.finish().unwrap()).unwrap(),
+            b"raw data"
+        );
+    }
+
+    #[test]
+    fn test_decompress_brotli() {
+        // "hello" 的 brotli 压缩结果
+        let data = [0x0b, 0x02, 0x80, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x03];
+        assert_eq!(decompress("br", &data).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_decompress_zstd() {
+        // "hello" 的 zstd 压缩结果
+        let data = [
+            0x28, 0xb5, 0x2f, 0xfd, 0x04, 0x58, 0x29, 0x00, 0x00, 0x68, 0x65, 0x6c, 0x6c, 0x6f,
+            0xa3, 0x6d, 0x9f, 0x88,
+        ];
+        assert_eq!(decompress("zstd", &data).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_invalid_compressed_body_kept() {
+        let input = "HTTP/1.1 200 OK\nContent-Encoding: gzip\n\nplain text, not gzip";
+        let mut result = parse_http_text(input);
+        apply_body_decoding(&mut result);
+        assert_eq!(result.body.as_deref(), Some("plain text, not gzip"));
+        assert!(result.body_provenance.is_none());
+    }
+}
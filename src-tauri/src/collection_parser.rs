@@ -0,0 +1,1046 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::models::{CollectionRequest, HttpContentType, ParseNode, ParseResult};
+use crate::multipart::{self, FormPart, FORM_BOUNDARY};
+use crate::parse_utils::{self, header_node, metadata_node, str_field};
+use crate::query_tree;
+
+/// Insomnia（Nunjucks）的 `{{ _.name }}` 写法，去掉 `_.` 前缀后与 `{{name}}` 统一处理
+static RE_NUNJUCKS_PREFIX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{\{(\s*)_\.").unwrap());
+
+/// Insomnia 模板标签：`{% uuid %}`、`{% response ... %}` 等，无法在导入时求值
+static RE_TEMPLATE_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{%\s*(.*?)\s*%\}").unwrap());
+
+/// 集合中的认证设置，统一后再转换为具体的 header / query 参数
+enum Auth {
+    None,
+    Bearer {
+        prefix: String,
+        token: String,
+    },
+    Basic {
+        username: String,
+        password: String,
+    },
+    ApiKey {
+        key: String,
+        value: String,
+        location: String,
+    },
+    Other(String),
+}
+
+/// 变量替换器：记录未解析的变量，供 ParseResult.warnings 使用。
+struct Resolver {
+    variables: HashMap<String, String>,
+    warnings: Vec<String>,
+}
+
+impl Resolver {
+    fn new(variables: HashMap<String, String>) -> Self {
+        Resolver {
+            variables,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn resolve(&mut self, text: &str) -> String {
+        let text = RE_NUNJUCKS_PREFIX.replace_all(text, "{{$1");
+        for caps in RE_TEMPLATE_TAG.captures_iter(&text) {
+            let warning = format!("Template tag not evaluated: {{% {} %}}", &caps[1]);
+            if !self.warnings.contains(&warning) {
+                self.warnings.push(warning);
+            }
+        }
+        parse_utils::substitute_variables(&text, &self.variables, &mut self.warnings)
+    }
+}
+
+/// 解析 Postman Collection v2.1（兼容 v2.0）导出文件。
+/// 递归遍历文件夹，使用集合变量与可选的环境文件替换 `{{name}}`，并把 bearer / basic / apikey 认证转换为 header。
+pub fn parse_postman(input: &str, environment: Option<&str>) -> Vec<CollectionRequest> {
+    let Ok(root) = serde_json::from_str::<Value>(input.trim()) else {
+        return Vec::new();
+    };
+
+    // 优先级：集合变量 < 环境变量
+    let mut variables: HashMap<String, String> = key_values(root.get("variable"), "key")
+        .into_iter()
+        .collect();
+    if let Some(environment) = environment {
+        variables.extend(environment_variables(environment));
+    }
+
+    let mut requests = Vec::new();
+    walk_postman_items(&root, &[], root.get("auth"), &variables, &mut requests);
+    requests
+}
+
+/// 遍历 `item` 数组：含 `item` 的为文件夹，含 `request` 的为请求。文件夹的 auth 由子项继承。
+fn walk_postman_items(
+    node: &Value,
+    path: &[String],
+    inherited_auth: Option<&Value>,
+    variables: &HashMap<String, String>,
+    requests: &mut Vec<CollectionRequest>,
+) {
+    let Some(items) = node.get("item").and_then(Value::as_array) else {
+        return;
+    };
+    for item in items {
+        let name = str_field(item, "name").unwrap_or_default();
+        let auth = item.get("auth").filter(|a| !a.is_null()).or(inherited_auth);
+        if item.get("item").is_some() {
+            let mut path = path.to_vec();
+            path.push(name);
+            walk_postman_items(item, &path, auth, variables, requests);
+        } else if let Some(request) = item.get("request") {
+            let mut resolver = Resolver::new(variables.clone());
+            requests.push(postman_request(
+                &path.join("/"),
+                &name,
+                item,
+                request,
+                auth,
+                &mut resolver,
+            ));
+        }
+    }
+}
+
+/// 将 Postman 的 `request` 对象（或仅为 URL 的字符串）转换为 CollectionRequest。
+fn postman_request(
+    folder: &str,
+    name: &str,
+    item: &Value,
+    request: &Value,
+    inherited_auth: Option<&Value>,
+    resolver: &mut Resolver,
+) -> CollectionRequest {
+    let mut metadata: Vec<ParseNode> = Vec::new();
+
+    if let Some(url) = request.as_str() {
+        let url = resolver.resolve(url);
+        let spec = RequestSpec {
+            method: "GET".to_string(),
+            url,
+            headers: Vec::new(),
+            body: None,
+            content_type: None,
+        };
+        return build_request(folder, name, spec, Auth::None, metadata, resolver, item);
+    }
+
+    let method = str_field(request, "method")
+        .unwrap_or_else(|| "GET".to_string())
+        .to_uppercase();
+    let url = postman_url(request.get("url"), resolver);
+    let headers = key_values(request.get("header"), "key")
+        .into_iter()
+        .map(|(k, v)| (resolver.resolve(&k), resolver.resolve(&v)))
+        .collect();
+
+    let mut body = None;
+    let mut content_type = None;
+    if let Some(spec) = request.get("body").filter(|b| !bool_field(b, "disabled")) {
+        (body, content_type) = postman_body(spec, resolver, &mut metadata);
+    }
+
+    let auth = request
+        .get("auth")
+        .filter(|a| !a.is_null())
+        .or(inherited_auth)
+        .map(|a| postman_auth(a, resolver))
+        .unwrap_or(Auth::None);
+
+    let spec = RequestSpec {
+        method,
+        url,
+        headers,
+        body,
+        content_type,
+    };
+    build_request(folder, name, spec, auth, metadata, resolver, item)
+}
+
+/// Postman URL：优先使用 `raw`，否则由 protocol / host / path / query 拼接；`:id` 形式的路径变量按 `url.variable` 替换。
+fn postman_url(url: Option<&Value>, resolver: &mut Resolver) -> String {
+    let Some(url) = url else {
+        return String::new();
+    };
+    if let Some(raw) = url.as_str() {
+        return resolver.resolve(raw);
+    }
+
+    let raw = str_field(url, "raw").unwrap_or_else(|| {
+        let join = |key: &str, sep: &str| match url.get(key) {
+            Some(Value::Array(parts)) => parts
+                .iter()
+                .filter_map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(sep),
+            Some(Value::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+        let mut raw = join("host", ".");
+        if let Some(protocol) = str_field(url, "protocol") {
+            raw = format!("{protocol}://{raw}");
+        }
+        if let Some(port) = str_field(url, "port") {
+            raw.push_str(&format!(":{port}"));
+        }
+        let path = join("path", "/");
+        if !path.is_empty() {
+            raw.push('/');
+            raw.push_str(&path);
+        }
+        let query: Vec<String> = key_values(url.get("query"), "key")
+            .into_iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect();
+        if !query.is_empty() {
+            raw.push('?');
+            raw.push_str(&query.join("&"));
+        }
+        raw
+    });
+
+    let path_variables = key_values(url.get("variable"), "key");
+    let raw = if path_variables.is_empty() {
+        raw
+    } else {
+        let (path, rest) = match raw.find(['?', '#']) {
+            Some(pos) => raw.split_at(pos),
+            None => (raw.as_str(), ""),
+        };
+        let path: Vec<String> = path
+            .split('/')
+            .map(|segment| {
+                segment
+                    .strip_prefix(':')
+                    .and_then(|name| path_variables.iter().find(|(k, _)| k == name))
+                    .map(|(_, v)| v.clone())
+                    .unwrap_or_else(|| segment.to_string())
+            })
+            .collect();
+        format!("{}{rest}", path.join("/"))
+    };
+    resolver.resolve(&raw)
+}
+
+/// Postman body：raw / urlencoded / formdata / graphql / file 五种模式，返回（body, 隐含的 Content-Type）。
+fn postman_body(
+    body: &Value,
+    resolver: &mut Resolver,
+    metadata: &mut Vec<ParseNode>,
+) -> (Option<String>, Option<String>) {
+    match str_field(body, "mode").as_deref() {
+        Some("raw") => {
+            let text = resolver.resolve(&str_field(body, "raw").unwrap_or_default());
+            let language = body
+                .pointer("/options/raw/language")
+                .and_then(Value::as_str)
+                .unwrap_or("text");
+            let content_type = match language {
+                "json" => "application/json",
+                "xml" => "application/xml",
+                "html" => "text/html",
+                "javascript" => "application/javascript",
+                _ => "text/plain",
+            };
+            (Some(text), Some(content_type.to_string()))
+        }
+        Some("urlencoded") => {
+            let pairs: Vec<(String, String)> = key_values(body.get("urlencoded"), "key")
+                .into_iter()
+                .map(|(k, v)| (resolver.resolve(&k), resolver.resolve(&v)))
+                .collect();
+            (
+                Some(form_encode(&pairs)),
+                Some("application/x-www-form-urlencoded".to_string()),
+            )
+        }
+        Some("formdata") => {
+            let parts: Vec<FormPart> = enabled_items(body.get("formdata"))
+                .filter_map(|field| {
+                    let name = resolver.resolve(&str_field(field, "key")?);
+                    let declared = str_field(field, "contentType").filter(|t| !t.is_empty());
+                    if str_field(field, "type").as_deref() == Some("file") {
+                        let src = match field.get("src") {
+                            Some(Value::Array(files)) => files.first().and_then(Value::as_str),
+                            Some(other) => other.as_str(),
+                            None => None,
+                        }
+                        .unwrap_or_default();
+                        let filename = file_name(src);
+                        Some(FormPart {
+                            name,
+                            value: String::new(),
+                            content_type: Some(declared.unwrap_or_else(|| {
+                                multipart::guess_content_type(&filename).to_string()
+                            })),
+                            filename: Some(filename),
+                        })
+                    } else {
+                        Some(FormPart {
+                            name,
+                            value: resolver.resolve(&value_text(field.get("value"))),
+                            filename: None,
+                            content_type: declared,
+                        })
+                    }
+                })
+                .collect();
+            multipart_body(&parts)
+        }
+        Some("graphql") => {
+            let graphql = body.get("graphql");
+            let query = graphql
+                .and_then(|g| str_field(g, "query"))
+                .unwrap_or_default();
+            let variables = graphql
+                .and_then(|g| str_field(g, "variables"))
+                .map(|v| resolver.resolve(&v))
+                .and_then(|v| serde_json::from_str::<Value>(&v).ok())
+                .unwrap_or_else(|| Value::Object(Default::default()));
+            let payload =
+                serde_json::json!({ "query": resolver.resolve(&query), "variables": variables });
+            (
+                Some(payload.to_string()),
+                Some("application/json".to_string()),
+            )
+        }
+        Some("file") => {
+            // 文件内容无法读取，只记录路径
+            if let Some(src) = body.pointer("/file/src").and_then(Value::as_str) {
+                metadata.push(metadata_node("upload-file", src));
+            }
+            (None, None)
+        }
+        _ => (None, None),
+    }
+}
+
+/// Postman auth：`{type, bearer: [{key, value}]}`（v2.1）或 `{type, bearer: {token}}`（v2.0）。
+fn postman_auth(auth: &Value, resolver: &mut Resolver) -> Auth {
+    let kind = str_field(auth, "type").unwrap_or_default();
+    let mut param = |key: &str| {
+        let value = match auth.get(&kind) {
+            Some(Value::Array(params)) => params
+                .iter()
+                .find(|p| p.get("key").and_then(Value::as_str) == Some(key))
+                .map(|p| value_text(p.get("value"))),
+            Some(Value::Object(params)) => params.get(key).map(|v| value_text(Some(v))),
+            _ => None,
+        };
+        value.map(|v| resolver.resolve(&v))
+    };
+    match kind.as_str() {
+        "noauth" | "" => Auth::None,
+        "bearer" => Auth::Bearer {
+            prefix: "Bearer".to_string(),
+            token: param("token").unwrap_or_default(),
+        },
+        "basic" => Auth::Basic {
+            username: param("username").unwrap_or_default(),
+            password: param("password").unwrap_or_default(),
+        },
+        "apikey" => Auth::ApiKey {
+            key: param("key").unwrap_or_default(),
+            value: param("value").unwrap_or_default(),
+            location: param("in").unwrap_or_else(|| "header".to_string()),
+        },
+        // OAuth 2.0 已获取的 token 以 Bearer 形式发送
+        "oauth2" => match param("accessToken") {
+            Some(token) => Auth::Bearer {
+                prefix: param("headerPrefix")
+                    .filter(|p| !p.is_empty())
+                    .unwrap_or_else(|| "Bearer".to_string()),
+                token,
+            },
+            None => Auth::Other(kind),
+        },
+        _ => Auth::Other(kind),
+    }
+}
+
+/// 解析 Insomnia v4 导出文件（`_type: "export"`）。
+/// 按 `parentId` 还原文件夹路径，依次叠加基础环境、子环境、外部环境文件与文件夹环境来替换变量；
+/// 请求未设置认证时继承所在文件夹的认证。
+pub fn parse_insomnia(input: &str, environment: Option<&str>) -> Vec<CollectionRequest> {
+    let Ok(root) = serde_json::from_str::<Value>(input.trim()) else {
+        return Vec::new();
+    };
+    let Some(resources) = root.get("resources").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    let by_id: HashMap<&str, &Value> = resources
+        .iter()
+        .filter_map(|r| Some((r.get("_id")?.as_str()?, r)))
+        .collect();
+    let resource_type = |r: &Value| str_field(r, "_type").unwrap_or_default();
+    let parent_type = |r: &Value| {
+        r.get("parentId")
+            .and_then(Value::as_str)
+            .and_then(|id| by_id.get(id))
+            .map(|p| resource_type(p))
+    };
+
+    // 基础环境挂在 workspace 下，子环境挂在基础环境下（导出文件不记录当前选中的环境，取第一个）
+    let base_environment = resources.iter().find(|r| {
+        resource_type(r) == "environment" && parent_type(r).as_deref() != Some("environment")
+    });
+    let sub_environment = base_environment.and_then(|base| {
+        resources
+            .iter()
+            .find(|r| resource_type(r) == "environment" && r.get("parentId") == base.get("_id"))
+    });
+    let mut base_variables = HashMap::new();
+    for env in [base_environment, sub_environment].into_iter().flatten() {
+        if let Some(data) = env.get("data") {
+            flatten_json("", data, &mut base_variables);
+        }
+    }
+    if let Some(environment) = environment {
+        base_variables.extend(environment_variables(environment));
+    }
+
+    resources
+        .iter()
+        .filter(|r| resource_type(r) == "request")
+        .map(|request| {
+            // 自内向外收集所在的文件夹
+            let mut groups: Vec<&Value> = Vec::new();
+            let mut parent = request.get("parentId").and_then(Value::as_str);
+            while let Some(group) = parent
+                .and_then(|id| by_id.get(id))
+                .filter(|g| resource_type(g) == "request_group")
+            {
+                groups.push(group);
+                parent = group.get("parentId").and_then(Value::as_str);
+            }
+            groups.reverse();
+
+            let mut variables = base_variables.clone();
+            for group in &groups {
+                if let Some(data) = group.get("environment") {
+                    flatten_json("", data, &mut variables);
+                }
+            }
+            let folder: Vec<String> = groups
+                .iter()
+                .map(|g| str_field(g, "name").unwrap_or_default())
+                .collect();
+            let auth = std::iter::once(request)
+                .chain(groups.iter().rev().copied())
+                .filter_map(|r| r.get("authentication"))
+                .find(|a| a.as_object().is_some_and(|o| !o.is_empty()));
+
+            let mut resolver = Resolver::new(variables);
+            insomnia_request(&folder.join("/"), request, auth, &mut resolver)
+        })
+        .collect()
+}
+
+/// 将 Insomnia 的 `request` 资源转换为 CollectionRequest。
+fn insomnia_request(
+    folder: &str,
+    request: &Value,
+    auth: Option<&Value>,
+    resolver: &mut Resolver,
+) -> CollectionRequest {
+    let name = str_field(request, "name").unwrap_or_default();
+    let method = str_field(request, "method")
+        .unwrap_or_else(|| "GET".to_string())
+        .to_uppercase();
+
+    let mut url = resolver.resolve(&str_field(request, "url").unwrap_or_default());
+    let parameters: Vec<(String, String)> = key_values(request.get("parameters"), "name")
+        .into_iter()
+        .map(|(k, v)| (resolver.resolve(&k), resolver.resolve(&v)))
+        .collect();
+    if !parameters.is_empty() {
+        append_query(&mut url, &form_encode(&parameters));
+    }
+
+    let headers = key_values(request.get("headers"), "name")
+        .into_iter()
+        .map(|(k, v)| (resolver.resolve(&k), resolver.resolve(&v)))
+        .collect();
+
+    let (body, content_type) = match request.get("body") {
+        Some(body) => insomnia_body(body, resolver),
+        None => (None, None),
+    };
+
+    let auth = auth
+        .map(|a| insomnia_auth(a, resolver))
+        .unwrap_or(Auth::None);
+    let spec = RequestSpec {
+        method,
+        url,
+        headers,
+        body,
+        content_type,
+    };
+    build_request(folder, &name, spec, auth, Vec::new(), resolver, request)
+}
+
+/// Insomnia body：表单与 multipart 使用 `params`，其余使用 `text`；GraphQL 的 text 已是 JSON。
+fn insomnia_body(body: &Value, resolver: &mut Resolver) -> (Option<String>, Option<String>) {
+    let mime_type = str_field(body, "mimeType").filter(|m| !m.is_empty());
+    match mime_type.as_deref() {
+        Some("application/x-www-form-urlencoded") => {
+            let pairs: Vec<(String, String)> = key_values(body.get("params"), "name")
+                .into_iter()
+                .map(|(k, v)| (resolver.resolve(&k), resolver.resolve(&v)))
+                .collect();
+            (Some(form_encode(&pairs)), mime_type)
+        }
+        Some("multipart/form-data") => {
+            let parts: Vec<FormPart> = enabled_items(body.get("params"))
+                .filter_map(|field| {
+                    let name = resolver.resolve(&str_field(field, "name")?);
+                    if str_field(field, "type").as_deref() == Some("file") {
+                        let filename = file_name(&str_field(field, "fileName").unwrap_or_default());
+                        Some(FormPart {
+                            name,
+                            value: String::new(),
+                            content_type: Some(
+                                multipart::guess_content_type(&filename).to_string(),
+                            ),
+                            filename: Some(filename),
+                        })
+                    } else {
+                        Some(FormPart {
+                            name,
+                            value: resolver.resolve(&value_text(field.get("value"))),
+                            filename: None,
+                            content_type: None,
+                        })
+                    }
+                })
+                .collect();
+            multipart_body(&parts)
+        }
+        Some("application/graphql") => (
+            str_field(body, "text").map(|t| resolver.resolve(&t)),
+            Some("application/json".to_string()),
+        ),
+        _ => (
+            str_field(body, "text").map(|t| resolver.resolve(&t)),
+            mime_type,
+        ),
+    }
+}
+
+/// Insomnia authentication：`{type, token, prefix}`、`{type, username, password}`、`{type, key, value, addTo}`。
+fn insomnia_auth(auth: &Value, resolver: &mut Resolver) -> Auth {
+    if bool_field(auth, "disabled") {
+        return Auth::None;
+    }
+    let mut field = |key: &str| resolver.resolve(&value_text(auth.get(key)));
+    let kind = str_field(auth, "type").unwrap_or_default();
+    match kind.as_str() {
+        "none" | "" => Auth::None,
+        "bearer" => Auth::Bearer {
+            prefix: Some(field("prefix"))
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| "Bearer".to_string()),
+            token: field("token"),
+        },
+        "basic" => Auth::Basic {
+            username: field("username"),
+            password: field("password"),
+        },
+        "apikey" => Auth::ApiKey {
+            key: field("key"),
+            value: field("value"),
+            location: Some(field("addTo"))
+                .filter(|a| !a.is_empty())
+                .unwrap_or_else(|| "header".to_string()),
+        },
+        _ => Auth::Other(kind),
+    }
+}
+
+/// 两种集合格式统一后的请求描述
+struct RequestSpec {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    /// body 模式隐含的 Content-Type，仅在没有显式 header 时补充
+    content_type: Option<String>,
+}
+
+/// 应用认证、补充 Content-Type，生成最终的 ParseResult。
+fn build_request(
+    folder: &str,
+    name: &str,
+    spec: RequestSpec,
+    auth: Auth,
+    mut metadata: Vec<ParseNode>,
+    resolver: &mut Resolver,
+    source: &Value,
+) -> CollectionRequest {
+    let RequestSpec {
+        method,
+        mut url,
+        headers,
+        body,
+        content_type,
+    } = spec;
+    let mut headers: Vec<ParseNode> = headers
+        .iter()
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| header_node(key, value))
+        .collect();
+    let has_header = |headers: &[ParseNode], name: &str| {
+        headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+    };
+
+    match auth {
+        Auth::None => {}
+        Auth::Bearer { prefix, token } => {
+            if !has_header(&headers, "authorization") {
+                headers.push(header_node("Authorization", &format!("{prefix} {token}")));
+            }
+        }
+        Auth::Basic { username, password } => {
+            if !has_header(&headers, "authorization") {
+                let encoded = general_purpose::STANDARD.encode(format!("{username}:{password}"));
+                headers.push(header_node("Authorization", &format!("Basic {encoded}")));
+            }
+        }
+        Auth::ApiKey {
+            key,
+            value,
+            location,
+        } => match location.as_str() {
+            "query" | "queryParams" => {
+                append_query(&mut url, &form_encode(&[(key, value)]));
+            }
+            "cookie" => match headers
+                .iter_mut()
+                .find(|h| h.key.eq_ignore_ascii_case("cookie"))
+            {
+                Some(cookie) => {
+                    *cookie = header_node(&cookie.key, &format!("{}; {key}={value}", cookie.value));
+                }
+                None => headers.push(header_node("Cookie", &format!("{key}={value}"))),
+            },
+            _ => {
+                if !key.is_empty() && !has_header(&headers, &key) {
+                    headers.push(header_node(&key, &value));
+                }
+            }
+        },
+        // digest / oauth1 / aws 等需要签名的认证无法静态生成，只记录类型
+        Auth::Other(kind) => metadata.push(metadata_node("auth", &kind)),
+    }
+
+    if let Some(content_type) = content_type.filter(|_| body.is_some()) {
+        if !has_header(&headers, "content-type") {
+            headers.push(header_node("Content-Type", &content_type));
+        }
+    }
+
+    metadata.insert(0, metadata_node("name", name));
+    if !folder.is_empty() {
+        metadata.insert(1, metadata_node("folder", folder));
+    }
+
+    let query_params = parse_utils::parse_query_params(&url);
+    let query_tree = query_params
+        .as_deref()
+        .and_then(query_tree::build_query_tree);
+    let url_nodes = parse_utils::parse_url_nodes(&url);
+    let warnings = std::mem::take(&mut resolver.warnings);
+
+    CollectionRequest {
+        folder: folder.to_string(),
+        name: name.to_string(),
+        request: ParseResult {
+            content_type: HttpContentType::Request,
+            method: Some(method),
+            url: Some(url),
+            url_nodes,
+            headers,
+            query_params,
+            query_tree,
+            body,
+            metadata: Some(metadata),
+            warnings: if warnings.is_empty() {
+                None
+            } else {
+                Some(warnings)
+            },
+            raw_text: serde_json::to_string_pretty(source).unwrap_or_default(),
+            ..Default::default()
+        },
+    }
+}
+
+/// 读取外部环境文件：Postman 环境（`values: [{key, value, enabled}]`）、
+/// Insomnia 环境（`data: {...}`）或普通的 JSON 对象，嵌套对象展开为 `a.b` 形式的 key。
+fn environment_variables(input: &str) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+    let Ok(root) = serde_json::from_str::<Value>(input.trim()) else {
+        return variables;
+    };
+    if let Some(values) = root.get("values").filter(|v| v.is_array()) {
+        variables.extend(
+            values
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|v| v.get("enabled").and_then(Value::as_bool) != Some(false))
+                .filter_map(|v| Some((str_field(v, "key")?, value_text(v.get("value"))))),
+        );
+    } else if let Some(data) = root.get("data").filter(|d| d.is_object()) {
+        flatten_json("", data, &mut variables);
+    } else {
+        flatten_json("", &root, &mut variables);
+    }
+    variables
+}
+
+/// 将 JSON 对象展开为扁平的变量表（`{"a": {"b": 1}}` → `a.b = 1`），同时保留对象本身的 JSON 文本。
+fn flatten_json(prefix: &str, value: &Value, variables: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            if !prefix.is_empty() {
+                variables.insert(prefix.to_string(), value.to_string());
+            }
+            for (key, child) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_json(&key, child, variables);
+            }
+        }
+        other => {
+            variables.insert(prefix.to_string(), value_text(Some(other)));
+        }
+    }
+}
+
+/// 读取 `[{key, value, disabled}]` 形式的列表，跳过被禁用的项。
+fn key_values(list: Option<&Value>, key_field: &str) -> Vec<(String, String)> {
+    enabled_items(list)
+        .filter_map(|item| Some((str_field(item, key_field)?, value_text(item.get("value")))))
+        .collect()
+}
+
+fn enabled_items(list: Option<&Value>) -> impl Iterator<Item = &Value> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|item| !bool_field(item, "disabled"))
+}
+
+fn form_encode(pairs: &[(String, String)]) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish()
+}
+
+fn multipart_body(parts: &[FormPart]) -> (Option<String>, Option<String>) {
+    (
+        Some(multipart::build_multipart_body(parts, FORM_BOUNDARY)),
+        Some(format!("multipart/form-data; boundary={FORM_BOUNDARY}")),
+    )
+}
+
+fn append_query(url: &mut String, query: &str) {
+    if query.is_empty() {
+        return;
+    }
+    let insert_at = url.find('#').unwrap_or(url.len());
+    let separator = if url[..insert_at].contains('?') {
+        "&"
+    } else {
+        "?"
+    };
+    url.insert_str(insert_at, &format!("{separator}{query}"));
+}
+
+/// 取文件路径中的文件名部分（兼容 Windows 路径）。
+fn file_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+}
+
+/// 标量值转为文本：字符串原样返回，数字 / 布尔转为字面量，null 与缺省为空字符串。
+fn value_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn bool_field(obj: &Value, key: &str) -> bool {
+    obj.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSTMAN: &str = r#"{
+  "info": {
+    "_postman_id": "0f6c1b9e",
+    "name": "Shop API",
+    "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+  },
+  "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}", "type": "string" }] },
+  "variable": [
+    { "key": "baseUrl", "value": "https://api.example.com" },
+    { "key": "token", "value": "collection-token" }
+  ],
+  "item": [
+    {
+      "name": "Users",
+      "item": [
+        {
+          "name": "Admin",
+          "auth": { "type": "basic", "basic": [
+            { "key": "username", "value": "root" },
+            { "key": "password", "value": "{{adminPass}}" }
+          ] },
+          "item": [
+            {
+              "name": "Get user",
+              "request": {
+                "method": "GET",
+                "header": [
+                  { "key": "Accept", "value": "application/json" },
+                  { "key": "X-Debug", "value": "1", "disabled": true }
+                ],
+                "url": {
+                  "raw": "{{baseUrl}}/users/:id?expand=roles",
+                  "host": ["{{baseUrl}}"],
+                  "path": ["users", ":id"],
+                  "variable": [{ "key": "id", "value": "42" }]
+                }
+              }
+            }
+          ]
+        },
+        {
+          "name": "Create user",
+          "request": {
+            "method": "POST",
+            "header": [],
+            "body": {
+              "mode": "raw",
+              "raw": "{\"name\": \"{{name}}\"}",
+              "options": { "raw": { "language": "json" } }
+            },
+            "url": "{{baseUrl}}/users"
+          }
+        }
+      ]
+    },
+    {
+      "name": "Login",
+      "request": {
+        "auth": { "type": "apikey", "apikey": [
+          { "key": "key", "value": "api_key" },
+          { "key": "value", "value": "k-123" },
+          { "key": "in", "value": "query" }
+        ] },
+        "method": "post",
+        "body": {
+          "mode": "urlencoded",
+          "urlencoded": [
+            { "key": "user", "value": "alice" },
+            { "key": "pass", "value": "a b&c" }
+          ]
+        },
+        "url": { "raw": "{{baseUrl}}/login" }
+      }
+    },
+    {
+      "name": "Public",
+      "request": {
+        "auth": { "type": "noauth" },
+        "method": "GET",
+        "url": "{{baseUrl}}/health"
+      }
+    }
+  ]
+}"#;
+
+    const INSOMNIA: &str = r#"{
+  "_type": "export",
+  "__export_format": 4,
+  "resources": [
+    { "_id": "wrk_1", "_type": "workspace", "parentId": null, "name": "Shop" },
+    { "_id": "env_base", "_type": "environment", "parentId": "wrk_1", "name": "Base Environment",
+      "data": { "base_url": "https://api.example.com", "auth": { "token": "t-1" } } },
+    { "_id": "env_dev", "_type": "environment", "parentId": "env_base", "name": "Dev",
+      "data": { "base_url": "https://dev.example.com" } },
+    { "_id": "fld_1", "_type": "request_group", "parentId": "wrk_1", "name": "Orders",
+      "environment": { "version": "v2" },
+      "authentication": { "type": "bearer", "token": "{{ _.auth.token }}" } },
+    { "_id": "req_1", "_type": "request", "parentId": "fld_1", "name": "List orders",
+      "method": "GET", "url": "{{ _.base_url }}/{{ _.version }}/orders",
+      "parameters": [
+        { "name": "page", "value": "2" },
+        { "name": "debug", "value": "1", "disabled": true }
+      ],
+      "headers": [{ "name": "X-Request-Id", "value": "{% uuid 'v4' %}" }],
+      "body": {}, "authentication": {} },
+    { "_id": "req_2", "_type": "request", "parentId": "wrk_1", "name": "Upload",
+      "method": "POST", "url": "{{ _.base_url }}/upload",
+      "body": { "mimeType": "multipart/form-data", "params": [
+        { "name": "title", "value": "cat" },
+        { "name": "file", "type": "file", "fileName": "/home/me/cat.png" }
+      ] },
+      "headers": [],
+      "authentication": { "type": "apikey", "key": "X-Api-Key", "value": "{{ _.missing }}", "addTo": "header" } }
+  ]
+}"#;
+
+    fn header<'a>(result: &'a ParseResult, name: &str) -> Option<&'a str> {
+        parse_utils::header_value(&result.headers, name)
+    }
+
+    #[test]
+    fn test_postman_folders_and_inherited_auth() {
+        let requests = parse_postman(POSTMAN, None);
+        assert_eq!(requests.len(), 4);
+
+        let get_user = &requests[0];
+        assert_eq!(get_user.folder, "Users/Admin");
+        assert_eq!(get_user.name, "Get user");
+        let request = &get_user.request;
+        assert_eq!(
+            request.url.as_deref(),
+            Some("https://api.example.com/users/42?expand=roles")
+        );
+        assert_eq!(request.headers.len(), 2);
+        // 文件夹的 basic auth 覆盖集合的 bearer auth，密码变量未定义
+        assert_eq!(
+            header(request, "Authorization"),
+            Some(
+                format!(
+                    "Basic {}",
+                    general_purpose::STANDARD.encode("root:{{adminPass}}")
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(
+            request.warnings.as_deref().unwrap(),
+            ["Unresolved variable: {{adminPass}}".to_string()]
+        );
+
+        let create = &requests[1].request;
+        assert_eq!(requests[1].folder, "Users");
+        assert_eq!(
+            header(create, "Authorization"),
+            Some("Bearer collection-token")
+        );
+        assert_eq!(header(create, "Content-Type"), Some("application/json"));
+        assert_eq!(create.body.as_deref(), Some(r#"{"name": "{{name}}"}"#));
+    }
+
+    #[test]
+    fn test_postman_apikey_query_and_form_body() {
+        let requests = parse_postman(POSTMAN, None);
+        let login = &requests[2];
+        assert_eq!(login.folder, "");
+        assert_eq!(login.request.method.as_deref(), Some("POST"));
+        assert_eq!(
+            login.request.url.as_deref(),
+            Some("https://api.example.com/login?api_key=k-123")
+        );
+        assert_eq!(
+            login.request.body.as_deref(),
+            Some("user=alice&pass=a+b%26c")
+        );
+        assert_eq!(
+            header(&login.request, "Content-Type"),
+            Some("application/x-www-form-urlencoded")
+        );
+
+        let public = &requests[3].request;
+        assert!(header(public, "Authorization").is_none());
+        assert_eq!(public.metadata.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_postman_environment_overrides_collection() {
+        let environment = r#"{"name": "prod", "values": [
+            {"key": "baseUrl", "value": "https://prod.example.com", "enabled": true},
+            {"key": "adminPass", "value": "pw", "enabled": true},
+            {"key": "token", "value": "ignored", "enabled": false}
+        ]}"#;
+        let requests = parse_postman(POSTMAN, Some(environment));
+        let request = &requests[0].request;
+        assert_eq!(
+            request.url.as_deref(),
+            Some("https://prod.example.com/users/42?expand=roles")
+        );
+        assert!(request.warnings.is_none());
+        assert_eq!(
+            header(&requests[1].request, "Authorization"),
+            Some("Bearer collection-token")
+        );
+    }
+
+    #[test]
+    fn test_insomnia_environments_and_folder_auth() {
+        let requests = parse_insomnia(INSOMNIA, None);
+        assert_eq!(requests.len(), 2);
+
+        let list = &requests[0];
+        assert_eq!(list.folder, "Orders");
+        assert_eq!(
+            list.request.url.as_deref(),
+            Some("https://dev.example.com/v2/orders?page=2")
+        );
+        assert_eq!(header(&list.request, "Authorization"), Some("Bearer t-1"));
+        assert_eq!(
+            list.request.warnings.as_deref().unwrap(),
+            ["Template tag not evaluated: {% uuid 'v4' %}".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_insomnia_multipart_and_apikey() {
+        let requests = parse_insomnia(INSOMNIA, None);
+        let upload = &requests[1];
+        assert_eq!(upload.folder, "");
+        let request = &upload.request;
+        assert_eq!(header(request, "X-Api-Key"), Some("{{ missing }}"));
+        assert!(header(request, "Content-Type")
+            .unwrap()
+            .starts_with("multipart/form-data; boundary="));
+        let body = request.body.as_deref().unwrap();
+        assert!(body.contains("name=\"title\"\r\n\r\ncat\r\n"));
+        assert!(body.contains("filename=\"cat.png\"\r\nContent-Type: image/png"));
+        assert_eq!(
+            request.warnings.as_deref().unwrap(),
+            ["Unresolved variable: {{missing}}".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(parse_postman("not json", None).is_empty());
+        assert!(parse_insomnia("{}", None).is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+/// 候选的键值对分隔符，按优先顺序排列
+const SEPARATORS: [char; 4] = ['&', ';', ',', '|'];
+
+/// 候选的赋值符号
+const ASSIGNMENTS: [char; 2] = ['=', ':'];
+
+/// 未加引号的 key 中允许的符号（字母、数字之外）
+const KEY_SYMBOLS: &str = "_-.[]%+$@~*!";
+
+const MAX_KEY_LEN: usize = 128;
+
+/// 推断出的复合值：分隔符、赋值符号与按顺序排列的键值对。
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompoundValue {
+    pub separator: char,
+    pub assignment: char,
+    pub pairs: Vec<(String, String)>,
+}
+
+/// 推断复合值的分隔语法：在 `&` `;` `,` `|` 与 `=` `:` 的组合中选出能把整个值切成
+/// 至少两个合法键值对的一种（键值对最多者优先，相同时按上面的顺序）。
+/// 值可以用单引号或双引号包裹以包含分隔符；key 须为标识符样式，
+/// 因此普通文本、URL 与 `Content-Type` 一类带参数的 header 值不会被识别。
+pub fn parse_compound(value: &str) -> Option<CompoundValue> {
+    let value = value.trim();
+    if value.contains(['\n', '\r']) {
+        return None;
+    }
+
+    let mut best: Option<CompoundValue> = None;
+    for separator in SEPARATORS {
+        if !value.contains(separator) {
+            continue;
+        }
+        for assignment in ASSIGNMENTS {
+            let Some(pairs) = split_pairs(value, separator, assignment) else {
+                continue;
+            };
+            if best.as_ref().is_none_or(|b| pairs.len() > b.pairs.len()) {
+                best = Some(CompoundValue {
+                    separator,
+                    assignment,
+                    pairs,
+                });
+            }
+        }
+    }
+    best
+}
+
+/// 按给定语法切分；任一片段不是合法键值对时返回 None。
+fn split_pairs(value: &str, separator: char, assignment: char) -> Option<Vec<(String, String)>> {
+    // `k=v&k=v` 是最常见的形式，沿用原有的宽松规则：值可以包含空格
+    let form_like = separator == '&' && assignment == '=';
+
+    let mut parts = split_unquoted(value, separator)?;
+    // 允许末尾多一个分隔符（`a=1;b=2;`）
+    if parts.last().is_some_and(|p| p.trim().is_empty()) {
+        parts.pop();
+    }
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let mut pairs = Vec::with_capacity(parts.len());
+    for part in parts {
+        // 只按第一个赋值符号切分，值中再出现的属于值本身（如 base64 的 `=` 填充、时间中的 `:`）
+        let halves = split_unquoted(part, assignment)?;
+        if halves.len() < 2 {
+            return None;
+        }
+        let key = halves[0].trim();
+        let raw_value = part[halves[0].len() + assignment.len_utf8()..].trim();
+
+        let key = match unquote(key) {
+            Some(key) if !key.is_empty() => key,
+            Some(_) => return None,
+            None if is_key(key) => key.to_string(),
+            None => return None,
+        };
+        let value = match unquote(raw_value) {
+            Some(value) => value,
+            None if raw_value.starts_with(['"', '\'']) => return None,
+            None if !form_like && raw_value.contains(char::is_whitespace) => return None,
+            None => raw_value.to_string(),
+        };
+        pairs.push((key, value));
+    }
+
+    // 纯数字的 key（如 `10:00,11:30`）多半是时间或数值列表
+    if !form_like
+        && !pairs
+            .iter()
+            .any(|(k, _)| k.chars().any(char::is_alphabetic))
+    {
+        return None;
+    }
+    Some(pairs)
+}
+
+/// 在引号之外按 `delimiter` 切分。只有出现在片段开头（或赋值符号之后）的引号才开启引用，
+/// 因此 `O'Brien` 中的撇号不影响切分；引号未闭合时返回 None。
+fn split_unquoted(text: &str, delimiter: char) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+    let mut at_token_start = true;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        if c == delimiter {
+            parts.push(&text[start..i]);
+            start = i + c.len_utf8();
+            at_token_start = true;
+        } else if at_token_start && (c == '"' || c == '\'') {
+            quote = Some(c);
+            at_token_start = false;
+        } else if c == '=' || c == ':' {
+            at_token_start = true;
+        } else if !c.is_whitespace() {
+            at_token_start = false;
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    parts.push(&text[start..]);
+    Some(parts)
+}
+
+/// 去掉包裹的引号并处理反斜杠转义；未加引号时返回 None。
+fn unquote(text: &str) -> Option<String> {
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    Some(result)
+}
+
+/// 标识符样式的 key：字母、数字与少量符号，不含空白。
+fn is_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_KEY_LEN
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || KEY_SYMBOLS.contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(value: &str) -> Vec<(String, String)> {
+        parse_compound(value).unwrap().pairs
+    }
+
+    fn pair(k: &str, v: &str) -> (String, String) {
+        (k.to_string(), v.to_string())
+    }
+
+    #[test]
+    fn test_separator_and_assignment_grammars() {
+        let value = parse_compound("k1:v1;k2:v2").unwrap();
+        assert_eq!((value.separator, value.assignment), (';', ':'));
+        assert_eq!(value.pairs, [pair("k1", "v1"), pair("k2", "v2")]);
+
+        let value = parse_compound("env=prod|region=eu|id=42").unwrap();
+        assert_eq!((value.separator, value.assignment), ('|', '='));
+
+        let value = parse_compound("timeout=5, max=1000").unwrap();
+        assert_eq!(value.separator, ',');
+        assert_eq!(value.pairs[1], pair("max", "1000"));
+
+        assert_eq!(pairs("a=1;b=2;"), [pair("a", "1"), pair("b", "2")]);
+        assert_eq!(pairs("token=YWJj==&v=2")[0], pair("token", "YWJj=="));
+    }
+
+    #[test]
+    fn test_quoted_values() {
+        assert_eq!(
+            pairs(r#"name="Doe, John";note='a;b';tag="say \"hi\"""#),
+            [
+                pair("name", "Doe, John"),
+                pair("note", "a;b"),
+                pair("tag", "say \"hi\""),
+            ]
+        );
+        assert_eq!(
+            pairs(r#""user id":7,"role":admin"#)[0],
+            pair("user id", "7")
+        );
+        // 词中的撇号不是引号
+        assert_eq!(pairs("name=O'Brien&age=3")[0], pair("name", "O'Brien"));
+        assert!(parse_compound(r#"a="unterminated;b=2"#).is_none());
+    }
+
+    #[test]
+    fn test_most_pairs_wins() {
+        let value = parse_compound("a=1;b=2;c=x,y").unwrap();
+        assert_eq!(value.separator, ';');
+        assert_eq!(value.pairs[2], pair("c", "x,y"));
+    }
+
+    #[test]
+    fn test_prose_and_urls_rejected() {
+        for text in [
+            "Hello, world: this is a test",
+            "Note: see below, Todo: fix it later",
+            "https://example.com/path?a=1&b=2",
+            "text/html; charset=utf-8",
+            "max-age=31536000; includeSubDomains",
+            "en-US,en;q=0.9",
+            "10:00,11:30",
+            "key=value",
+            "a=1\nb=2",
+        ] {
+            assert!(parse_compound(text).is_none(), "{text}");
+        }
+    }
+}
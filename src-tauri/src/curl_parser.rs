@@ -1,5 +1,11 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+
 use crate::models::{HttpContentType, ParseNode, ParseResult};
-use crate::parse_utils;
+use crate::multipart::{self, FormPart, FORM_BOUNDARY};
+use crate::parse_utils::{self, header_node, metadata_node};
+use crate::query_tree;
+use crate::shell_words::{self, ShellStyle};
 
 /// 将 cURL 命令文本解析为结构化的 ParseResult。
 pub fn parse_curl(input: &str) -> ParseResult {
@@ -9,281 +15,575 @@ pub fn parse_curl(input: &str) -> ParseResult {
     // 去掉 curl 前缀
     let without_curl = strip_curl_prefix(trimmed);
 
-    // 合并反斜杠续行
-    let merged = merge_continuation_lines(without_curl);
-
-    // Shell token 化
-    let tokens = shell_tokenize(&merged);
+    // 按转义风格（POSIX shell / Windows cmd / PowerShell）token 化
+    let shell = shell_words::detect_shell_style(trimmed);
+    let tokens = shell_words::tokenize(without_curl, shell);
 
     let mut method: Option<String> = None;
     let mut url_str: Option<String> = None;
     let mut headers: Vec<ParseNode> = Vec::new();
-    let mut body: Option<String> = None;
-    let mut has_data = false;
+    let mut metadata: Vec<ParseNode> = Vec::new();
+    let mut data_parts: Vec<String> = Vec::new();
+    let mut json_parts: Vec<String> = Vec::new();
+    let mut form_parts: Vec<FormPart> = Vec::new();
+    let mut user: Option<String> = None;
+    let mut auth_scheme: Option<String> = None;
+    let mut user_agent: Option<String> = None;
+    let mut referer: Option<String> = None;
+    let mut bearer: Option<String> = None;
+    let mut head = false;
+    let mut get = false;
+    let mut upload = false;
+
+    if shell != ShellStyle::Posix {
+        metadata.push(metadata_node("shell", shell.name()));
+    }
 
-    let mut i = 0;
-    while i < tokens.len() {
-        let tok = &tokens[i];
-        match tok.as_str() {
+    for (flag, value) in normalize_options(&tokens) {
+        let value = value.unwrap_or_default();
+        match flag.as_str() {
             "-H" | "--header" => {
-                if let Some(val) = tokens.get(i + 1) {
-                    if let Some(header) = parse_header_token(val) {
-                        headers.push(header);
-                    }
-                    i += 2;
-                } else {
-                    i += 1;
+                if let Some(header) = parse_header_token(&value) {
+                    headers.push(header);
                 }
             }
             "-b" | "--cookie" => {
-                if let Some(val) = tokens.get(i + 1) {
-                    let children = parse_utils::parse_cookie_children(val);
-                    headers.push(ParseNode {
-                        key: "Cookie".to_string(),
-                        value: val.clone(),
-                        children,
-                        description: None,
-                        decoded_value: None,
-                        value_type: None,
-                    });
-                    i += 2;
+                // 不含 `=` 时是 cookie 文件路径，而不是 cookie 字符串
+                if value.contains('=') {
+                    headers.push(header_node("Cookie", &value));
                 } else {
-                    i += 1;
+                    metadata.push(metadata_node("cookie-file", &value));
                 }
             }
-            "-X" | "--request" => {
-                if let Some(val) = tokens.get(i + 1) {
-                    method = Some(val.to_uppercase());
-                    i += 2;
-                } else {
-                    i += 1;
-                }
+            "-X" | "--request" => method = Some(value.to_uppercase()),
+            "-d" | "--data" | "--data-ascii" | "--data-raw" | "--data-binary" => {
+                data_parts.push(value)
             }
-            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-urlencode" => {
-                if let Some(val) = tokens.get(i + 1) {
-                    body = Some(val.clone());
-                    has_data = true;
-                    i += 2;
-                } else {
-                    i += 1;
+            "--data-urlencode" => {
+                let (data, file) = encode_data_urlencode(&value);
+                if let Some(file) = file {
+                    metadata.push(metadata_node("data-urlencode-file", file));
                 }
+                data_parts.push(data);
             }
-            _ => {
-                // 忽略已知的无参数 flag
-                if tok == "--compressed" || tok == "--location" || tok == "-L" || tok == "-k"
-                    || tok == "--insecure" || tok == "-s" || tok == "--silent"
-                    || tok == "-S" || tok == "--show-error" || tok == "-v" || tok == "--verbose"
-                {
-                    i += 1;
-                    continue;
-                }
-                // 忽略未知的 --flag=value 或 --flag value 形式
-                if tok.starts_with("--") && tok.contains('=') {
-                    i += 1;
-                    continue;
+            "--json" => json_parts.push(value),
+            "-F" | "--form" => form_parts.push(parse_form_option(&value, false)),
+            "--form-string" => form_parts.push(parse_form_option(&value, true)),
+            "-u" | "--user" => user = Some(value),
+            "--basic" => auth_scheme = None,
+            "--digest" | "--ntlm" | "--negotiate" | "--anyauth" => {
+                auth_scheme = Some(flag.trim_start_matches('-').to_string())
+            }
+            "--oauth2-bearer" => bearer = Some(value),
+            "-A" | "--user-agent" => user_agent = Some(value),
+            "-e" | "--referer" => {
+                referer = Some(value.trim_end_matches(";auto").to_string())
+            }
+            "-I" | "--head" => head = true,
+            "-G" | "--get" => get = true,
+            "--url" => {
+                if url_str.is_none() {
+                    url_str = Some(value);
                 }
-                if tok.starts_with('-') && tok.len() > 1 && url_str.is_some() {
-                    // 未知的带参数 flag，跳过它和它的值
-                    i += 2;
-                    continue;
+            }
+            "-T" | "--upload-file" => {
+                upload = true;
+                metadata.push(metadata_node("upload-file", &value));
+            }
+            URL_ARGUMENT => {
+                if url_str.is_none() {
+                    url_str = Some(value);
                 }
-                // 第一个非 flag token 是 URL
-                if url_str.is_none() && !tok.starts_with('-') {
-                    url_str = Some(tok.clone());
+            }
+            _ => {
+                if let Some(node) = transport_metadata(&flag, &value) {
+                    metadata.push(node);
                 }
-                i += 1;
             }
         }
     }
 
-    // 默认 method
-    let method = method.or_else(|| {
-        if has_data {
-            Some("POST".to_string())
+    let has_header = |headers: &[ParseNode], name: &str| {
+        headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+    };
+
+    // -u 生成 Basic 认证头；显式的 -H Authorization 优先
+    if let Some(credentials) = &user {
+        match auth_scheme.as_deref() {
+            None if !has_header(&headers, "authorization") => {
+                let credentials = if credentials.contains(':') {
+                    credentials.clone()
+                } else {
+                    format!("{credentials}:")
+                };
+                let encoded = general_purpose::STANDARD.encode(credentials);
+                headers.push(header_node("Authorization", &format!("Basic {encoded}")));
+            }
+            None => {}
+            Some(scheme) => metadata.push(metadata_node(scheme, credentials)),
+        }
+    }
+    if let Some(token) = &bearer {
+        if !has_header(&headers, "authorization") {
+            headers.push(header_node("Authorization", &format!("Bearer {token}")));
+        }
+    }
+    if let Some(agent) = &user_agent {
+        if !has_header(&headers, "user-agent") {
+            headers.push(header_node("User-Agent", agent));
+        }
+    }
+    if let Some(referer) = &referer {
+        if !has_header(&headers, "referer") {
+            headers.push(header_node("Referer", referer));
+        }
+    }
+
+    // 组装 body：多个 -d 以 `&` 连接，--json 直接拼接，-F 生成 multipart
+    let mut body: Option<String> = None;
+    if !json_parts.is_empty() {
+        data_parts.push(json_parts.concat());
+        for name in ["Content-Type", "Accept"] {
+            if !has_header(&headers, name) {
+                headers.push(header_node(name, "application/json"));
+            }
+        }
+    }
+    let has_data = !data_parts.is_empty();
+    if has_data {
+        let data = data_parts.join("&");
+        if get {
+            // -G 把数据追加到 URL 的查询字符串
+            if let Some(url) = &mut url_str {
+                url.push(if url.contains('?') { '&' } else { '?' });
+                url.push_str(&data);
+            }
         } else {
-            Some("GET".to_string())
+            if json_parts.is_empty() && !has_header(&headers, "content-type") {
+                headers.push(header_node(
+                    "Content-Type",
+                    "application/x-www-form-urlencoded",
+                ));
+            }
+            body = Some(data);
+        }
+    } else if !form_parts.is_empty() {
+        if !has_header(&headers, "content-type") {
+            headers.push(header_node(
+                "Content-Type",
+                &format!("multipart/form-data; boundary={FORM_BOUNDARY}"),
+            ));
         }
+        let boundary = headers
+            .iter()
+            .find(|h| h.key.eq_ignore_ascii_case("content-type"))
+            .and_then(|h| multipart::boundary_from_content_type(&h.value))
+            .unwrap_or_else(|| FORM_BOUNDARY.to_string());
+        body = Some(multipart::build_multipart_body(&form_parts, &boundary));
+    }
+
+    // method 优先级：-X > -I > -G > 有 body 时 POST（-T 为 PUT）> GET
+    let method = method.or_else(|| {
+        let implied = if head {
+            "HEAD"
+        } else if get {
+            "GET"
+        } else if body.is_some() {
+            "POST"
+        } else if upload {
+            "PUT"
+        } else {
+            "GET"
+        };
+        Some(implied.to_string())
     });
 
     // 解析 URL query params
     let query_params = url_str.as_ref().and_then(|u| parse_utils::parse_query_params(u));
+    let query_tree = query_params.as_deref().and_then(query_tree::build_query_tree);
+    let url_nodes = url_str.as_ref().and_then(|u| parse_utils::parse_url_nodes(u));
 
     ParseResult {
         content_type: HttpContentType::Request,
         method,
         url: url_str,
-        status_code: None,
-        status_text: None,
-        protocol: None,
+        url_nodes,
         headers,
         query_params,
+        query_tree,
         body,
+        metadata: if metadata.is_empty() { None } else { Some(metadata) },
         raw_text,
+        ..Default::default()
     }
 }
 
-/// 去掉 `curl ` 前缀（支持 `curl` 后紧跟空格的情况）。
+/// 去掉 `curl ` / `curl.exe ` 前缀（不区分大小写，与格式检测一致）。
 fn strip_curl_prefix(s: &str) -> &str {
     let trimmed = s.trim_start();
-    trimmed
-        .strip_prefix("curl ")
-        .or_else(|| trimmed.strip_prefix("curl\t"))
-        .unwrap_or(if trimmed == "curl" { "" } else { trimmed })
-}
-
-/// 合并反斜杠续行（`\` + 换行）为单行。
-fn merge_continuation_lines(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars().peekable();
-    while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            match chars.peek() {
-                Some('\n') => {
-                    chars.next();
-                    // 跳过续行后的前导空白
-                    while let Some(&ws) = chars.peek() {
-                        if ws == ' ' || ws == '\t' {
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-                }
-                Some('\r') => {
-                    chars.next();
-                    if chars.peek() == Some(&'\n') {
-                        chars.next();
-                    }
-                    while let Some(&ws) = chars.peek() {
-                        if ws == ' ' || ws == '\t' {
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
-                }
-                _ => {
-                    result.push(ch);
-                }
+    for prefix in ["curl.exe", "curl"] {
+        let matches = trimmed
+            .get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix));
+        if matches {
+            let rest = &trimmed[prefix.len()..];
+            if rest.is_empty() {
+                return "";
+            }
+            if rest.starts_with([' ', '\t', '\r', '\n']) {
+                return rest;
             }
-        } else {
-            result.push(ch);
         }
     }
-    result
+    trimmed
 }
 
-/// Shell 风格 token 化。
-/// 按空格分割，但尊重单引号、双引号和 `$'...'` 内的内容。
-fn shell_tokenize(s: &str) -> Vec<String> {
-    let mut tokens = Vec::new();
-    let mut current = String::new();
-    let mut chars = s.chars().peekable();
-
-    while let Some(&ch) = chars.peek() {
-        match ch {
-            ' ' | '\t' | '\n' | '\r' => {
-                if !current.is_empty() {
-                    tokens.push(std::mem::take(&mut current));
-                }
-                chars.next();
+/// 解析 `-H 'Key: Value'` 中的 header 字符串为 ParseNode。
+fn parse_header_token(header_str: &str) -> Option<ParseNode> {
+    let (key, value) = header_str.split_once(':')?;
+    let key = key.trim().to_string();
+    let value = value.trim().to_string();
+
+    let children = parse_utils::parse_header_value_children(&key, &value);
+
+    Some(ParseNode {
+        key,
+        value,
+        children,
+        ..Default::default()
+    })
+}
+
+/// 规范化后的 URL 参数（非 flag 的 token）
+const URL_ARGUMENT: &str = "<url>";
+
+/// 需要参数的短 flag（可与参数连写，如 `-XPOST`、`-HAccept:*/*`）
+const SHORT_VALUE_FLAGS: &[char] = &[
+    'H', 'b', 'X', 'd', 'u', 'A', 'e', 'F', 'x', 'o', 'm', 'w', 'T', 'c', 'E', 'r', 'U', 'K',
+    'C', 't', 'Y', 'y', 'z', 'D', 'P', 'Q',
+];
+
+/// 需要参数的长 flag（按 `curl --help all` 整理，另含较新版本加入的选项）
+const LONG_VALUE_FLAGS: &[&str] = &[
+    "--abstract-unix-socket",
+    "--alt-svc",
+    "--aws-sigv4",
+    "--cacert",
+    "--capath",
+    "--cert",
+    "--cert-type",
+    "--ciphers",
+    "--config",
+    "--connect-timeout",
+    "--connect-to",
+    "--continue-at",
+    "--cookie",
+    "--cookie-jar",
+    "--create-file-mode",
+    "--crlfile",
+    "--curves",
+    "--data",
+    "--data-ascii",
+    "--data-binary",
+    "--data-raw",
+    "--data-urlencode",
+    "--delegation",
+    "--dns-interface",
+    "--dns-ipv4-addr",
+    "--dns-ipv6-addr",
+    "--dns-servers",
+    "--doh-url",
+    "--dump-header",
+    "--ech",
+    "--egd-file",
+    "--engine",
+    "--etag-compare",
+    "--etag-save",
+    "--expect100-timeout",
+    "--form",
+    "--form-string",
+    "--ftp-account",
+    "--ftp-alternative-to-user",
+    "--ftp-method",
+    "--ftp-port",
+    "--ftp-ssl-ccc-mode",
+    "--happy-eyeballs-timeout-ms",
+    "--header",
+    "--hostpubmd5",
+    "--hostpubsha256",
+    "--hsts",
+    "--interface",
+    "--ip-tos",
+    "--json",
+    "--keepalive-time",
+    "--key",
+    "--key-type",
+    "--knownhosts",
+    "--krb",
+    "--libcurl",
+    "--limit-rate",
+    "--local-port",
+    "--login-options",
+    "--mail-auth",
+    "--mail-from",
+    "--mail-rcpt",
+    "--max-filesize",
+    "--max-redirs",
+    "--max-time",
+    "--netrc-file",
+    "--noproxy",
+    "--oauth2-bearer",
+    "--output",
+    "--output-dir",
+    "--parallel-max",
+    "--pass",
+    "--pinnedpubkey",
+    "--preproxy",
+    "--proto",
+    "--proto-default",
+    "--proto-redir",
+    "--proxy",
+    "--proxy-cacert",
+    "--proxy-capath",
+    "--proxy-cert",
+    "--proxy-cert-type",
+    "--proxy-ciphers",
+    "--proxy-crlfile",
+    "--proxy-header",
+    "--proxy-key",
+    "--proxy-key-type",
+    "--proxy-pass",
+    "--proxy-pinnedpubkey",
+    "--proxy-service-name",
+    "--proxy-tls13-ciphers",
+    "--proxy-tlsauthtype",
+    "--proxy-tlspassword",
+    "--proxy-tlsuser",
+    "--proxy-user",
+    "--proxy1.0",
+    "--pubkey",
+    "--quote",
+    "--random-file",
+    "--range",
+    "--rate",
+    "--referer",
+    "--request",
+    "--request-target",
+    "--resolve",
+    "--retry",
+    "--retry-delay",
+    "--retry-max-time",
+    "--sasl-authzid",
+    "--service-name",
+    "--socks4",
+    "--socks4a",
+    "--socks5",
+    "--socks5-gssapi-service",
+    "--socks5-hostname",
+    "--speed-limit",
+    "--speed-time",
+    "--ssl-sessions",
+    "--stderr",
+    "--telnet-option",
+    "--tftp-blksize",
+    "--time-cond",
+    "--tls-max",
+    "--tls13-ciphers",
+    "--tlsauthtype",
+    "--tlspassword",
+    "--tlsuser",
+    "--trace",
+    "--trace-ascii",
+    "--trace-config",
+    "--unix-socket",
+    "--upload-file",
+    "--upload-flags",
+    "--url",
+    "--url-query",
+    "--user",
+    "--user-agent",
+    "--variable",
+    "--vlan-priority",
+    "--write-out",
+];
+
+/// 不影响请求本身、但值得保留的传输选项：（flag, metadata key, 固定值）。
+/// 固定值为 None 时使用 flag 的参数，无参数 flag 记为 `true`。
+const TRANSPORT_OPTIONS: &[(&str, &str, Option<&str>)] = &[
+    ("-x", "proxy", None),
+    ("--proxy", "proxy", None),
+    ("-U", "proxy-user", None),
+    ("--proxy-user", "proxy-user", None),
+    ("--preproxy", "preproxy", None),
+    ("--noproxy", "noproxy", None),
+    ("--resolve", "resolve", None),
+    ("--connect-to", "connect-to", None),
+    ("-k", "insecure", None),
+    ("--insecure", "insecure", None),
+    ("-0", "http-version", Some("HTTP/1.0")),
+    ("--http1.0", "http-version", Some("HTTP/1.0")),
+    ("--http1.1", "http-version", Some("HTTP/1.1")),
+    ("--http2", "http-version", Some("HTTP/2")),
+    ("--http2-prior-knowledge", "http-version", Some("HTTP/2 (prior knowledge)")),
+    ("--http3", "http-version", Some("HTTP/3")),
+    ("--http3-only", "http-version", Some("HTTP/3 (only)")),
+    ("-4", "ip-version", Some("IPv4")),
+    ("--ipv4", "ip-version", Some("IPv4")),
+    ("-6", "ip-version", Some("IPv6")),
+    ("--ipv6", "ip-version", Some("IPv6")),
+    ("-L", "follow-redirects", None),
+    ("--location", "follow-redirects", None),
+    ("--compressed", "compressed", None),
+    ("--cacert", "cacert", None),
+    ("-E", "cert", None),
+    ("--cert", "cert", None),
+    ("--key", "key", None),
+    ("--interface", "interface", None),
+    ("--unix-socket", "unix-socket", None),
+    ("-m", "max-time", None),
+    ("--max-time", "max-time", None),
+    ("--connect-timeout", "connect-timeout", None),
+];
+
+/// 将 token 规范化为（flag, 参数）列表：拆开连写的短 flag（`-sSL`、`-XPOST`），
+/// 支持 `--flag=value`，非 flag 的 token 记为 `URL_ARGUMENT`。`--` 之后全部视为 URL。
+fn normalize_options(tokens: &[String]) -> Vec<(String, Option<String>)> {
+    let mut options = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let tok = &tokens[i];
+        i += 1;
+
+        if tok == "--" {
+            for rest in &tokens[i..] {
+                options.push((URL_ARGUMENT.to_string(), Some(rest.clone())));
             }
-            '\'' => {
-                chars.next(); // consume opening '
-                // 单引号：原样保留内容，直到下一个 '
-                while let Some(&c) = chars.peek() {
-                    if c == '\'' {
-                        chars.next();
-                        break;
-                    }
-                    current.push(c);
-                    chars.next();
+            break;
+        }
+
+        if tok.starts_with("--") {
+            if let Some((name, value)) = tok.split_once('=') {
+                // curl 本身不支持 `--flag=value`，只接受已知的带参数 flag，其余忽略
+                if LONG_VALUE_FLAGS.contains(&name) {
+                    options.push((name.to_string(), Some(value.to_string())));
                 }
+            } else if LONG_VALUE_FLAGS.contains(&tok.as_str()) {
+                options.push((tok.clone(), tokens.get(i).cloned()));
+                i += 1;
+            } else {
+                options.push((tok.clone(), None));
             }
-            '"' => {
-                chars.next(); // consume opening "
-                // 双引号：处理转义
-                while let Some(&c) = chars.peek() {
-                    if c == '"' {
-                        chars.next();
-                        break;
-                    }
-                    if c == '\\' {
-                        chars.next();
-                        if let Some(&escaped) = chars.peek() {
-                            match escaped {
-                                '"' | '\\' | '$' | '`' => {
-                                    current.push(escaped);
-                                    chars.next();
-                                }
-                                'n' => {
-                                    current.push('\n');
-                                    chars.next();
-                                }
-                                't' => {
-                                    current.push('\t');
-                                    chars.next();
-                                }
-                                _ => {
-                                    current.push('\\');
-                                    current.push(escaped);
-                                    chars.next();
-                                }
-                            }
-                        }
+            continue;
+        }
+
+        if tok.len() > 1 && tok.starts_with('-') {
+            let flags = &tok[1..];
+            for (pos, c) in flags.char_indices() {
+                let flag = format!("-{c}");
+                if SHORT_VALUE_FLAGS.contains(&c) {
+                    let attached = &flags[pos + c.len_utf8()..];
+                    let value = if attached.is_empty() {
+                        i += 1;
+                        tokens.get(i - 1).cloned()
                     } else {
-                        current.push(c);
-                        chars.next();
-                    }
+                        Some(attached.to_string())
+                    };
+                    options.push((flag, value));
+                    break;
                 }
+                options.push((flag, None));
             }
-            '$' => {
-                chars.next();
-                if chars.peek() == Some(&'\'') {
-                    // $'...' ANSI-C quoting: 按单引号处理
-                    chars.next(); // consume '
-                    while let Some(&c) = chars.peek() {
-                        if c == '\'' {
-                            chars.next();
-                            break;
-                        }
-                        current.push(c);
-                        chars.next();
-                    }
-                } else {
-                    current.push('$');
-                }
-            }
-            _ => {
-                current.push(ch);
-                chars.next();
-            }
+            continue;
         }
+
+        options.push((URL_ARGUMENT.to_string(), Some(tok.clone())));
     }
+    options
+}
 
-    if !current.is_empty() {
-        tokens.push(current);
+fn transport_metadata(flag: &str, value: &str) -> Option<ParseNode> {
+    let (_, key, fixed) = TRANSPORT_OPTIONS.iter().find(|(f, _, _)| *f == flag)?;
+    let value = fixed.unwrap_or(if value.is_empty() { "true" } else { value });
+    Some(metadata_node(key, value))
+}
+
+/// 按 curl 的 `--data-urlencode` 规则编码：`content`、`=content`、`name=content` 编码内容部分；
+/// `@file`、`name@file` 引用的文件无法读取，内容留空（`name=`），同时返回文件路径。
+fn encode_data_urlencode(value: &str) -> (String, Option<&str>) {
+    match value.find(['=', '@']) {
+        Some(pos) if value.as_bytes()[pos] == b'=' => {
+            let (name, content) = (&value[..pos], &value[pos + 1..]);
+            if name.is_empty() {
+                (percent_encode(content), None)
+            } else {
+                (format!("{name}={}", percent_encode(content)), None)
+            }
+        }
+        Some(pos) => {
+            let (name, file) = (&value[..pos], &value[pos + 1..]);
+            let data = if name.is_empty() { String::new() } else { format!("{name}=") };
+            (data, Some(file))
+        }
+        None => (percent_encode(value), None),
     }
+}
 
-    tokens
+/// 与 curl_easy_escape 一致：除字母、数字和 `-._~` 外全部百分号编码。
+fn percent_encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
 }
 
-/// 解析 `-H 'Key: Value'` 中的 header 字符串为 ParseNode。
-fn parse_header_token(header_str: &str) -> Option<ParseNode> {
-    let (key, value) = header_str.split_once(':')?;
-    let key = key.trim().to_string();
-    let value = value.trim().to_string();
+/// 解析 `-F` 参数。`@path` 为文件上传（文件名取路径最后一段），`<path` 为从文件读取的文本字段，
+/// 二者均可带 `;type=` / `;filename=`；`--form-string` 的值原样使用。
+fn parse_form_option(value: &str, literal: bool) -> FormPart {
+    let (name, rest) = value.split_once('=').unwrap_or((value, ""));
+    let mut part = FormPart {
+        name: name.to_string(),
+        value: rest.to_string(),
+        filename: None,
+        content_type: None,
+    };
+    if literal {
+        return part;
+    }
 
-    let children = parse_utils::parse_header_value_children(&key, &value);
+    let (is_file, spec) = match rest.chars().next() {
+        Some('@') => (true, &rest[1..]),
+        Some('<') => (false, &rest[1..]),
+        _ => {
+            if let Some((text, content_type)) = rest.split_once(";type=") {
+                part.value = text.to_string();
+                part.content_type = Some(content_type.to_string());
+            }
+            return part;
+        }
+    };
 
-    Some(ParseNode {
-        key,
-        value,
-        children,
-        description: None,
-        decoded_value: None,
-        value_type: None,
-    })
+    let mut params = spec.split(';');
+    let path = params.next().unwrap_or("").trim_matches('"');
+    part.value = String::new();
+    if is_file {
+        part.filename = path.rsplit(['/', '\\']).next().map(|f| f.to_string());
+    }
+    for param in params {
+        match param.split_once('=') {
+            Some(("type", t)) => part.content_type = Some(t.to_string()),
+            Some(("filename", f)) => part.filename = Some(f.trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    if is_file && part.content_type.is_none() {
+        part.content_type = Some(multipart::guess_content_type(path).to_string());
+    }
+    part
 }
 
 #[cfg(test)]
@@ -477,4 +777,218 @@ mod tests {
         let result = parse_curl(input);
         assert_eq!(result.raw_text, input);
     }
+
+    #[test]
+    fn test_user_basic_auth() {
+        let result = parse_curl("curl -u alice:s3cret https://example.com/");
+        let auth = result.headers.iter().find(|h| h.key == "Authorization").unwrap();
+        assert_eq!(auth.value, "Basic YWxpY2U6czNjcmV0");
+
+        // 显式的 Authorization 头优先
+        let result = parse_curl("curl -u a:b -H 'Authorization: Bearer x' https://example.com/");
+        assert_eq!(result.headers.len(), 1);
+        assert_eq!(result.headers[0].value, "Bearer x");
+    }
+
+    #[test]
+    fn test_digest_auth_kept_as_metadata() {
+        let result = parse_curl("curl --digest -u bob:pw https://example.com/");
+        assert!(result.headers.is_empty());
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata[0].key, "digest");
+        assert_eq!(metadata[0].value, "bob:pw");
+    }
+
+    #[test]
+    fn test_user_agent_and_referer() {
+        let result = parse_curl("curl -A 'MyAgent/1.0' -e 'https://ref.example/;auto' https://example.com/");
+        assert_eq!(result.headers[0].key, "User-Agent");
+        assert_eq!(result.headers[0].value, "MyAgent/1.0");
+        assert_eq!(result.headers[1].key, "Referer");
+        assert_eq!(result.headers[1].value, "https://ref.example/");
+    }
+
+    #[test]
+    fn test_head_flag() {
+        let result = parse_curl("curl -I https://example.com/");
+        assert_eq!(result.method.as_deref(), Some("HEAD"));
+        assert_eq!(result.url.as_deref(), Some("https://example.com/"));
+
+        let result = parse_curl("curl -sIL https://example.com/");
+        assert_eq!(result.method.as_deref(), Some("HEAD"));
+    }
+
+    #[test]
+    fn test_get_moves_data_into_query() {
+        let result = parse_curl("curl -G https://example.com/search?lang=en -d q=rust --data-urlencode 'tag=a b'");
+        assert_eq!(result.method.as_deref(), Some("GET"));
+        assert!(result.body.is_none());
+        assert_eq!(
+            result.url.as_deref(),
+            Some("https://example.com/search?lang=en&q=rust&tag=a%20b")
+        );
+        assert_eq!(result.query_params.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_url_option() {
+        let result = parse_curl("curl -H 'accept: */*' --url https://example.com/a");
+        assert_eq!(result.url.as_deref(), Some("https://example.com/a"));
+    }
+
+    #[test]
+    fn test_multiple_data_joined() {
+        let result = parse_curl("curl https://example.com/ -d a=1 -d b=2 --data-raw '@c'");
+        assert_eq!(result.body.as_deref(), Some("a=1&b=2&@c"));
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        let content_type = result.headers.iter().find(|h| h.key == "Content-Type").unwrap();
+        assert_eq!(content_type.value, "application/x-www-form-urlencoded");
+    }
+
+    #[test]
+    fn test_data_urlencode_forms() {
+        assert_eq!(encode_data_urlencode("hello world").0, "hello%20world");
+        assert_eq!(encode_data_urlencode("=a&b").0, "a%26b");
+        assert_eq!(encode_data_urlencode("name=你好").0, "name=%E4%BD%A0%E5%A5%BD");
+        assert_eq!(encode_data_urlencode("name@file.txt"), ("name=".to_string(), Some("file.txt")));
+        assert_eq!(encode_data_urlencode("@file.txt"), (String::new(), Some("file.txt")));
+
+        let result = parse_curl("curl https://example.com/ --data-urlencode 'msg@body.txt' -d a=1");
+        assert_eq!(result.body.as_deref(), Some("msg=&a=1"));
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata[0].key, "data-urlencode-file");
+        assert_eq!(metadata[0].value, "body.txt");
+    }
+
+    #[test]
+    fn test_json_option() {
+        let result = parse_curl(r#"curl --json '{"a":1}' https://example.com/api"#);
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(result.body.as_deref(), Some(r#"{"a":1}"#));
+        let keys: Vec<&str> = result.headers.iter().map(|h| h.key.as_str()).collect();
+        assert_eq!(keys, vec!["Content-Type", "Accept"]);
+        assert_eq!(result.headers[0].value, "application/json");
+    }
+
+    #[test]
+    fn test_form_option_builds_multipart() {
+        let result = parse_curl("curl -F 'user=alice' -F 'avatar=@/tmp/me.png' -F 'note=<notes.txt;type=text/markdown' https://example.com/upload");
+        assert_eq!(result.method.as_deref(), Some("POST"));
+
+        let content_type = result.headers.iter().find(|h| h.key == "Content-Type").unwrap();
+        assert!(content_type.value.starts_with("multipart/form-data; boundary="));
+
+        let body = result.body.unwrap();
+        assert!(body.contains("Content-Disposition: form-data; name=\"user\"\r\n\r\nalice\r\n"));
+        assert!(body.contains("name=\"avatar\"; filename=\"me.png\"\r\nContent-Type: image/png\r\n"));
+        assert!(body.contains("name=\"note\"\r\nContent-Type: text/markdown\r\n"));
+        assert!(body.ends_with(&format!("--{FORM_BOUNDARY}--\r\n")));
+
+        let parts = multipart::parse_multipart(&body, FORM_BOUNDARY).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].value, "alice");
+    }
+
+    #[test]
+    fn test_attached_short_options() {
+        let result = parse_curl("curl -XPUT -H'X-Id: 7' -dx=1 https://example.com/");
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+        assert_eq!(result.headers[0].key, "X-Id");
+        assert_eq!(result.body.as_deref(), Some("x=1"));
+    }
+
+    #[test]
+    fn test_transport_options_as_metadata() {
+        let result = parse_curl("curl -x http://proxy:8080 --resolve example.com:443:127.0.0.1 -k --http2 -L -o out.json --max-time 5 https://example.com/");
+        assert_eq!(result.url.as_deref(), Some("https://example.com/"));
+        let metadata = result.metadata.unwrap();
+        let pairs: Vec<(&str, &str)> = metadata
+            .iter()
+            .map(|m| (m.key.as_str(), m.value.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("proxy", "http://proxy:8080"),
+                ("resolve", "example.com:443:127.0.0.1"),
+                ("insecure", "true"),
+                ("http-version", "HTTP/2"),
+                ("follow-redirects", "true"),
+                ("max-time", "5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cookie_file_not_header() {
+        let result = parse_curl("curl -b cookies.txt https://example.com/");
+        assert!(result.headers.is_empty());
+        assert_eq!(result.metadata.unwrap()[0].key, "cookie-file");
+    }
+
+    #[test]
+    fn test_upload_file_defaults_put() {
+        let result = parse_curl("curl -T report.csv https://example.com/files/");
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+    }
+
+    #[test]
+    fn test_windows_cmd_copy_as_curl() {
+        let input = "curl ^\"https://example.com/api?a=1^&b=2^\" ^\n  -H ^\"content-type: application/json^\" ^\n  -b ^\"sid=abc; theme=dark^\" ^\n  --data-raw ^\"^{^\\^\"name^\\^\":^\\^\"test^\\^\"^}^\" ^\n  --compressed";
+        let result = parse_curl(input);
+
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api?a=1&b=2"));
+        assert_eq!(result.headers[0].value, "application/json");
+        let cookie = result.headers.iter().find(|h| h.key == "Cookie").unwrap();
+        assert_eq!(cookie.children.as_ref().unwrap().len(), 2);
+        assert_eq!(result.body.as_deref(), Some(r#"{"name":"test"}"#));
+        assert_eq!(result.metadata.unwrap()[0].value, "cmd");
+    }
+
+    #[test]
+    fn test_powershell_curl_exe() {
+        let input = "curl.exe 'https://example.com/api' `\n  -H 'accept: */*' `\n  -H \"x-token: a`\"b\" `\n  --data-raw '{\"it''s\":1}'";
+        let result = parse_curl(input);
+
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api"));
+        let token = result.headers.iter().find(|h| h.key == "x-token").unwrap();
+        assert_eq!(token.value, "a\"b");
+        assert_eq!(result.body.as_deref(), Some(r#"{"it's":1}"#));
+        assert_eq!(result.metadata.unwrap()[0].value, "powershell");
+    }
+
+    #[test]
+    fn test_value_flags_before_url() {
+        let input = "curl --socks5-hostname 127.0.0.1:1080 --proxy-cacert ca.pem --tls-max 1.2 \
+            --trace out.txt --stderr err.txt --netrc-file .netrc https://example.com/a";
+        let result = parse_curl(input);
+        assert_eq!(result.url.as_deref(), Some("https://example.com/a"));
+    }
+
+    #[test]
+    fn test_uppercase_program_name() {
+        for input in ["CURL.EXE https://example.com/a", "Curl -X PUT https://example.com/a"] {
+            let result = parse_curl(input);
+            assert_eq!(result.url.as_deref(), Some("https://example.com/a"), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_ansi_c_multipart_body() {
+        let input = r#"curl 'https://example.com/upload' \
+  -H 'content-type: multipart/form-data; boundary=----WebKitFormBoundaryX3b' \
+  --data-raw $'------WebKitFormBoundaryX3b\r\nContent-Disposition: form-data; name="title"\r\n\r\n中文\r\n------WebKitFormBoundaryX3b--\r\n'"#;
+        let mut result = parse_curl(input);
+        assert_eq!(
+            result.body.as_deref(),
+            Some("------WebKitFormBoundaryX3b\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\n中文\r\n------WebKitFormBoundaryX3b--\r\n")
+        );
+
+        crate::body_parser::apply_body_parsing(&mut result);
+        let nodes = result.body_nodes.unwrap();
+        assert_eq!(nodes[0].key, "title");
+        assert_eq!(nodes[0].value, "中文");
+    }
 }
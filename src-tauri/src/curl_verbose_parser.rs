@@ -0,0 +1,229 @@
+use crate::models::{HttpExchange, ParseNode};
+use crate::parser;
+
+const TLS_KEYWORDS: &[&str] = &[
+    "tls",
+    "ssl",
+    "alpn",
+    "certificate",
+    "subject:",
+    "issuer:",
+    "start date",
+    "expire date",
+];
+
+const CONNECTION_KEYWORDS: &[&str] = &["trying", "connected", "connection", "host "];
+
+/// 解析 `curl -v` 的输出记录，返回请求与响应。
+/// `> ` 行为发出的请求，`< ` 行为收到的响应，`* ` 行为连接/TLS 说明（作为请求的 metadata），
+/// 响应头之后不带前缀的行为响应 body。
+pub fn parse_curl_verbose(input: &str) -> HttpExchange {
+    let mut request_lines: Vec<&str> = Vec::new();
+    let mut responses: Vec<(Vec<&str>, Vec<&str>)> = Vec::new();
+    let mut notes: Vec<ParseNode> = Vec::new();
+    let mut in_response_body = false;
+
+    for line in input.lines() {
+        let line = line.trim_end_matches('\r');
+
+        if let Some(rest) = strip_marker(line, '>') {
+            // 只保留第一组请求（-L 重定向时的后续请求忽略）
+            if responses.is_empty() {
+                request_lines.push(rest);
+            }
+            in_response_body = false;
+        } else if let Some(rest) = strip_marker(line, '<') {
+            if is_status_line(rest) || responses.is_empty() {
+                responses.push((Vec::new(), Vec::new()));
+            }
+            if let Some((headers, _)) = responses.last_mut() {
+                headers.push(rest);
+            }
+            in_response_body = true;
+        } else if let Some(rest) = strip_marker(line, '*') {
+            let note = rest.trim();
+            if !note.is_empty() {
+                notes.push(make_note(note));
+            }
+        } else if is_data_marker(line) {
+            // `{ [13 bytes data]` / `} [5 bytes data]` 为进度信息，忽略
+        } else if in_response_body {
+            if let Some((_, body)) = responses.last_mut() {
+                body.push(line);
+            }
+        }
+    }
+
+    let request = if request_lines.iter().any(|l| !l.trim().is_empty()) {
+        let mut result = parser::parse_http_text(&request_lines.join("\n"));
+        if !notes.is_empty() {
+            result.metadata = Some(notes);
+        }
+        Some(result)
+    } else {
+        None
+    };
+
+    // 跳过 `HTTP/1.1 100 Continue` 等 1xx 中间响应
+    let response = responses
+        .iter()
+        .map(|(headers, body)| {
+            let mut text = headers.join("\n").trim_end().to_string();
+            let body_text = body.join("\n");
+            if !body_text.trim().is_empty() {
+                text.push_str("\n\n");
+                text.push_str(body_text.trim_end());
+            }
+            parser::parse_http_text(&text)
+        })
+        .find(|r| !matches!(r.status_code, Some(100..=199)));
+
+    HttpExchange {
+        request,
+        response,
+        started_at: None,
+        duration_ms: None,
+    }
+}
+
+/// 去掉 `> ` / `< ` / `* ` 前缀。单独的 `>` / `<`（头部结束标志）返回空串。
+fn strip_marker(line: &str, marker: char) -> Option<&str> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() {
+        return Some("");
+    }
+    rest.strip_prefix(' ')
+}
+
+fn is_data_marker(line: &str) -> bool {
+    (line.starts_with("{ [") || line.starts_with("} [")) && line.ends_with(']')
+}
+
+fn is_status_line(line: &str) -> bool {
+    line.starts_with("HTTP/")
+}
+
+/// 将 `* ` 说明行归类为 tls / connection / info 节点。
+fn make_note(note: &str) -> ParseNode {
+    let lower = note.to_lowercase();
+    let key = if TLS_KEYWORDS.iter().any(|kw| lower.contains(kw)) {
+        "tls"
+    } else if CONNECTION_KEYWORDS.iter().any(|kw| lower.contains(kw)) {
+        "connection"
+    } else {
+        "info"
+    };
+
+    ParseNode {
+        key: key.to_string(),
+        value: note.to_string(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpContentType;
+
+    const TRANSCRIPT: &str = "*   Trying 93.184.216.34:443...
+* Connected to example.com (93.184.216.34) port 443 (#0)
+* ALPN: offers h2,http/1.1
+* SSL connection using TLSv1.3 / TLS_AES_256_GCM_SHA384
+> GET /api/users?page=1 HTTP/1.1
+> Host: example.com
+> User-Agent: curl/8.1.2
+> Accept: */*
+>
+< HTTP/1.1 200 OK
+< Content-Type: application/json
+< Set-Cookie: sid=abc; Path=/
+<
+{ [13 bytes data]
+{\"ok\": true}
+* Connection #0 to host example.com left intact";
+
+    #[test]
+    fn test_request_parsed() {
+        let request = parse_curl_verbose(TRANSCRIPT).request.unwrap();
+
+        assert!(matches!(request.content_type, HttpContentType::Request));
+        assert_eq!(request.method.as_deref(), Some("GET"));
+        assert_eq!(request.protocol.as_deref(), Some("HTTP/1.1"));
+        assert_eq!(request.headers.len(), 3);
+        assert_eq!(request.headers[0].key, "Host");
+        let params = request.query_params.unwrap();
+        assert_eq!(params[0].key, "page");
+    }
+
+    #[test]
+    fn test_response_parsed_with_body() {
+        let response = parse_curl_verbose(TRANSCRIPT).response.unwrap();
+
+        assert!(matches!(response.content_type, HttpContentType::Response));
+        assert_eq!(response.status_code, Some(200));
+        assert_eq!(response.headers.len(), 2);
+        assert_eq!(response.body.as_deref(), Some("{\"ok\": true}"));
+
+        let set_cookie = response
+            .headers
+            .iter()
+            .find(|h| h.key == "Set-Cookie")
+            .unwrap();
+        assert_eq!(set_cookie.children.as_ref().unwrap()[0].key, "sid");
+    }
+
+    #[test]
+    fn test_connection_notes_as_metadata() {
+        let request = parse_curl_verbose(TRANSCRIPT).request.unwrap();
+        let metadata = request.metadata.unwrap();
+
+        assert_eq!(metadata.len(), 5);
+        assert_eq!(metadata[0].key, "connection");
+        assert_eq!(metadata[0].value, "Trying 93.184.216.34:443...");
+        assert_eq!(metadata[2].key, "tls");
+        assert_eq!(metadata[3].key, "tls");
+        assert_eq!(metadata[4].key, "connection");
+    }
+
+    #[test]
+    fn test_skip_continue_response() {
+        let input = "> POST /upload HTTP/1.1
+> Host: example.com
+> Expect: 100-continue
+>
+< HTTP/1.1 100 Continue
+<
+< HTTP/1.1 201 Created
+< Location: /files/1
+<";
+        let HttpExchange {
+            request, response, ..
+        } = parse_curl_verbose(input);
+        assert_eq!(request.unwrap().method.as_deref(), Some("POST"));
+        let response = response.unwrap();
+        assert_eq!(response.status_code, Some(201));
+        assert_eq!(response.headers[0].key, "Location");
+    }
+
+    #[test]
+    fn test_request_only() {
+        let input = "> GET / HTTP/1.1\n> Host: localhost\n>\n* Empty reply from server";
+        let HttpExchange {
+            request, response, ..
+        } = parse_curl_verbose(input);
+        assert!(request.is_some());
+        assert!(response.is_none());
+        assert_eq!(request.unwrap().metadata.unwrap()[0].key, "info");
+    }
+
+    #[test]
+    fn test_leading_command_line_ignored() {
+        let input = "$ curl -v http://localhost/\n> GET / HTTP/1.1\n> Host: localhost\n>\n< HTTP/1.1 204 No Content\n<";
+        let HttpExchange {
+            request, response, ..
+        } = parse_curl_verbose(input);
+        assert_eq!(request.unwrap().headers.len(), 1);
+        assert_eq!(response.unwrap().status_code, Some(204));
+    }
+}
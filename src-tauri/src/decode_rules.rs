@@ -0,0 +1,365 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::decoder::{self, DecodeContext, Decoder};
+use crate::error::AppError;
+use crate::models::ParseNode;
+
+/// 用户定义的解码规则：key 匹配后，用正则命名捕获或分隔符把值拆成子节点，
+/// 子节点依次经过 `decoders` 中的内置解码器（未指定时自动识别）。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DecodeRule {
+    /// 规则名，同时作为设置中的解码器 id
+    pub name: String,
+    /// 节点 key 的匹配模式（不区分大小写，支持 `*`）
+    pub key: String,
+    /// 值必须匹配的正则；命名捕获组生成同名子节点
+    pub pattern: Option<String>,
+    /// 没有命名捕获组时按此分隔符拆分值
+    pub split: Option<String>,
+    /// 拆分后各段的名称，缺少名称的段以序号命名
+    pub fields: Vec<String>,
+    /// 依次作用于每个子节点（无子节点时作用于整个值）的内置解码器 id
+    pub decoders: Vec<String>,
+    /// 优先级，默认 100（先于全部内置解码器）
+    pub priority: Option<i32>,
+}
+
+/// 规则文件的顶层结构：TOML 中写作 `[[rule]]`，JSON 中为 `{"rules": [...]}`。
+#[derive(Deserialize)]
+struct RulesFile {
+    #[serde(alias = "rule", default)]
+    rules: Vec<DecodeRule>,
+}
+
+/// 编译后的规则，作为解码器注册到 `DecoderRegistry`。
+pub struct RuleDecoder {
+    rule: DecodeRule,
+    pattern: Option<Regex>,
+}
+
+impl RuleDecoder {
+    pub fn new(rule: DecodeRule) -> Result<Self, String> {
+        if rule.name.trim().is_empty() {
+            return Err("rule name is empty".to_string());
+        }
+        if rule.key.trim().is_empty() {
+            return Err(format!("rule `{}`: key is empty", rule.name));
+        }
+        if rule.split.as_deref() == Some("") {
+            return Err(format!("rule `{}`: split delimiter is empty", rule.name));
+        }
+        let pattern = rule
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("rule `{}`: invalid pattern: {e}", rule.name))?;
+        Ok(RuleDecoder { rule, pattern })
+    }
+
+    /// `new` 加上与其他解码器的关系校验：规则名不能占用内置解码器的 id，
+    /// 解码器链只能引用内置解码器（规则之间不能互相调用，避免无限递归）。
+    pub fn validated(rule: DecodeRule) -> Result<Self, String> {
+        if decoder::is_builtin(&rule.name) {
+            return Err(format!(
+                "rule `{}`: name conflicts with a built-in decoder",
+                rule.name
+            ));
+        }
+        if let Some(id) = rule.decoders.iter().find(|id| !decoder::is_builtin(id)) {
+            return Err(format!("rule `{}`: unknown decoder `{id}`", rule.name));
+        }
+        Self::new(rule)
+    }
+
+    /// 按命名捕获或分隔符拆出的（名称, 值）；值不符合规则时返回 None，无需拆分时返回空列表。
+    fn fields(&self, value: &str) -> Option<Vec<(String, String)>> {
+        if let Some(pattern) = &self.pattern {
+            let caps = pattern.captures(value)?;
+            let named: Vec<(String, String)> = pattern
+                .capture_names()
+                .flatten()
+                .filter_map(|name| Some((name.to_string(), caps.name(name)?.as_str().to_string())))
+                .collect();
+            if !named.is_empty() {
+                return Some(named);
+            }
+        }
+        let Some(delimiter) = &self.rule.split else {
+            return Some(Vec::new());
+        };
+        let parts: Vec<&str> = value.split(delimiter.as_str()).collect();
+        if parts.len() < 2 {
+            return None;
+        }
+        Some(
+            parts
+                .into_iter()
+                .enumerate()
+                .map(|(i, part)| {
+                    let name = self
+                        .rule
+                        .fields
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| i.to_string());
+                    (name, part.to_string())
+                })
+                .collect(),
+        )
+    }
+
+    /// 依次运行解码器链，每一步的 decoded_value 作为下一步的输入；返回最后一个成功步骤的节点，
+    /// 其 provenance 为规则之前的步骤加上成功执行的解码器。
+    fn run_chain(&self, key: &str, value: &str, ctx: &DecodeContext) -> Option<ParseNode> {
+        let mut last: Option<ParseNode> = None;
+        let mut current = value.to_string();
+        let mut steps = ctx.chain().to_vec();
+        for id in &self.rule.decoders {
+            let mut step = plain_node(key, &current);
+            if !ctx.decode_with(id, &mut step) {
+                break;
+            }
+            steps.push(id.clone());
+            step.provenance = Some(steps.clone());
+            let next = step.decoded_value.clone();
+            last = Some(step);
+            match next {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        last
+    }
+}
+
+impl Decoder for RuleDecoder {
+    fn id(&self) -> &str {
+        &self.rule.name
+    }
+
+    fn decode(&self, node: &mut ParseNode, ctx: &DecodeContext) -> bool {
+        if !decoder::glob_match(&self.rule.key, &node.key) {
+            return false;
+        }
+        let value = node.value.trim().to_string();
+        let Some(fields) = self.fields(&value) else {
+            return false;
+        };
+
+        if fields.is_empty() {
+            // 只做匹配：解码器链作用于整个值
+            let Some(decoded) = self.run_chain(&node.key, &value, ctx) else {
+                return false;
+            };
+            node.decoded_value = decoded.decoded_value;
+            node.children = decoded.children;
+            node.provenance = decoded.provenance;
+        } else {
+            let children = fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let mut child = plain_node(&name, &value);
+                    match self.run_chain(&name, &value, ctx) {
+                        Some(decoded) => {
+                            child.decoded_value = decoded.decoded_value;
+                            child.value_type = decoded.value_type;
+                            child.children = decoded.children;
+                            child.provenance = decoded.provenance;
+                        }
+                        None => ctx.decode_child(&mut child),
+                    }
+                    child
+                })
+                .collect();
+            node.children = Some(children);
+        }
+        node.value_type = Some("custom".to_string());
+        node.description = Some(self.rule.name.clone());
+        true
+    }
+}
+
+/// 读取规则文件：扩展名为 `.json` 时按 JSON 解析，否则按 TOML 解析。
+pub fn load_rules(path: &Path) -> Result<Vec<DecodeRule>, AppError> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| AppError::InternalError(format!("Failed to read decode rules: {e}")))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    parse_rules(&text, is_json)
+        .map_err(|e| AppError::ParseError(format!("Invalid decode rules: {e}")))
+}
+
+/// 解析并校验规则：正则可编译、解码器链只引用内置解码器。
+pub fn parse_rules(text: &str, is_json: bool) -> Result<Vec<DecodeRule>, String> {
+    let file: RulesFile = if is_json {
+        serde_json::from_str(text).map_err(|e| e.to_string())?
+    } else {
+        toml::from_str(text).map_err(|e| e.to_string())?
+    };
+    validate_rules(&file.rules)?;
+    Ok(file.rules)
+}
+
+/// 校验一组规则（无论来自规则文件、设置还是调用参数），返回第一个错误。
+pub fn validate_rules(rules: &[DecodeRule]) -> Result<(), String> {
+    for rule in rules {
+        RuleDecoder::validated(rule.clone())?;
+    }
+    Ok(())
+}
+
+fn plain_node(key: &str, value: &str) -> ParseNode {
+    ParseNode {
+        key: key.to_string(),
+        value: value.to_string(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::{DecoderConfig, DecoderRegistry};
+
+    const RULES: &str = r#"
+[[rule]]
+name = "trace-ctx"
+key = "X-Trace-Ctx"
+split = "|"
+fields = ["env", "region", "userId", "ts"]
+
+[[rule]]
+name = "session-blob"
+key = "session*"
+pattern = '^sess_v1:(?P<payload>[A-Za-z0-9+/=]+)$'
+decoders = ["base64", "json"]
+"#;
+
+    fn registry() -> DecoderRegistry {
+        DecoderRegistry::new(&DecoderConfig {
+            decoders: Vec::new(),
+            rules: parse_rules(RULES, false).unwrap(),
+            max_depth: None,
+            max_output_bytes: None,
+        })
+    }
+
+    #[test]
+    fn test_split_rule() {
+        let mut node = plain_node("x-trace-ctx", "prod|eu-west-1|42|1700000000");
+        registry().decode_node(&mut node, Some("x-trace-ctx"));
+
+        assert_eq!(node.value_type.as_deref(), Some("custom"));
+        assert_eq!(node.description.as_deref(), Some("trace-ctx"));
+        let children = node.children.unwrap();
+        let keys: Vec<&str> = children.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, ["env", "region", "userId", "ts"]);
+        assert_eq!(children[1].value, "eu-west-1");
+        // 未指定解码器链时子节点自动识别
+        assert_eq!(children[3].value_type.as_deref(), Some("timestamp"));
+    }
+
+    #[test]
+    fn test_pattern_rule_with_decoder_chain() {
+        // base64 of {"uid":7,"role":"admin"}
+        let mut node = plain_node("session_id", "sess_v1:eyJ1aWQiOjcsInJvbGUiOiJhZG1pbiJ9");
+        registry().decode_node(&mut node, None);
+
+        let children = node.children.unwrap();
+        assert_eq!(children[0].key, "payload");
+        assert_eq!(children[0].value_type.as_deref(), Some("json"));
+        assert!(children[0]
+            .decoded_value
+            .as_deref()
+            .unwrap()
+            .contains("\"role\": \"admin\""));
+        assert_eq!(
+            children[0].provenance.as_deref().unwrap(),
+            ["session-blob", "base64", "json"]
+        );
+    }
+
+    #[test]
+    fn test_non_matching_value_falls_back_to_builtins() {
+        let mut node = plain_node("session", "1700000000");
+        registry().decode_node(&mut node, None);
+        assert_eq!(node.value_type.as_deref(), Some("timestamp"));
+
+        let mut node = plain_node("X-Trace-Ctx", "single");
+        registry().decode_node(&mut node, None);
+        assert!(node.value_type.is_none());
+    }
+
+    #[test]
+    fn test_json_rules_and_validation() {
+        let rules = parse_rules(
+            r#"{"rules":[{"name":"r","key":"k","pattern":"^(?P<a>\\d+)-(?P<b>\\d+)$"}]}"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(rules[0].name, "r");
+
+        let err = parse_rules(
+            "[[rule]]\nname = \"r\"\nkey = \"k\"\npattern = \"(\"",
+            false,
+        );
+        assert!(err.unwrap_err().contains("invalid pattern"));
+        let err = parse_rules(
+            "[[rule]]\nname = \"r\"\nkey = \"k\"\ndecoders = [\"rot13\"]",
+            false,
+        );
+        assert!(err.unwrap_err().contains("unknown decoder `rot13`"));
+    }
+
+    #[test]
+    fn test_self_referencing_rules_do_not_recurse() {
+        // 不经过 parse_rules（如 parse_text 的 decoderConfig 参数）传入的规则同样被校验
+        let rule = |name: &str, decoders: &[&str]| DecodeRule {
+            name: name.to_string(),
+            key: "x".to_string(),
+            decoders: decoders.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let rules = vec![
+            rule("loop", &["loop"]),
+            rule("ping", &["pong"]),
+            rule("pong", &["ping"]),
+            rule("base64", &[]),
+        ];
+        assert!(validate_rules(&rules[..1]).is_err());
+        assert!(validate_rules(&rules[3..]).is_err());
+
+        let registry = DecoderRegistry::new(&DecoderConfig {
+            rules,
+            ..Default::default()
+        });
+        let mut node = plain_node("x", "1700000000");
+        registry.decode_node(&mut node, None);
+        assert_eq!(node.value_type.as_deref(), Some("timestamp"));
+    }
+
+    #[test]
+    fn test_decode_with_honours_max_depth() {
+        let rules = vec![DecodeRule {
+            name: "chain".to_string(),
+            key: "x".to_string(),
+            decoders: vec!["base64".to_string()],
+            ..Default::default()
+        }];
+        let registry = DecoderRegistry::new(&DecoderConfig {
+            rules,
+            max_depth: Some(1),
+            ..Default::default()
+        });
+        // base64 of {"a":1}
+        let mut node = plain_node("x", "eyJhIjoxfQ==");
+        registry.decode_node(&mut node, None);
+        assert_ne!(node.value_type.as_deref(), Some("custom"));
+    }
+}
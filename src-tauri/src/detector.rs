@@ -1,24 +1,94 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
+use crate::httpie_parser;
+
+/// 请求行：带 HTTP 版本时 method 可为任意 RFC 9110 token；
+/// 不带版本时要求大写 method 与 origin / absolute / authority / asterisk 形式的 target。
 static RE_REQUEST_LINE: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
-        r"^(GET|POST|PUT|DELETE|PATCH|HEAD|OPTIONS|TRACE|CONNECT)\s+\S+(\s+HTTP/\d\.\d)?\s*$",
+        r"^(?:[!#$%&'*+.^_`|~0-9A-Za-z-]+\s+\S+\s+HTTP/\d(\.\d)?|[A-Z][A-Z_-]*\s+(?:/\S*|\*|[A-Za-z][A-Za-z0-9+.-]*://\S+|[A-Za-z0-9.-]+:\d+))\s*$",
     )
     .unwrap()
 });
 
 static RE_RESPONSE_LINE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^HTTP/\d\.\d\s+\d{3}\s+.*$").unwrap());
+    LazyLock::new(|| Regex::new(r"^HTTP/\d(\.\d)?\s+\d{3}(\s+.*)?$").unwrap());
+
+/// fetch 调用：允许前置 `import` / `require` / 注释行，以及 `const x = await fetch(` 形式（Node.js）。
+static RE_FETCH_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:(?:import\s[^\n]*|(?:const|let|var)\s+\w+\s*=\s*require\([^\n]*|//[^\n]*)\n\s*)*(?:(?:const|let|var)\s+\w+\s*=\s*)?(?:await\s+)?fetch\s*\(",
+    )
+    .unwrap()
+});
+
+/// PowerShell：`Invoke-WebRequest` / `Invoke-RestMethod`（含 `iwr` / `irm` 别名与赋值形式），
+/// 或 Chrome "Copy as PowerShell" 开头的 WebRequestSession。
+static RE_POWERSHELL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?im)^[ \t]*(?:(?:\$\w+\s*=\s*)?(?:Invoke-WebRequest|Invoke-RestMethod|iwr|irm)\s|\$\w+\s*=\s*New-Object\s+Microsoft\.PowerShell\.Commands\.WebRequestSession)",
+    )
+    .unwrap()
+});
+
+/// Python `requests` / `httpx`：导入语句，或行首的 `requests.get(` / `httpx.post(` 等调用。
+static RE_PYTHON_REQUESTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?m)^(?:(?:import|from)\s+(?:requests|httpx)\b|[ \t]*(?:[\w.]+\s*=\s*)?(?:await\s+)?(?:requests|httpx)\.(?:get|post|put|patch|delete|head|options|request|stream)\s*\()",
+    )
+    .unwrap()
+});
+
+/// Python 片段的第一条语句：导入、赋值、`with` / `def` 或 requests / httpx 调用。
+static RE_PYTHON_STATEMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:(?:import|from|with|def)\s|async\s+(?:with|def)\s|[\w.]+\s*=[^=]|(?:await\s+)?(?:requests|httpx)\.)",
+    )
+    .unwrap()
+});
+
+/// `.http` 文件标记：`###` 分隔行或 `@name = value` 变量定义。
+static RE_HTTP_FILE_MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*(?:###|@[\w.-]+\s*=)").unwrap());
+
+/// `.http` 文件中的请求行：可省略 method 与版本，URL 可以以 `{{变量}}` 开头。
+static RE_HTTP_FILE_REQUEST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^(?:[A-Z]+[ \t]+)?(?:https?://|\{\{|/)\S*(?:[ \t]+HTTP/[\d.]+)?[ \t]*$").unwrap()
+});
+
+/// ZAP 导出消息的分隔行：`==== 12 ==========`
+static RE_ZAP_SEPARATOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^==== \d+ =+[ \t]*\r?\n").unwrap());
+
+/// HTTPie 的 URL 参数：带 scheme 的 URL、`:3000/path` 形式的 localhost 简写、
+/// 或带点号 / 端口的主机名（可带路径）。
+static RE_HTTPIE_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:[A-Za-z][A-Za-z0-9+.-]*://\S+|:\d*(?:[/?#]\S*)?|(?:[\w-]+(?:\.[\w-]+)+|localhost|[\w.-]+:\d+)(?::\d+)?(?:[/?#]\S*)?)$",
+    )
+    .unwrap()
+});
 
 static RE_HEADER_LINE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^[\w-]+:\s*.*$").unwrap());
+    LazyLock::new(|| Regex::new(r"^:?[\w-]+:\s*.*$").unwrap());
 
 /// 输入格式类型
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputFormat {
     Curl,
+    CurlVerbose,
     Fetch,
+    PowerShell,
+    Httpie,
+    Wget,
+    Har,
+    Postman,
+    Insomnia,
+    Burp,
+    Zap,
+    Python,
+    HttpFile,
     RawHttp,
     Unknown,
 }
@@ -30,14 +100,58 @@ pub fn detect_input_format(text: &str) -> InputFormat {
         return InputFormat::Unknown;
     }
 
-    if trimmed.starts_with("curl ") || trimmed.starts_with("curl\t") {
+    if is_curl_verbose_like(trimmed) {
+        return InputFormat::CurlVerbose;
+    }
+
+    if is_curl_command(trimmed) {
         return InputFormat::Curl;
     }
 
-    if trimmed.starts_with("fetch(") || trimmed.starts_with("fetch (") {
+    if RE_FETCH_CALL.is_match(trimmed) {
         return InputFormat::Fetch;
     }
 
+    if RE_POWERSHELL.is_match(trimmed) {
+        return InputFormat::PowerShell;
+    }
+
+    if is_httpie_command(trimmed) {
+        return InputFormat::Httpie;
+    }
+
+    if is_command(trimmed, &["wget", "wget.exe"]) {
+        return InputFormat::Wget;
+    }
+
+    if is_har_like(trimmed) {
+        return InputFormat::Har;
+    }
+
+    if is_postman_like(trimmed) {
+        return InputFormat::Postman;
+    }
+
+    if is_insomnia_like(trimmed) {
+        return InputFormat::Insomnia;
+    }
+
+    if is_burp_like(trimmed) {
+        return InputFormat::Burp;
+    }
+
+    if RE_ZAP_SEPARATOR.is_match(trimmed) {
+        return InputFormat::Zap;
+    }
+
+    if is_python_snippet(trimmed) {
+        return InputFormat::Python;
+    }
+
+    if is_http_file_like(trimmed) {
+        return InputFormat::HttpFile;
+    }
+
     if is_raw_http_like(trimmed) {
         return InputFormat::RawHttp;
     }
@@ -50,6 +164,132 @@ pub fn is_http_like(text: &str) -> bool {
     detect_input_format(text) != InputFormat::Unknown
 }
 
+/// 判断文本是否为 curl 命令（含 Windows 下的 `curl.exe`）。
+fn is_curl_command(text: &str) -> bool {
+    ["curl", "curl.exe"].iter().any(|prefix| {
+        text.get(..prefix.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+            && text[prefix.len()..].starts_with([' ', '\t'])
+    })
+}
+
+/// 判断文本是否以给定的命令名开头（区分大小写，避免把 `HTTP/1.1 ...` 当作 HTTPie）。
+fn is_command(text: &str, programs: &[&str]) -> bool {
+    programs.iter().any(|program| {
+        text.strip_prefix(program)
+            .is_some_and(|rest| rest.starts_with([' ', '\t']))
+    })
+}
+
+/// 判断文本是否为 HTTPie 命令：`http` / `https` 开头的普通文本很常见，
+/// 因此还要求（跳过选项后）第一个参数是 URL，或是 method 且其后紧跟 URL。
+fn is_httpie_command(text: &str) -> bool {
+    if !is_command(text, &["http", "https", "xh", "xhs"]) {
+        return false;
+    }
+    let mut words = text
+        .lines()
+        .next()
+        .unwrap_or("")
+        .split_whitespace()
+        .skip(1)
+        .map(|word| word.trim_matches(['\'', '"']));
+    let mut positionals = Vec::new();
+    while let Some(word) = words.next() {
+        if word.starts_with('-') && word != "-" {
+            if httpie_parser::VALUE_OPTIONS.contains(&word) {
+                words.next();
+            }
+            continue;
+        }
+        positionals.push(word);
+        if positionals.len() == 2 {
+            break;
+        }
+    }
+    let is_url = |word: Option<&&str>| word.is_some_and(|w| RE_HTTPIE_URL.is_match(w));
+    is_url(positionals.first())
+        || (positionals
+            .first()
+            .is_some_and(|w| w.chars().all(|c| c.is_ascii_alphabetic()))
+            && is_url(positionals.get(1)))
+}
+
+/// 判断文本是否像 `curl -v` 输出：包含 `> ` 请求行，且包含 `< ` 响应行或 `* ` 说明行；
+/// 还要求出现 `> METHOD target HTTP/x` 或 `< HTTP/x status`，避免把 Markdown 的引用与列表当作 curl -v。
+fn is_curl_verbose_like(text: &str) -> bool {
+    let mut request_lines = 0;
+    let mut other_lines = 0;
+    let mut start_line = false;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if let Some(rest) = line.strip_prefix("> ") {
+            request_lines += 1;
+            start_line |= RE_REQUEST_LINE.is_match(rest) && rest.contains("HTTP/");
+        } else if let Some(rest) = line.strip_prefix("< ") {
+            other_lines += 1;
+            start_line |= RE_RESPONSE_LINE.is_match(rest);
+        } else if line.starts_with("* ") {
+            other_lines += 1;
+        }
+    }
+    start_line && request_lines >= 2 && other_lines >= 1
+}
+
+/// 判断文本是否为 Python `requests` / `httpx` 片段：第一条语句（跳过注释）必须像 Python 代码，
+/// 避免原始 HTTP body 中的 `import requests` 被误判。
+fn is_python_snippet(text: &str) -> bool {
+    let first_statement = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .unwrap_or("");
+    RE_PYTHON_STATEMENT.is_match(first_statement) && RE_PYTHON_REQUESTS.is_match(text)
+}
+
+/// 判断文本是否像 `.http` 文件：`###` 分隔行或 `@name = value` 之后（跳过空行与注释）
+/// 紧跟请求行。原始 HTTP 的 body 中出现的 `### 标题` 不会被误判。
+fn is_http_file_like(text: &str) -> bool {
+    let mut after_marker = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if RE_HTTP_FILE_MARKER.is_match(line) {
+            after_marker = true;
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        if after_marker && RE_HTTP_FILE_REQUEST.is_match(line) {
+            return true;
+        }
+        after_marker = false;
+    }
+    false
+}
+
+/// 判断文本是否像 HAR 文件（顶层 JSON 对象，包含 `log.entries`）。
+fn is_har_like(text: &str) -> bool {
+    text.starts_with('{') && text.contains("\"log\"") && text.contains("\"entries\"")
+}
+
+/// 判断文本是否像 Postman 集合（`info._postman_id` 或 getpostman.com 的 schema，且包含 `item`）。
+fn is_postman_like(text: &str) -> bool {
+    text.starts_with('{')
+        && text.contains("\"item\"")
+        && (text.contains("\"_postman_id\"") || text.contains("schema.getpostman.com"))
+}
+
+/// 判断文本是否像 Insomnia 导出文件（`_type: "export"`，包含 `resources`）。
+fn is_insomnia_like(text: &str) -> bool {
+    text.starts_with('{') && text.contains("\"__export_format\"") && text.contains("\"resources\"")
+}
+
+/// 判断文本是否像 Burp Suite "Save items" 导出的 XML（根元素 `<items burpVersion=...>`）。
+fn is_burp_like(text: &str) -> bool {
+    text.starts_with('<') && text.contains("<items") && text.contains("burpVersion")
+}
+
 /// 判断文本是否像原始 HTTP 格式。
 fn is_raw_http_like(text: &str) -> bool {
     if text.len() < 10 {
@@ -97,6 +337,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_curl_windows() {
+        assert_eq!(
+            detect_input_format("curl ^\"https://example.com/^\" ^\n  -H ^\"accept: */*^\""),
+            InputFormat::Curl
+        );
+        assert_eq!(
+            detect_input_format("curl.exe 'https://example.com/' `\n  -H 'accept: */*'"),
+            InputFormat::Curl
+        );
+        assert_eq!(detect_input_format("curly braces"), InputFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_curl_verbose() {
+        let input = "*   Trying 127.0.0.1:80...\n> GET / HTTP/1.1\n> Host: localhost\n>\n< HTTP/1.1 200 OK";
+        assert_eq!(detect_input_format(input), InputFormat::CurlVerbose);
+    }
+
+    #[test]
+    fn test_detect_curl_verbose_with_command_line() {
+        let input = "curl -v http://localhost/\n> GET / HTTP/1.1\n> Host: localhost\n>\n< HTTP/1.1 200 OK";
+        assert_eq!(detect_input_format(input), InputFormat::CurlVerbose);
+    }
+
+    #[test]
+    fn test_markdown_is_not_curl_verbose() {
+        let input = "Notes\n* item one\n> quoted line\n> another quote";
+        assert_eq!(detect_input_format(input), InputFormat::Unknown);
+        assert!(!is_http_like(input));
+    }
+
     #[test]
     fn test_detect_fetch() {
         assert_eq!(
@@ -105,6 +377,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_fetch_node() {
+        assert_eq!(
+            detect_input_format(
+                "const fetch = require('node-fetch');\n\nconst res = await fetch(\"https://example.com\");"
+            ),
+            InputFormat::Fetch
+        );
+        assert_eq!(
+            detect_input_format("await fetch('https://example.com', { method: 'POST' })"),
+            InputFormat::Fetch
+        );
+    }
+
+    #[test]
+    fn test_detect_powershell() {
+        assert_eq!(
+            detect_input_format(
+                "$session = New-Object Microsoft.PowerShell.Commands.WebRequestSession\nInvoke-WebRequest -UseBasicParsing -Uri \"https://example.com\""
+            ),
+            InputFormat::PowerShell
+        );
+        assert_eq!(
+            detect_input_format("$r = irm https://example.com/api -Method Post"),
+            InputFormat::PowerShell
+        );
+        assert_eq!(detect_input_format("iwrx https://example.com"), InputFormat::Unknown);
+    }
+
+    #[test]
+    fn test_detect_python() {
+        assert_eq!(
+            detect_input_format("import requests\n\nrequests.get('https://example.com')"),
+            InputFormat::Python
+        );
+        assert_eq!(
+            detect_input_format("resp = httpx.post(url, json={'a': 1})"),
+            InputFormat::Python
+        );
+        assert_eq!(
+            detect_input_format("GET /requests.get(x) HTTP/1.1\nHost: a"),
+            InputFormat::RawHttp
+        );
+        assert_eq!(
+            detect_input_format("# fetch the page\nwith httpx.Client() as client:\n    client.get(url)\nhttpx.get(url)"),
+            InputFormat::Python
+        );
+        // 原始请求的 text body 恰好是 Python 代码
+        assert_eq!(
+            detect_input_format("POST /run HTTP/1.1\nHost: a\nContent-Type: text/plain\n\nimport requests\nrequests.get('x')"),
+            InputFormat::RawHttp
+        );
+    }
+
+    #[test]
+    fn test_detect_httpie_and_wget() {
+        assert_eq!(
+            detect_input_format("http POST :8080/api name=foo"),
+            InputFormat::Httpie
+        );
+        assert_eq!(
+            detect_input_format("https example.com/users"),
+            InputFormat::Httpie
+        );
+        assert_eq!(
+            detect_input_format("wget --post-data='a=1' https://example.com"),
+            InputFormat::Wget
+        );
+        assert_ne!(
+            detect_input_format("HTTP/1.1 200 OK\nContent-Type: text/plain"),
+            InputFormat::Httpie
+        );
+        assert_eq!(
+            detect_input_format("http --auth user:pw -f post localhost:5000/login a=1"),
+            InputFormat::Httpie
+        );
+        assert_eq!(
+            detect_input_format("xh https://api.example.com/users"),
+            InputFormat::Httpie
+        );
+        for prose in [
+            "http is the protocol of the web",
+            "https everywhere is enabled on this server",
+            "http server started, listening on port 8080",
+        ] {
+            assert_eq!(detect_input_format(prose), InputFormat::Unknown, "{prose}");
+            assert!(!is_http_like(prose), "{prose}");
+        }
+    }
+
     #[test]
     fn test_detect_fetch_with_space() {
         assert_eq!(
@@ -113,6 +475,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_http_file() {
+        assert_eq!(
+            detect_input_format("@host = https://example.com\n\nGET {{host}}/users\n"),
+            InputFormat::HttpFile
+        );
+        assert_eq!(
+            detect_input_format("### login\nPOST https://example.com/login HTTP/1.1\n\n###\nhttps://example.com/me"),
+            InputFormat::HttpFile
+        );
+        assert_eq!(
+            detect_input_format("GET https://example.com/a HTTP/1.1\n\n###\n# @name b\nGET https://example.com/b"),
+            InputFormat::HttpFile
+        );
+        // 原始请求的 body 以 Markdown 标题开头
+        assert_eq!(
+            detect_input_format("POST /notes HTTP/1.1\nHost: example.com\nContent-Type: text/markdown\n\n### heading\nsome text\n"),
+            InputFormat::RawHttp
+        );
+    }
+
+    #[test]
+    fn test_detect_har() {
+        assert_eq!(
+            detect_input_format(r#"{"log": {"version": "1.2", "entries": []}}"#),
+            InputFormat::Har
+        );
+    }
+
+    #[test]
+    fn test_detect_collections() {
+        assert_eq!(
+            detect_input_format(r#"{"info": {"_postman_id": "1", "name": "x"}, "item": []}"#),
+            InputFormat::Postman
+        );
+        assert_eq!(
+            detect_input_format(r#"{"_type": "export", "__export_format": 4, "resources": []}"#),
+            InputFormat::Insomnia
+        );
+    }
+
+    #[test]
+    fn test_detect_proxy_exports() {
+        assert_eq!(
+            detect_input_format("<?xml version=\"1.0\"?>\n<items burpVersion=\"2024.1\"></items>"),
+            InputFormat::Burp
+        );
+        assert_eq!(
+            detect_input_format("==== 1 ==========\nGET http://example.com/ HTTP/1.1\n"),
+            InputFormat::Zap
+        );
+    }
+
+    #[test]
+    fn test_detect_plain_json_not_har() {
+        assert_eq!(
+            detect_input_format(r#"{"name": "test", "value": 42}"#),
+            InputFormat::Unknown
+        );
+    }
+
     #[test]
     fn test_detect_raw_http_request() {
         assert_eq!(
@@ -129,6 +552,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detect_http2_response() {
+        assert_eq!(
+            detect_input_format("HTTP/2 200\ncontent-type: text/html"),
+            InputFormat::RawHttp
+        );
+    }
+
+    #[test]
+    fn test_detect_http3_request() {
+        assert_eq!(
+            detect_input_format("GET /index.html HTTP/3\nhost: example.com"),
+            InputFormat::RawHttp
+        );
+    }
+
+    #[test]
+    fn test_detect_pseudo_headers() {
+        assert_eq!(
+            detect_input_format(":method: GET\n:path: /\n:authority: example.com"),
+            InputFormat::RawHttp
+        );
+    }
+
+    #[test]
+    fn test_detect_extension_methods() {
+        for line in [
+            "PROPFIND /dav/ HTTP/1.1\nDepth: 1",
+            "MKCOL /dav/new/ HTTP/1.1\nHost: dav.example.com",
+            "PURGE /cache/item\nHost: cdn.example.com",
+            "OPTIONS * HTTP/1.1\nHost: example.com",
+            "CONNECT example.com:443 HTTP/1.1\nHost: example.com:443",
+            "GET http://example.com/ HTTP/1.1\nHost: example.com",
+        ] {
+            assert_eq!(detect_input_format(line), InputFormat::RawHttp, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_detect_two_word_prose_not_request() {
+        assert_eq!(detect_input_format("Hello world"), InputFormat::Unknown);
+        assert_eq!(detect_input_format("Meeting tomorrow"), InputFormat::Unknown);
+    }
+
     #[test]
     fn test_detect_unknown() {
         assert_eq!(
@@ -1,201 +1,116 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::js_literal::{self, JsValue};
 use crate::models::{HttpContentType, ParseNode, ParseResult};
-use crate::parse_utils;
+use crate::parse_utils::{self, empty_result, header_node};
+use crate::query_tree;
+
+static RE_FETCH_CALL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bfetch\s*\(").unwrap());
+
+/// 作为元数据展示的 RequestInit 字段（不会出现在报文中）。
+const METADATA_FIELDS: &[&str] = &["referrer", "referrerPolicy", "mode", "credentials", "cache"];
 
-/// 解析浏览器 DevTools "Copy as fetch" 格式的文本。
+/// 解析浏览器 DevTools "Copy as fetch" / "Copy as fetch (Node.js)" 格式的文本。
+/// options 按 JS 字面量宽松读取：支持无引号 key、单引号、模板字符串、尾随逗号、
+/// `JSON.stringify(...)`、`new Headers(...)` 与 `new URLSearchParams(...)`。
 pub fn parse_fetch(input: &str) -> ParseResult {
     let raw_text = input.to_string();
-    let trimmed = input.trim();
 
-    // 提取 fetch(...) 内部内容
-    let inner = match extract_fetch_inner(trimmed) {
-        Some(s) => s,
-        None => {
-            return empty_result(raw_text);
+    // 提取 fetch(...) 的参数
+    let Some(found) = RE_FETCH_CALL.find(input) else {
+        return empty_result(raw_text);
+    };
+    let args = js_literal::parse_call_arguments(&input[found.end()..]);
+    let Some(url_str) = args.first().and_then(JsValue::to_text) else {
+        return empty_result(raw_text);
+    };
+    let options = args.get(1);
+    let field = |name: &str| options.and_then(|o| o.get(name));
+
+    // 提取 method，标准方法名按 fetch 规范归一化为大写
+    let method = field("method")
+        .and_then(JsValue::to_text)
+        .map(|m| normalize_method(&m))
+        .unwrap_or_else(|| "GET".to_string());
+
+    // 提取 headers：普通对象或 `[[name, value], ...]`
+    let mut headers: Vec<ParseNode> = match field("headers") {
+        Some(JsValue::Object(entries)) => entries
+            .iter()
+            .map(|(key, val)| header_node(key, &val.to_text().unwrap_or_default()))
+            .collect(),
+        Some(JsValue::Array(pairs)) => pairs
+            .iter()
+            .filter_map(|pair| match pair {
+                JsValue::Array(pair) if pair.len() == 2 => Some(header_node(
+                    &pair[0].to_text().unwrap_or_default(),
+                    &pair[1].to_text().unwrap_or_default(),
+                )),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    // 提取 body；URLSearchParams 在未显式设置时隐含表单 Content-Type
+    let body = match field("body") {
+        Some(JsValue::SearchParams(encoded)) => {
+            if parse_utils::header_value(&headers, "content-type").is_none() {
+                headers.push(header_node(
+                    "content-type",
+                    "application/x-www-form-urlencoded;charset=UTF-8",
+                ));
+            }
+            Some(encoded.clone())
         }
+        Some(value) => value.to_text(),
+        None => None,
     };
 
-    // 分离 URL 和 options
-    let (url_str, options_json) = split_url_and_options(inner);
-
-    // 解析 options JSON
-    let options: Option<serde_json::Value> = options_json
-        .and_then(|json_str| serde_json::from_str(json_str).ok());
-
-    // 提取 method
-    let method = options
-        .as_ref()
-        .and_then(|o| o.get("method"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("GET")
-        .to_string();
-
-    // 提取 headers
-    let headers = options
-        .as_ref()
-        .and_then(|o| o.get("headers"))
-        .and_then(|v| v.as_object())
-        .map(|obj| {
-            obj.iter()
-                .map(|(key, val)| {
-                    let value = val.as_str().unwrap_or("").to_string();
-                    let children = parse_utils::parse_header_value_children(key, &value);
-                    ParseNode {
-                        key: key.clone(),
-                        value,
-                        children,
-                        description: None,
-                        decoded_value: None,
-                        value_type: None,
-                    }
+    let metadata: Vec<ParseNode> = match options {
+        Some(JsValue::Object(entries)) => entries
+            .iter()
+            .filter(|(key, _)| METADATA_FIELDS.contains(&key.as_str()))
+            .filter_map(|(key, val)| {
+                Some(ParseNode {
+                    key: key.clone(),
+                    value: val.to_text()?,
+                    ..Default::default()
                 })
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default();
-
-    // 提取 body
-    let body = options
-        .as_ref()
-        .and_then(|o| o.get("body"))
-        .and_then(|v| {
-            if v.is_null() {
-                None
-            } else {
-                // body 可能是字符串或其他 JSON 值
-                v.as_str()
-                    .map(|s| s.to_string())
-                    .or_else(|| Some(v.to_string()))
-            }
-        });
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
 
     // 解析 URL query params
     let query_params = parse_utils::parse_query_params(&url_str);
+    let query_tree = query_params.as_deref().and_then(query_tree::build_query_tree);
+    let url_nodes = parse_utils::parse_url_nodes(&url_str);
 
     ParseResult {
         content_type: HttpContentType::Request,
         method: Some(method),
         url: Some(url_str),
-        status_code: None,
-        status_text: None,
-        protocol: None,
+        url_nodes,
         headers,
         query_params,
+        query_tree,
         body,
+        metadata: (!metadata.is_empty()).then_some(metadata),
         raw_text,
+        ..Default::default()
     }
 }
 
-/// 提取 `fetch(` 和最后一个 `)` 之间的内容。
-/// 支持末尾带或不带分号。
-fn extract_fetch_inner(input: &str) -> Option<&str> {
-    // 找到 fetch( 的位置
-    let start = input.find("fetch(")? + "fetch(".len();
-    // 找到最后一个 ) 的位置（去掉末尾分号后）
-    let trimmed = input.trim_end_matches(';').trim_end();
-    let end = trimmed.rfind(')')?;
-    if end < start {
-        return None;
-    }
-    Some(&trimmed[start..end])
-}
-
-/// 将 fetch 内部参数分离为 URL 字符串和可选的 options JSON。
-/// 输入形如: `"url", {...}` 或 `"url"`
-fn split_url_and_options(inner: &str) -> (String, Option<&str>) {
-    let trimmed = inner.trim();
-
-    // URL 是第一个引号包裹的字符串
-    let url_str = extract_quoted_string(trimmed);
-
-    // 找到 URL 字符串结束后的位置
-    // 寻找第一个引号对之后的逗号
-    let after_url = skip_quoted_string(trimmed);
-
-    let options = after_url.and_then(|rest| {
-        // 跳过逗号和空白
-        let rest = rest.trim_start_matches(',').trim();
-        if rest.is_empty() {
-            None
-        } else {
-            Some(rest)
-        }
-    });
-
-    (url_str, options)
-}
-
-/// 提取第一个引号包裹的字符串内容（处理转义引号）。
-fn extract_quoted_string(input: &str) -> String {
-    let trimmed = input.trim();
-    if !trimmed.starts_with('"') && !trimmed.starts_with('\'') {
-        // 没有引号，取到逗号或结尾
-        return trimmed
-            .split(',')
-            .next()
-            .unwrap_or(trimmed)
-            .trim()
-            .to_string();
-    }
-
-    let quote_char = trimmed.chars().next().unwrap();
-    let mut chars = trimmed.chars();
-    chars.next(); // consume opening quote
-    let mut result = String::new();
-
-    while let Some(ch) = chars.next() {
-        if ch == '\\' {
-            if let Some(escaped) = chars.next() {
-                result.push(escaped);
-            }
-        } else if ch == quote_char {
-            break;
-        } else {
-            result.push(ch);
-        }
-    }
-
-    result
-}
-
-/// 跳过第一个引号包裹的字符串，返回剩余部分。
-fn skip_quoted_string(input: &str) -> Option<&str> {
-    let trimmed = input.trim();
-    if !trimmed.starts_with('"') && !trimmed.starts_with('\'') {
-        // 没有引号包裹，找逗号
-        return trimmed.find(',').map(|pos| &trimmed[pos..]);
-    }
-
-    let quote_char = trimmed.chars().next()?;
-    let mut byte_offset = quote_char.len_utf8(); // skip opening quote
-
-    for ch in trimmed[byte_offset..].chars() {
-        byte_offset += ch.len_utf8();
-        if ch == '\\' {
-            // skip next char (escape)
-            if let Some(escaped) = trimmed[byte_offset..].chars().next() {
-                byte_offset += escaped.len_utf8();
-            }
-        } else if ch == quote_char {
-            return Some(&trimmed[byte_offset..]);
-        }
-    }
-
-    None
-}
-
-/// 返回一个空的 ParseResult（解析失败时使用）。
-fn empty_result(raw_text: String) -> ParseResult {
-    ParseResult {
-        content_type: HttpContentType::Unknown,
-        method: None,
-        url: None,
-        status_code: None,
-        status_text: None,
-        protocol: None,
-        headers: vec![],
-        query_params: None,
-        body: None,
-        raw_text,
-    }
+/// fetch 会把大小写不同的标准方法名归一化为大写，其余方法保持原样。
+fn normalize_method(method: &str) -> String {
+    const NORMALIZED: &[&str] = &["DELETE", "GET", "HEAD", "OPTIONS", "POST", "PUT"];
+    NORMALIZED
+        .iter()
+        .find(|m| m.eq_ignore_ascii_case(method))
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| method.to_string())
 }
 
 #[cfg(test)]
@@ -385,4 +300,91 @@ mod tests {
         assert!(matches!(result.content_type, HttpContentType::Unknown));
         assert!(result.method.is_none());
     }
+
+    #[test]
+    fn test_js_object_literal_options() {
+        let input = r#"fetch('https://example.com/api?a=1', {
+  method: 'post',
+  headers: {
+    Accept: 'application/json',
+    'Content-Type': `application/json`,
+  },
+  body: JSON.stringify({ name: "test", tags: ['a', 'b',], }),
+});"#;
+
+        let result = parse_fetch(input);
+
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api?a=1"));
+        assert_eq!(result.headers.len(), 2);
+        assert_eq!(result.headers[0].key, "Accept");
+        assert_eq!(result.headers[1].value, "application/json");
+        assert_eq!(
+            result.body.as_deref(),
+            Some(r#"{"name":"test","tags":["a","b"]}"#)
+        );
+    }
+
+    #[test]
+    fn test_headers_and_url_search_params() {
+        let input = r#"fetch("https://example.com/login", {
+  method: "POST",
+  headers: new Headers([["x-token", "abc"]]),
+  body: new URLSearchParams({ user: "a b", remember: 1 }),
+})"#;
+
+        let result = parse_fetch(input);
+
+        assert_eq!(result.headers[0].key, "x-token");
+        assert_eq!(result.headers[1].key, "content-type");
+        assert_eq!(
+            result.headers[1].value,
+            "application/x-www-form-urlencoded;charset=UTF-8"
+        );
+        assert_eq!(result.body.as_deref(), Some("user=a+b&remember=1"));
+    }
+
+    #[test]
+    fn test_request_init_metadata() {
+        let input = r#"fetch("https://example.com/api", {
+  "headers": {
+    "accept": "*/*"
+  },
+  "referrer": "https://example.com/page",
+  "referrerPolicy": "strict-origin-when-cross-origin",
+  "body": null,
+  "method": "GET",
+  "mode": "cors",
+  "credentials": "include"
+});"#;
+
+        let result = parse_fetch(input);
+
+        let metadata = result.metadata.unwrap();
+        let keys: Vec<&str> = metadata.iter().map(|n| n.key.as_str()).collect();
+        assert_eq!(keys, vec!["referrer", "referrerPolicy", "mode", "credentials"]);
+        assert_eq!(metadata[0].value, "https://example.com/page");
+        assert_eq!(metadata[3].value, "include");
+    }
+
+    #[test]
+    fn test_node_variant() {
+        let input = r#"const fetch = require("node-fetch");
+
+const res = await fetch("https://example.com/api", {
+  "headers": {
+    "accept": "application/json",
+    "Referer": "https://example.com/"
+  },
+  "body": "{\"id\":1}",
+  "method": "PUT"
+}).then((r) => r.json());"#;
+
+        let result = parse_fetch(input);
+
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api"));
+        assert_eq!(result.headers.len(), 2);
+        assert_eq!(result.body.as_deref(), Some(r#"{"id":1}"#));
+    }
 }
@@ -0,0 +1,432 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use serde_json::Value;
+
+use crate::models::{HttpContentType, HttpExchange, ParseNode, ParseResult};
+use crate::parse_utils::{self, str_field};
+use crate::query_tree;
+
+/// 解析 DevTools "Save all as HAR" 导出的 HAR 文件，每个 `log.entries[]` 生成一个请求/响应对。
+pub fn parse_har(input: &str) -> Vec<HttpExchange> {
+    let root: Value = match serde_json::from_str(input.trim()) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    let entries = match root
+        .get("log")
+        .and_then(|log| log.get("entries"))
+        .and_then(|e| e.as_array())
+    {
+        Some(arr) => arr,
+        None => return Vec::new(),
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let request = entry.get("request").map(parse_har_request)?;
+            let response = entry.get("response").and_then(parse_har_response);
+            Some(HttpExchange {
+                request: Some(request),
+                response,
+                started_at: str_field(entry, "startedDateTime"),
+                duration_ms: entry.get("time").and_then(|v| v.as_f64()),
+            })
+        })
+        .collect()
+}
+
+/// 将 HAR 的 `request` 对象转换为 ParseResult。
+fn parse_har_request(req: &Value) -> ParseResult {
+    let method = str_field(req, "method").map(|m| m.to_uppercase());
+    let url_str = str_field(req, "url");
+
+    let (mut headers, pseudo_headers) = parse_utils::split_pseudo_headers(har_headers(req));
+    if !has_header(&headers, "cookie") {
+        // 部分导出工具会省略 Cookie header，只保留 cookies 数组
+        if let Some(cookie_str) = har_cookie_string(req) {
+            let children = parse_utils::parse_cookie_children(&cookie_str);
+            headers.push(ParseNode {
+                key: "Cookie".to_string(),
+                value: cookie_str,
+                children,
+                ..Default::default()
+            });
+        }
+    }
+
+    let query_params = url_str
+        .as_ref()
+        .and_then(|u| parse_utils::parse_query_params(u))
+        .or_else(|| har_name_value_nodes(req.get("queryString")));
+
+    let query_tree = query_params.as_deref().and_then(query_tree::build_query_tree);
+    let url_nodes = url_str.as_ref().and_then(|u| parse_utils::parse_url_nodes(u));
+
+    ParseResult {
+        content_type: HttpContentType::Request,
+        method,
+        url: url_str,
+        url_nodes,
+        protocol: har_protocol(req),
+        headers,
+        pseudo_headers,
+        query_params,
+        query_tree,
+        body: req.get("postData").and_then(har_post_data_text),
+        raw_text: serde_json::to_string_pretty(req).unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// 将 HAR 的 `response` 对象转换为 ParseResult。
+/// status 为 0 且没有任何 header 的响应（请求被取消或拦截）视为不存在。
+fn parse_har_response(resp: &Value) -> Option<ParseResult> {
+    let status_code = resp
+        .get("status")
+        .and_then(|v| v.as_u64())
+        .and_then(|n| u16::try_from(n).ok());
+    let (headers, pseudo_headers) = parse_utils::split_pseudo_headers(har_headers(resp));
+
+    if status_code.unwrap_or(0) == 0 && headers.is_empty() {
+        return None;
+    }
+
+    Some(ParseResult {
+        content_type: HttpContentType::Response,
+        status_code,
+        status_text: str_field(resp, "statusText"),
+        protocol: har_protocol(resp),
+        headers,
+        pseudo_headers,
+        body: resp.get("content").and_then(har_content_text),
+        raw_text: serde_json::to_string_pretty(resp).unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+/// 将 `headers: [{name, value}]` 转换为 header 节点，Cookie/Set-Cookie 自动拆解。
+fn har_headers(obj: &Value) -> Vec<ParseNode> {
+    obj.get("headers")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|h| {
+                    let key = str_field(h, "name")?;
+                    let value = str_field(h, "value").unwrap_or_default();
+                    let children = parse_utils::parse_header_value_children(&key, &value);
+                    Some(ParseNode {
+                        key,
+                        value,
+                        children,
+                        ..Default::default()
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 将 `[{name, value}]` 数组转换为节点列表，空数组返回 None。
+fn har_name_value_nodes(arr: Option<&Value>) -> Option<Vec<ParseNode>> {
+    let nodes: Vec<ParseNode> = arr?
+        .as_array()?
+        .iter()
+        .filter_map(|item| {
+            Some(ParseNode {
+                key: str_field(item, "name")?,
+                value: str_field(item, "value").unwrap_or_default(),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    if nodes.is_empty() {
+        None
+    } else {
+        Some(nodes)
+    }
+}
+
+/// 从 `cookies` 数组拼接 `name=value; name2=value2` 形式的 Cookie 字符串。
+fn har_cookie_string(req: &Value) -> Option<String> {
+    let pairs: Vec<String> = req
+        .get("cookies")?
+        .as_array()?
+        .iter()
+        .filter_map(|c| {
+            let name = str_field(c, "name")?;
+            let value = str_field(c, "value").unwrap_or_default();
+            Some(format!("{name}={value}"))
+        })
+        .collect();
+
+    if pairs.is_empty() {
+        None
+    } else {
+        Some(pairs.join("; "))
+    }
+}
+
+/// 提取 `postData`：优先使用 `text`，否则将 `params` 拼成 urlencoded 形式。
+fn har_post_data_text(post_data: &Value) -> Option<String> {
+    if let Some(text) = str_field(post_data, "text") {
+        if !text.is_empty() {
+            return Some(text);
+        }
+    }
+
+    let params: Vec<String> = post_data
+        .get("params")?
+        .as_array()?
+        .iter()
+        .filter_map(|p| {
+            let name = str_field(p, "name")?;
+            match str_field(p, "fileName") {
+                Some(file_name) => Some(format!("{name}=@{file_name}")),
+                None => Some(format!(
+                    "{name}={}",
+                    str_field(p, "value").unwrap_or_default()
+                )),
+            }
+        })
+        .collect();
+
+    if params.is_empty() {
+        None
+    } else {
+        Some(params.join("&"))
+    }
+}
+
+/// 提取响应 `content.text`。`encoding: "base64"` 的内容若能解码为 UTF-8 则返回解码文本，
+/// 否则（二进制内容）保留原始 base64 文本。
+fn har_content_text(content: &Value) -> Option<String> {
+    let text = str_field(content, "text")?;
+    if text.is_empty() {
+        return None;
+    }
+
+    let is_base64 = str_field(content, "encoding")
+        .map(|e| e.eq_ignore_ascii_case("base64"))
+        .unwrap_or(false);
+    if !is_base64 {
+        return Some(text);
+    }
+
+    match general_purpose::STANDARD.decode(text.trim()) {
+        Ok(bytes) => Some(String::from_utf8(bytes).unwrap_or(text)),
+        Err(_) => Some(text),
+    }
+}
+
+/// 规范化 `httpVersion`（如 `http/2.0` → `HTTP/2.0`，ALPN 标识 `h2` / `h3` → `HTTP/2` / `HTTP/3`），
+/// 空字符串视为未知。
+fn har_protocol(obj: &Value) -> Option<String> {
+    str_field(obj, "httpVersion")
+        .filter(|v| !v.is_empty())
+        .map(|v| match v.to_ascii_lowercase().as_str() {
+            "h2" => "HTTP/2".to_string(),
+            "h3" => "HTTP/3".to_string(),
+            _ => v.to_uppercase(),
+        })
+}
+
+fn has_header(headers: &[ParseNode], name: &str) -> bool {
+    headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_HAR: &str = r#"{
+  "log": {
+    "version": "1.2",
+    "creator": { "name": "WebInspector", "version": "537.36" },
+    "entries": [
+      {
+        "startedDateTime": "2026-02-08T04:00:00.000Z",
+        "time": 123.5,
+        "request": {
+          "method": "GET",
+          "url": "https://example.com/api/users?page=1&limit=10",
+          "httpVersion": "http/2.0",
+          "headers": [
+            { "name": ":authority", "value": "example.com" },
+            { "name": ":method", "value": "GET" },
+            { "name": "accept", "value": "application/json" },
+            { "name": "cookie", "value": "session=abc; theme=dark" }
+          ],
+          "queryString": [
+            { "name": "page", "value": "1" },
+            { "name": "limit", "value": "10" }
+          ],
+          "cookies": [
+            { "name": "session", "value": "abc" },
+            { "name": "theme", "value": "dark" }
+          ]
+        },
+        "response": {
+          "status": 200,
+          "statusText": "OK",
+          "httpVersion": "http/2.0",
+          "headers": [
+            { "name": "content-type", "value": "application/json" },
+            { "name": "set-cookie", "value": "token=xyz; Path=/; HttpOnly" }
+          ],
+          "content": {
+            "size": 15,
+            "mimeType": "application/json",
+            "text": "eyJvayI6dHJ1ZX0=",
+            "encoding": "base64"
+          }
+        }
+      },
+      {
+        "startedDateTime": "2026-02-08T04:00:01.000Z",
+        "time": 50,
+        "request": {
+          "method": "post",
+          "url": "https://example.com/api/login",
+          "httpVersion": "HTTP/1.1",
+          "headers": [
+            { "name": "Content-Type", "value": "application/x-www-form-urlencoded" }
+          ],
+          "cookies": [
+            { "name": "sid", "value": "123" }
+          ],
+          "postData": {
+            "mimeType": "application/x-www-form-urlencoded",
+            "params": [
+              { "name": "user", "value": "alice" },
+              { "name": "pass", "value": "secret" }
+            ]
+          }
+        },
+        "response": {
+          "status": 0,
+          "statusText": "",
+          "httpVersion": "",
+          "headers": [],
+          "content": { "size": 0, "mimeType": "x-unknown" }
+        }
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn test_parse_all_entries() {
+        let entries = parse_har(SAMPLE_HAR);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].started_at.as_deref(),
+            Some("2026-02-08T04:00:00.000Z")
+        );
+        assert_eq!(entries[0].duration_ms, Some(123.5));
+    }
+
+    #[test]
+    fn test_request_fields() {
+        let entries = parse_har(SAMPLE_HAR);
+        let req = entries[0].request.as_ref().unwrap();
+
+        assert!(matches!(req.content_type, HttpContentType::Request));
+        assert_eq!(req.method.as_deref(), Some("GET"));
+        assert_eq!(
+            req.url.as_deref(),
+            Some("https://example.com/api/users?page=1&limit=10")
+        );
+        assert_eq!(req.protocol.as_deref(), Some("HTTP/2.0"));
+        assert_eq!(req.headers.len(), 2);
+        assert_eq!(req.pseudo_headers.as_ref().unwrap().len(), 2);
+
+        let params = req.query_params.as_ref().unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0].key, "page");
+        assert_eq!(params[0].value, "1");
+    }
+
+    #[test]
+    fn test_request_cookie_header_children() {
+        let entries = parse_har(SAMPLE_HAR);
+        let cookie = entries[0]
+            .request
+            .as_ref()
+            .unwrap()
+            .headers
+            .iter()
+            .find(|h| h.key == "cookie")
+            .unwrap();
+        let children = cookie.children.as_ref().unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].key, "session");
+    }
+
+    #[test]
+    fn test_cookie_header_synthesized_from_cookies_array() {
+        let entries = parse_har(SAMPLE_HAR);
+        let req = entries[1].request.as_ref().unwrap();
+        let cookie = req.headers.iter().find(|h| h.key == "Cookie").unwrap();
+        assert_eq!(cookie.value, "sid=123");
+        assert_eq!(cookie.children.as_ref().unwrap()[0].key, "sid");
+    }
+
+    #[test]
+    fn test_post_data_params() {
+        let entries = parse_har(SAMPLE_HAR);
+        let req = entries[1].request.as_ref().unwrap();
+        assert_eq!(req.method.as_deref(), Some("POST"));
+        assert_eq!(req.body.as_deref(), Some("user=alice&pass=secret"));
+    }
+
+    #[test]
+    fn test_response_base64_content_decoded() {
+        let entries = parse_har(SAMPLE_HAR);
+        let resp = entries[0].response.as_ref().unwrap();
+
+        assert!(matches!(resp.content_type, HttpContentType::Response));
+        assert_eq!(resp.status_code, Some(200));
+        assert_eq!(resp.status_text.as_deref(), Some("OK"));
+        assert_eq!(resp.body.as_deref(), Some(r#"{"ok":true}"#));
+
+        let set_cookie = resp.headers.iter().find(|h| h.key == "set-cookie").unwrap();
+        assert_eq!(set_cookie.children.as_ref().unwrap()[0].key, "token");
+    }
+
+    #[test]
+    fn test_aborted_response_is_none() {
+        let entries = parse_har(SAMPLE_HAR);
+        assert!(entries[1].response.is_none());
+    }
+
+    #[test]
+    fn test_binary_base64_content_kept() {
+        let content = serde_json::json!({
+            "mimeType": "image/png",
+            "text": "iVBORw0KGgoAAAANSUhEUg==",
+            "encoding": "base64"
+        });
+        assert_eq!(
+            har_content_text(&content).as_deref(),
+            Some("iVBORw0KGgoAAAANSUhEUg==")
+        );
+    }
+
+    #[test]
+    fn test_alpn_http_version() {
+        let protocol = |v: &str| har_protocol(&serde_json::json!({ "httpVersion": v }));
+        assert_eq!(protocol("h3").as_deref(), Some("HTTP/3"));
+        assert_eq!(protocol("h2").as_deref(), Some("HTTP/2"));
+        assert_eq!(protocol("http/3").as_deref(), Some("HTTP/3"));
+        assert_eq!(protocol("").as_deref(), None);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(parse_har("not json").is_empty());
+        assert!(parse_har(r#"{"foo": 1}"#).is_empty());
+    }
+}
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 
 use crate::error::AppError;
-use crate::models::ParseResult;
+use crate::models::{HttpExchange, ParseResult};
 
 const MAX_ENTRIES: usize = 100;
 
@@ -17,6 +17,9 @@ pub struct HistoryEntry {
     pub title: String,
     pub raw_text: String,
     pub parse_result: ParseResult,
+    /// 原文解析出的全部请求/响应对（旧版本历史记录中不存在，默认为空）
+    #[serde(default)]
+    pub exchanges: Vec<HttpExchange>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -27,6 +30,7 @@ pub struct HistoryEntrySummary {
     pub title: String,
     pub method: Option<String>,
     pub url: Option<String>,
+    pub status_code: Option<u16>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -89,22 +93,32 @@ fn to_summary(entry: &HistoryEntry) -> HistoryEntrySummary {
         title: entry.title.clone(),
         method: entry.parse_result.method.clone(),
         url: entry.parse_result.url.clone(),
+        status_code: entry
+            .exchanges
+            .iter()
+            .find_map(|e| e.response.as_ref())
+            .and_then(|r| r.status_code),
         created_at: entry.created_at,
     }
 }
 
+/// 保存前端已得到的解析结果，不再重新解析原文；未传 exchanges 时以 parse_result 作为唯一的交互。
 #[tauri::command]
 pub fn history_save(
     app: AppHandle,
     raw_text: String,
     parse_result: ParseResult,
+    exchanges: Option<Vec<HttpExchange>>,
 ) -> Result<HistoryEntry, AppError> {
     let now = Utc::now();
+    let exchanges =
+        exchanges.unwrap_or_else(|| vec![HttpExchange::from_message(parse_result.clone())]);
     let entry = HistoryEntry {
         id: now.timestamp_millis().to_string(),
         title: generate_title(&parse_result),
         raw_text,
         parse_result,
+        exchanges,
         created_at: now,
     };
 
@@ -0,0 +1,245 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::models::{HttpExchange, ParseResult};
+use crate::parse_utils::{self, metadata_node};
+use crate::parser;
+
+/// 文件变量定义：`@baseUrl = https://example.com`
+static RE_VARIABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^@([\w.-]+)\s*=\s*(.*?)\s*$").unwrap());
+
+/// 请求名称注释：`# @name login` / `// @name login`
+static RE_NAME_COMMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:#|//)\s*@name\s+(\S+)").unwrap());
+
+/// 解析 VS Code REST Client / JetBrains HTTP Client 的 `.http` 文件。
+/// 以 `###` 分隔多个请求，`@name = value` 定义文件级变量并替换 `{{name}}`，
+/// 每个请求块交给 `parser::parse_http_text` 解析；无法解析的变量保留原文并记入 warnings。
+pub fn parse_http_file(input: &str) -> Vec<HttpExchange> {
+    let variables = collect_variables(input);
+    split_blocks(input)
+        .into_iter()
+        .filter_map(|(title, block)| parse_block(title, &block, &variables))
+        .map(HttpExchange::from_message)
+        .collect()
+}
+
+/// 收集整个文件中的变量定义（REST Client 中文件变量对所有请求可见），后定义的覆盖先定义的。
+fn collect_variables(input: &str) -> HashMap<String, String> {
+    input
+        .lines()
+        .filter_map(|line| RE_VARIABLE.captures(line.trim()))
+        .map(|caps| (caps[1].to_string(), caps[2].to_string()))
+        .collect()
+}
+
+/// 按 `###` 行切分请求块，`###` 之后的文字作为块标题。
+fn split_blocks(input: &str) -> Vec<(Option<String>, Vec<&str>)> {
+    let mut blocks: Vec<(Option<String>, Vec<&str>)> = vec![(None, Vec::new())];
+    for line in input.lines() {
+        if let Some(title) = line.trim_start().strip_prefix("###") {
+            let title = title.trim().trim_start_matches('#').trim();
+            blocks.push(((!title.is_empty()).then(|| title.to_string()), Vec::new()));
+        } else if let Some((_, lines)) = blocks.last_mut() {
+            lines.push(line);
+        }
+    }
+    blocks
+}
+
+/// 解析单个请求块：去掉变量定义、注释与响应处理脚本，替换变量，补全请求行。
+fn parse_block(
+    title: Option<String>,
+    lines: &[&str],
+    variables: &HashMap<String, String>,
+) -> Option<ParseResult> {
+    let mut name = title;
+    let mut kept: Vec<&str> = Vec::new();
+    let mut in_body = false;
+    let mut in_handler = false;
+
+    for line in lines {
+        let trimmed = line.trim();
+        if in_handler {
+            in_handler = !trimmed.contains("%}");
+            continue;
+        }
+        // JetBrains 的响应处理脚本与响应输出重定向不属于请求
+        if trimmed.starts_with("> {%") {
+            in_handler = !trimmed.contains("%}");
+            continue;
+        }
+        if trimmed.starts_with(">>") || trimmed.starts_with("<>") {
+            continue;
+        }
+        if !in_body {
+            if RE_VARIABLE.is_match(trimmed) {
+                continue;
+            }
+            if let Some(caps) = RE_NAME_COMMENT.captures(trimmed) {
+                name = Some(caps[1].to_string());
+                continue;
+            }
+            if trimmed.starts_with('#') || trimmed.starts_with("//") {
+                continue;
+            }
+            if trimmed.is_empty() {
+                // 请求行之前的空行忽略，之后的第一个空行分隔 headers 与 body
+                if !kept.is_empty() {
+                    in_body = true;
+                    kept.push(line);
+                }
+                continue;
+            }
+        }
+        kept.push(line);
+    }
+
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+    if kept.is_empty() {
+        return None;
+    }
+
+    let mut warnings: Vec<String> = Vec::new();
+    let text =
+        parse_utils::substitute_variables(&kept.join("\n"), variables, &mut warnings);
+    let text = normalize_request_line(&text);
+
+    let mut result = parser::parse_http_text(&text);
+    if let Some(name) = name {
+        result
+            .metadata
+            .get_or_insert_with(Vec::new)
+            .push(metadata_node("name", &name));
+    }
+    if !warnings.is_empty() {
+        result.warnings = Some(warnings);
+    }
+    Some(result)
+}
+
+/// 补全请求行：只写 URL 时默认为 GET；将紧随其后以 `?` / `&` 开头的行拼接到 URL 的查询字符串。
+fn normalize_request_line(text: &str) -> String {
+    let mut lines = text.lines().peekable();
+    let first = lines.next().unwrap_or("").trim();
+    let mut request_line = if first.contains("://") && !first.contains(' ') {
+        format!("GET {first}")
+    } else {
+        first.to_string()
+    };
+
+    while let Some(next) = lines.peek() {
+        let next = next.trim();
+        if !(next.starts_with('?') || next.starts_with('&')) {
+            break;
+        }
+        // 拼接到 URL 之后、HTTP 版本之前
+        match request_line.rfind(" HTTP/") {
+            Some(pos) => request_line.insert_str(pos, next),
+            None => request_line.push_str(next),
+        }
+        lines.next();
+    }
+
+    std::iter::once(request_line)
+        .chain(lines.map(|l| l.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpContentType;
+
+    const FILE: &str = r#"@host = api.example.com
+@baseUrl = https://{{host}}/v1
+@token = abc123
+
+### List users
+GET {{baseUrl}}/users
+    ?page=1
+    &limit=20
+Accept: application/json
+Authorization: Bearer {{token}}
+
+###
+
+# @name createUser
+// a comment line
+POST {{baseUrl}}/users HTTP/1.1
+Content-Type: application/json
+
+{
+  "name": "{{userName}}",
+  "id": "{{$guid}}"
+}
+
+> {%
+    client.global.set("id", response.body.id);
+%}
+
+###
+https://example.com/health
+"#;
+
+    fn request(exchange: &HttpExchange) -> &ParseResult {
+        exchange.request.as_ref().unwrap()
+    }
+
+    #[test]
+    fn test_splits_requests_and_resolves_variables() {
+        let exchanges = parse_http_file(FILE);
+        assert_eq!(exchanges.len(), 3);
+
+        let list = request(&exchanges[0]);
+        assert!(matches!(list.content_type, HttpContentType::Request));
+        assert_eq!(list.method.as_deref(), Some("GET"));
+        assert_eq!(
+            list.url.as_deref(),
+            Some("https://api.example.com/v1/users?page=1&limit=20")
+        );
+        assert_eq!(list.headers[1].value, "Bearer abc123");
+        assert_eq!(list.metadata.as_ref().unwrap()[0].value, "List users");
+        assert!(list.warnings.is_none());
+
+        let health = request(&exchanges[2]);
+        assert_eq!(health.method.as_deref(), Some("GET"));
+        assert_eq!(health.url.as_deref(), Some("https://example.com/health"));
+    }
+
+    #[test]
+    fn test_named_request_body_and_warnings() {
+        let exchanges = parse_http_file(FILE);
+        let create = request(&exchanges[1]);
+
+        assert_eq!(create.method.as_deref(), Some("POST"));
+        assert_eq!(create.protocol.as_deref(), Some("HTTP/1.1"));
+        assert_eq!(create.metadata.as_ref().unwrap()[0].value, "createUser");
+        let body = create.body.as_deref().unwrap();
+        assert!(body.starts_with('{') && body.ends_with('}'));
+        assert!(!body.contains("client.global"));
+        assert_eq!(
+            create.warnings.as_deref().unwrap(),
+            [
+                "Unresolved variable: {{userName}}".to_string(),
+                "Dynamic variable not evaluated: {{$guid}}".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variable_only_blocks_are_skipped() {
+        let exchanges =
+            parse_http_file("@a = 1\n###\n# just a note\n###\nGET https://x.test/{{a}}");
+        assert_eq!(exchanges.len(), 1);
+        assert_eq!(
+            request(&exchanges[0]).url.as_deref(),
+            Some("https://x.test/1")
+        );
+    }
+}
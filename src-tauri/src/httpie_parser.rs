@@ -0,0 +1,545 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+
+use crate::js_literal::{self, JsValue};
+use crate::models::{HttpContentType, ParseNode, ParseResult};
+use crate::multipart::{self, FormPart, FORM_BOUNDARY};
+use crate::parse_utils::{self, empty_result, header_node, metadata_node};
+use crate::py_literal;
+use crate::query_tree;
+use crate::shell_words::{self, ShellStyle};
+
+/// 需要参数的选项（可写作 `--flag value` 或 `--flag=value`）
+pub(crate) const VALUE_OPTIONS: &[&str] = &[
+    "-a",
+    "--auth",
+    "-A",
+    "--auth-type",
+    "-p",
+    "--print",
+    "-o",
+    "--output",
+    "-s",
+    "--style",
+    "--pretty",
+    "--session",
+    "--session-read-only",
+    "--timeout",
+    "--proxy",
+    "--cert",
+    "--cert-key",
+    "--cert-key-pass",
+    "--verify",
+    "--max-redirects",
+    "--raw",
+    "--boundary",
+    "--format-options",
+    "--response-charset",
+    "--response-mime",
+    "--ssl",
+    "--ciphers",
+    "--default-scheme",
+    "-P",
+    "--history-print",
+];
+
+/// request item 的分隔符，同一位置优先匹配更长的分隔符
+const SEPARATORS: &[&str] = &[":=@", "=@", "==", ":=", ":", "=", "@", ";"];
+
+/// JSON 字段路径中数组下标的上限，更大的下标按对象 key 处理（否则中间要补齐大量 null）
+const MAX_ARRAY_INDEX: usize = 1024;
+
+/// 请求体的编码方式
+#[derive(PartialEq)]
+enum BodyMode {
+    Json,
+    Form,
+    Multipart,
+}
+
+/// JSON 字段路径的一段：`a[b]` 的 `b`、`a[0]` 的 `0`、`a[]` 的追加
+enum PathSegment {
+    Key(String),
+    Index(usize),
+    Append,
+}
+
+/// 解析 HTTPie 命令（`http` / `https`，以及兼容语法的 `xh` / `xhs`）。
+/// request item：`Header:Value` 为 header，`name==value` 为查询参数，`name=value` 为字符串字段，
+/// `name:=json` 为原始 JSON 字段，`name@path` 为上传文件；`=@` / `:=@` 引用文件内容（无法读取，保留原文）。
+pub fn parse_httpie(input: &str) -> ParseResult {
+    let raw_text = input.to_string();
+    let trimmed = input.trim();
+
+    let shell = shell_words::detect_shell_style(trimmed);
+    let mut tokens = shell_words::tokenize(trimmed, shell).into_iter();
+    let program = tokens.next().unwrap_or_default();
+    let default_scheme = if program.ends_with('s') {
+        "https"
+    } else {
+        "http"
+    };
+
+    let mut mode = BodyMode::Json;
+    let mut auth: Option<String> = None;
+    let mut auth_type = "basic".to_string();
+    let mut raw_body: Option<String> = None;
+    let mut metadata: Vec<ParseNode> = Vec::new();
+    let mut positionals: Vec<String> = Vec::new();
+
+    if shell != ShellStyle::Posix {
+        metadata.push(metadata_node("shell", shell.name()));
+    }
+
+    let mut options_done = false;
+    while let Some(tok) = tokens.next() {
+        if options_done || !tok.starts_with('-') || tok == "-" {
+            positionals.push(tok);
+            continue;
+        }
+        if tok == "--" {
+            options_done = true;
+            continue;
+        }
+        let (flag, attached) = match tok.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (tok.clone(), None),
+        };
+        let value = if VALUE_OPTIONS.contains(&flag.as_str()) {
+            attached.or_else(|| tokens.next()).unwrap_or_default()
+        } else {
+            String::new()
+        };
+        match flag.as_str() {
+            "-j" | "--json" => mode = BodyMode::Json,
+            "-f" | "--form" => mode = BodyMode::Form,
+            "--multipart" => mode = BodyMode::Multipart,
+            "-a" | "--auth" => auth = Some(value),
+            "-A" | "--auth-type" => auth_type = value.to_lowercase(),
+            "--raw" => raw_body = Some(value),
+            "--verify" => match value.to_lowercase().as_str() {
+                "no" | "false" => metadata.push(metadata_node("insecure", "true")),
+                "yes" | "true" => {}
+                _ => metadata.push(metadata_node("cacert", &value)),
+            },
+            "-F" | "--follow" => metadata.push(metadata_node("follow-redirects", "true")),
+            "--timeout" => metadata.push(metadata_node("timeout", &value)),
+            "--proxy" => metadata.push(metadata_node("proxy", &value)),
+            "--cert" => metadata.push(metadata_node("cert", &value)),
+            "--session" | "--session-read-only" => metadata.push(metadata_node("session", &value)),
+            _ => {}
+        }
+    }
+
+    // 位置参数：[METHOD] URL [REQUEST_ITEM ...]
+    let mut positionals = positionals.into_iter().peekable();
+    let mut method: Option<String> = None;
+    if let Some(first) = positionals.peek() {
+        let is_method = first.chars().all(|c| c.is_ascii_alphabetic()) && positionals.len() > 1;
+        if is_method {
+            method = positionals.next().map(|m| m.to_uppercase());
+        }
+    }
+    let Some(url_arg) = positionals.next() else {
+        return empty_result(raw_text);
+    };
+    let mut url_str = expand_url(&url_arg, default_scheme);
+
+    let mut headers: Vec<ParseNode> = Vec::new();
+    let mut query = url::form_urlencoded::Serializer::new(String::new());
+    let mut has_query = false;
+    let mut fields: Vec<(String, JsValue)> = Vec::new();
+    let mut files: Vec<FormPart> = Vec::new();
+
+    for item in positionals {
+        let Some((key, separator, value)) = split_item(&item) else {
+            continue;
+        };
+        match separator {
+            ":" => {
+                // `Header:` 表示取消默认 header，不会出现在请求中
+                if !value.is_empty() {
+                    headers.push(header_node(&key, &value));
+                }
+            }
+            ";" => headers.push(header_node(&key, "")),
+            "==" => {
+                query.append_pair(&key, &value);
+                has_query = true;
+            }
+            "=" => fields.push((key, JsValue::String(value))),
+            ":=" => {
+                let parsed = js_literal::parse_literal(&value).unwrap_or(JsValue::String(value));
+                fields.push((key, parsed));
+            }
+            "=@" | ":=@" => fields.push((key, JsValue::String(format!("@{value}")))),
+            _ => {
+                let (path, content_type) = match value.split_once(";type=") {
+                    Some((path, content_type)) => {
+                        (path.to_string(), Some(content_type.to_string()))
+                    }
+                    None => (value.clone(), None),
+                };
+                let filename = path.rsplit(['/', '\\']).next().unwrap_or("").to_string();
+                files.push(FormPart {
+                    name: key,
+                    value: String::new(),
+                    content_type: content_type
+                        .or_else(|| Some(multipart::guess_content_type(&filename).to_string())),
+                    filename: Some(filename),
+                });
+            }
+        }
+    }
+
+    if has_query {
+        url_str.push(if url_str.contains('?') { '&' } else { '?' });
+        url_str.push_str(&query.finish());
+    }
+
+    // 认证：默认 Basic，`-A bearer` 时 `-a` 为 token，Digest 记入元数据
+    let has_header = |headers: &[ParseNode], name: &str| {
+        headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+    };
+    if let Some(credentials) = &auth {
+        match auth_type.as_str() {
+            "bearer" => {
+                if !has_header(&headers, "authorization") {
+                    headers.push(header_node(
+                        "Authorization",
+                        &format!("Bearer {credentials}"),
+                    ));
+                }
+            }
+            "basic" => {
+                if !has_header(&headers, "authorization") {
+                    let encoded = general_purpose::STANDARD.encode(credentials);
+                    headers.push(header_node("Authorization", &format!("Basic {encoded}")));
+                }
+            }
+            scheme => metadata.push(metadata_node(scheme, credentials)),
+        }
+    }
+
+    // 请求体：有文件时为 multipart，表单模式为 urlencoded，否则序列化为 JSON
+    if !files.is_empty() {
+        mode = BodyMode::Multipart;
+    }
+    let mut implied_headers: Vec<(&str, String)> = Vec::new();
+    let body = if let Some(raw) = raw_body {
+        Some(raw)
+    } else if mode == BodyMode::Multipart {
+        let mut parts: Vec<FormPart> = fields
+            .iter()
+            .map(|(name, value)| FormPart {
+                name: name.clone(),
+                value: field_text(value),
+                filename: None,
+                content_type: None,
+            })
+            .collect();
+        parts.extend(files);
+        implied_headers.push((
+            "Content-Type",
+            format!("multipart/form-data; boundary={FORM_BOUNDARY}"),
+        ));
+        Some(multipart::build_multipart_body(&parts, FORM_BOUNDARY))
+    } else if fields.is_empty() {
+        None
+    } else if mode == BodyMode::Form {
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        for (name, value) in &fields {
+            form.append_pair(name, &field_text(value));
+        }
+        implied_headers.push((
+            "Content-Type",
+            "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+        ));
+        Some(form.finish())
+    } else {
+        let mut root = JsValue::Object(Vec::new());
+        for (key, value) in fields {
+            insert_json(&mut root, &split_json_path(&key), value);
+        }
+        implied_headers.push(("Content-Type", "application/json".to_string()));
+        implied_headers.push(("Accept", "application/json, */*;q=0.5".to_string()));
+        Some(py_literal::json_dumps(&root, false))
+    };
+    for (name, value) in implied_headers {
+        if !has_header(&headers, name) {
+            headers.push(header_node(name, &value));
+        }
+    }
+
+    // 未指定 method 时，有请求体为 POST，否则为 GET
+    let method = method.or_else(|| Some(if body.is_some() { "POST" } else { "GET" }.to_string()));
+
+    // 解析 URL query params
+    let query_params = parse_utils::parse_query_params(&url_str);
+    let query_tree = query_params
+        .as_deref()
+        .and_then(query_tree::build_query_tree);
+    let url_nodes = parse_utils::parse_url_nodes(&url_str);
+
+    ParseResult {
+        content_type: HttpContentType::Request,
+        method,
+        url: Some(url_str),
+        url_nodes,
+        headers,
+        query_params,
+        query_tree,
+        body,
+        metadata: if metadata.is_empty() {
+            None
+        } else {
+            Some(metadata)
+        },
+        raw_text,
+        ..Default::default()
+    }
+}
+
+/// 展开 HTTPie 的 URL 简写：`:8080/api` → `http://localhost:8080/api`，`example.com` → `http://example.com`。
+fn expand_url(url: &str, default_scheme: &str) -> String {
+    if url.contains("://") {
+        return url.to_string();
+    }
+    if let Some(rest) = url.strip_prefix(':') {
+        let rest = if rest.starts_with('/') || rest.is_empty() {
+            rest.to_string()
+        } else {
+            format!(":{rest}")
+        };
+        return format!("{default_scheme}://localhost{rest}");
+    }
+    format!("{default_scheme}://{url}")
+}
+
+/// 拆分 request item 为（key, 分隔符, value）。取最先出现的未转义分隔符，同一位置取最长的；
+/// `\` 可转义分隔符字符（如 `a\:b==c`）。
+fn split_item(item: &str) -> Option<(String, &'static str, String)> {
+    let chars: Vec<char> = item.chars().collect();
+    let mut key = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' && chars.get(i + 1).is_some_and(|n| ":=@;\\".contains(*n)) {
+            key.push(chars[i + 1]);
+            i += 2;
+            continue;
+        }
+        let rest: String = chars[i..].iter().collect();
+        if let Some(separator) = SEPARATORS.iter().find(|s| rest.starts_with(**s)) {
+            let value: String = rest[separator.len()..].to_string();
+            return Some((key, separator, value));
+        }
+        key.push(c);
+        i += 1;
+    }
+    None
+}
+
+/// 拆分嵌套 JSON 字段名：`user[name]` → `user`, `name`；`tags[]` → `tags`, 追加；`[0]` 为顶层数组。
+fn split_json_path(key: &str) -> Vec<PathSegment> {
+    let (base, mut rest) = match key.find('[') {
+        Some(pos) => (&key[..pos], &key[pos..]),
+        None => return vec![PathSegment::Key(key.to_string())],
+    };
+    let mut segments = Vec::new();
+    if !base.is_empty() {
+        segments.push(PathSegment::Key(base.to_string()));
+    }
+    while !rest.is_empty() {
+        let Some((inner, after)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) else {
+            return vec![PathSegment::Key(key.to_string())];
+        };
+        segments.push(if inner.is_empty() {
+            PathSegment::Append
+        } else if let Some(index) = inner
+            .parse::<usize>()
+            .ok()
+            .filter(|index| *index <= MAX_ARRAY_INDEX)
+        {
+            PathSegment::Index(index)
+        } else {
+            PathSegment::Key(inner.to_string())
+        });
+        rest = after;
+    }
+    segments
+}
+
+/// 按路径写入 JSON 值；重复的顶层 key 合并为数组（与 HTTPie 一致）。
+fn insert_json(target: &mut JsValue, path: &[PathSegment], value: JsValue) {
+    let Some((segment, rest)) = path.split_first() else {
+        return;
+    };
+    let container = || match rest.first() {
+        Some(PathSegment::Key(_)) => JsValue::Object(Vec::new()),
+        _ => JsValue::Array(Vec::new()),
+    };
+    match segment {
+        PathSegment::Key(key) => {
+            if !matches!(target, JsValue::Object(_)) {
+                *target = JsValue::Object(Vec::new());
+            }
+            let JsValue::Object(entries) = target else {
+                return;
+            };
+            let existing = entries.iter().position(|(k, _)| k == key);
+            match (existing, rest.is_empty()) {
+                (None, true) => entries.push((key.clone(), value)),
+                (None, false) => {
+                    let mut child = container();
+                    insert_json(&mut child, rest, value);
+                    entries.push((key.clone(), child));
+                }
+                (Some(i), true) => match &mut entries[i].1 {
+                    JsValue::Array(items) => items.push(value),
+                    slot => {
+                        let old = std::mem::replace(slot, JsValue::Null);
+                        *slot = JsValue::Array(vec![old, value]);
+                    }
+                },
+                (Some(i), false) => insert_json(&mut entries[i].1, rest, value),
+            }
+        }
+        PathSegment::Index(_) | PathSegment::Append => {
+            if !matches!(target, JsValue::Array(_)) {
+                *target = JsValue::Array(Vec::new());
+            }
+            let JsValue::Array(items) = target else {
+                return;
+            };
+            let index = match segment {
+                PathSegment::Index(index) => *index,
+                _ => items.len(),
+            };
+            while items.len() <= index {
+                items.push(JsValue::Null);
+            }
+            if rest.is_empty() {
+                items[index] = value;
+            } else {
+                if items[index] == JsValue::Null {
+                    items[index] = container();
+                }
+                insert_json(&mut items[index], rest, value);
+            }
+        }
+    }
+}
+
+/// 表单模式下字段的文本值：`:=` 字段按 JSON 文本提交。
+fn field_text(value: &JsValue) -> String {
+    match value {
+        JsValue::String(s) => s.clone(),
+        other => other.to_json(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header<'a>(result: &'a ParseResult, name: &str) -> Option<&'a str> {
+        parse_utils::header_value(&result.headers, name)
+    }
+
+    #[test]
+    fn test_json_items() {
+        let result = parse_httpie(
+            r#"http POST :8080/api name=foo Authorization:Bearer\ x age:=30 tags:='["a","b"]' q==search\ term"#,
+        );
+
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(
+            result.url.as_deref(),
+            Some("http://localhost:8080/api?q=search+term")
+        );
+        assert_eq!(result.headers[0].key, "Authorization");
+        assert_eq!(result.headers[0].value, "Bearer x");
+        assert_eq!(header(&result, "content-type"), Some("application/json"));
+        assert_eq!(
+            result.body.as_deref(),
+            Some(r#"{"name": "foo", "age": 30, "tags": ["a", "b"]}"#)
+        );
+    }
+
+    #[test]
+    fn test_default_method_and_url() {
+        let get = parse_httpie("http example.com/users X-Trace:1 page==2");
+        assert_eq!(get.method.as_deref(), Some("GET"));
+        assert_eq!(get.url.as_deref(), Some("http://example.com/users?page=2"));
+        assert!(get.body.is_none());
+
+        let post = parse_httpie("https api.example.com/items title=hi");
+        assert_eq!(post.method.as_deref(), Some("POST"));
+        assert_eq!(post.url.as_deref(), Some("https://api.example.com/items"));
+    }
+
+    #[test]
+    fn test_form_mode_and_auth() {
+        let result = parse_httpie("http -f -a user:pass --verify=no PUT example.com a=1 b='x y'");
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+        assert_eq!(result.body.as_deref(), Some("a=1&b=x+y"));
+        assert_eq!(
+            header(&result, "content-type"),
+            Some("application/x-www-form-urlencoded; charset=utf-8")
+        );
+        assert_eq!(header(&result, "authorization"), Some("Basic dXNlcjpwYXNz"));
+        assert_eq!(result.metadata.unwrap()[0].key, "insecure");
+
+        let bearer = parse_httpie("http -A bearer -a tok123 example.com");
+        assert_eq!(header(&bearer, "authorization"), Some("Bearer tok123"));
+    }
+
+    #[test]
+    fn test_file_upload_is_multipart() {
+        let result = parse_httpie("http -f POST example.com/upload name=doc file@/tmp/report.pdf");
+        let body = result.body.unwrap();
+        let parts = multipart::parse_multipart(&body, FORM_BOUNDARY).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert!(body.contains("filename=\"report.pdf\"\r\nContent-Type: application/pdf"));
+    }
+
+    #[test]
+    fn test_nested_json_and_escapes() {
+        let result = parse_httpie(
+            r"http :3000/u user[name]=bob user[roles][]=admin user[roles][]=dev tag=a tag=b 'a\:b=c' Empty;",
+        );
+        assert_eq!(
+            result.body.as_deref(),
+            Some(
+                r#"{"user": {"name": "bob", "roles": ["admin", "dev"]}, "tag": ["a", "b"], "a:b": "c"}"#
+            )
+        );
+        assert_eq!(result.headers[0].key, "Empty");
+        assert_eq!(result.headers[0].value, "");
+    }
+
+    #[test]
+    fn test_huge_array_index_is_a_key() {
+        let result = parse_httpie("http POST example.com a[99999999999]:=1 b[2]:=2");
+        assert_eq!(
+            result.body.as_deref(),
+            Some(r#"{"a": {"99999999999": 1}, "b": [null, null, 2]}"#)
+        );
+    }
+
+    #[test]
+    fn test_split_item_precedence() {
+        let (key, sep, value) = split_item("a:=@b.json").unwrap();
+        assert_eq!((key.as_str(), sep, value.as_str()), ("a", ":=@", "b.json"));
+        let (key, sep, value) = split_item("url==https://x?a=b").unwrap();
+        assert_eq!(
+            (key.as_str(), sep, value.as_str()),
+            ("url", "==", "https://x?a=b")
+        );
+        let (key, sep, _) = split_item("Host:example.com").unwrap();
+        assert_eq!((key.as_str(), sep), ("Host", ":"));
+    }
+}
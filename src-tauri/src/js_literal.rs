@@ -0,0 +1,671 @@
+/// 宽松读取的 JS 字面量值。对象保留源码中的 key 顺序（serde_json 的 Map 会按字母排序）。
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsValue {
+    Null,
+    Bool(bool),
+    /// 数字按源码文本保存
+    Number(String),
+    String(String),
+    Array(Vec<JsValue>),
+    Object(Vec<(String, JsValue)>),
+    /// `new URLSearchParams(...)`，已序列化为 `application/x-www-form-urlencoded`
+    SearchParams(String),
+    /// 无法静态求值的表达式（变量、函数调用等），保存原始源码
+    Expr(String),
+}
+
+impl JsValue {
+    /// 按 key 读取对象字段，非对象时返回 None。
+    pub fn get(&self, key: &str) -> Option<&JsValue> {
+        match self {
+            JsValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// 作为文本使用时的值：null / undefined 返回 None，对象与数组返回 JSON。
+    pub fn to_text(&self) -> Option<String> {
+        match self {
+            JsValue::Null => None,
+            JsValue::Bool(b) => Some(b.to_string()),
+            JsValue::Number(n) | JsValue::String(n) | JsValue::SearchParams(n) => Some(n.clone()),
+            JsValue::Expr(e) => Some(e.clone()),
+            JsValue::Array(_) | JsValue::Object(_) => Some(self.to_json()),
+        }
+    }
+
+    /// 与 `JSON.stringify` 一致的紧凑 JSON，key 顺序与源码一致。
+    pub fn to_json(&self) -> String {
+        match self {
+            JsValue::Null => "null".to_string(),
+            JsValue::Bool(b) => b.to_string(),
+            JsValue::Number(n) => n.replace('_', ""),
+            JsValue::String(s) | JsValue::SearchParams(s) | JsValue::Expr(s) => {
+                serde_json::Value::String(s.clone()).to_string()
+            }
+            JsValue::Array(items) => {
+                let items: Vec<String> = items.iter().map(JsValue::to_json).collect();
+                format!("[{}]", items.join(","))
+            }
+            JsValue::Object(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(k, v)| {
+                        format!("{}:{}", serde_json::Value::String(k.clone()), v.to_json())
+                    })
+                    .collect();
+                format!("{{{}}}", entries.join(","))
+            }
+        }
+    }
+
+    fn is_string(&self) -> bool {
+        matches!(self, JsValue::String(_) | JsValue::SearchParams(_))
+    }
+
+    /// 拼接时按原样转为文本的值（对象、数组与表达式除外）。
+    fn is_primitive(&self) -> bool {
+        matches!(
+            self,
+            JsValue::String(_) | JsValue::SearchParams(_) | JsValue::Number(_) | JsValue::Bool(_)
+        )
+    }
+
+    fn contains_expr(&self) -> bool {
+        match self {
+            JsValue::Expr(_) => true,
+            JsValue::Array(items) => items.iter().any(JsValue::contains_expr),
+            JsValue::Object(entries) => entries.iter().any(|(_, v)| v.contains_expr()),
+            _ => false,
+        }
+    }
+}
+
+/// 读取函数调用的参数列表，`input` 从 `(` 之后开始，到匹配的 `)` 为止。
+/// 缺少 `)` 时返回已读到的参数。
+pub fn parse_call_arguments(input: &str) -> Vec<JsValue> {
+    let mut reader = Reader::new(input);
+    reader.arguments()
+}
+
+/// 读取一个完整的 JS / JSON 字面量，其后还有其他内容时返回 None。
+pub fn parse_literal(input: &str) -> Option<JsValue> {
+    let mut reader = Reader::new(input);
+    reader.skip_ws();
+    reader.peek()?;
+    let value = reader.operand();
+    reader.skip_ws();
+    reader.peek().is_none().then_some(value)
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn new(input: &str) -> Self {
+        Reader {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.chars[start..end].iter().collect()
+    }
+
+    /// 跳过空白与 `//`、`/* */` 注释。
+    fn skip_ws(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.pos += 1,
+                Some('/') if self.peek_at(1) == Some('/') => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.pos += 1;
+                    }
+                }
+                Some('/') if self.peek_at(1) == Some('*') => {
+                    self.pos += 2;
+                    while self.peek().is_some()
+                        && !(self.peek() == Some('*') && self.peek_at(1) == Some('/'))
+                    {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.chars.len());
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn arguments(&mut self) -> Vec<JsValue> {
+        let mut args = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => break,
+                Some(')') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => self.pos += 1,
+                Some(_) => {
+                    let before = self.pos;
+                    args.push(self.operand());
+                    // 游离的 `;` 等字符不会被任何值消费，跳过以免死循环
+                    if self.pos == before {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+        args
+    }
+
+    /// 读取一个值，并处理其后的字符串拼接（`"Bearer " + token`）；只有至少一侧是字符串时才拼接
+    /// （`1 + 2` 是数值加法），其余无法识别的运算符连同整个表达式按原文保存。
+    fn operand(&mut self) -> JsValue {
+        let start = self.pos;
+        let mut value = self.value();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None | Some(',' | ')' | ']' | '}' | ';') => return value,
+                Some('+') => {
+                    self.pos += 1;
+                    self.skip_ws();
+                    let rhs = self.value();
+                    let concatenates = (value.is_string() && rhs.is_primitive())
+                        || (rhs.is_string() && value.is_primitive());
+                    value = match (value.to_text(), rhs.to_text()) {
+                        (Some(l), Some(r)) if concatenates => JsValue::String(l + &r),
+                        _ => JsValue::Expr(self.text(start, self.pos).trim().to_string()),
+                    };
+                }
+                Some(_) => {
+                    self.skip_expression();
+                    return JsValue::Expr(self.text(start, self.pos).trim().to_string());
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> JsValue {
+        self.skip_ws();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some(quote @ ('"' | '\'')) => {
+                self.pos += 1;
+                JsValue::String(self.string(quote))
+            }
+            Some('`') => {
+                self.pos += 1;
+                JsValue::String(self.template())
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' || c == '-' => self.number(),
+            Some(c) if is_ident_start(c) => self.identifier_value(),
+            _ => {
+                let start = self.pos;
+                self.skip_expression();
+                JsValue::Expr(self.text(start, self.pos).trim().to_string())
+            }
+        }
+    }
+
+    fn object(&mut self) -> JsValue {
+        self.pos += 1;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_ws();
+            let key = match self.peek() {
+                None => break,
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some(quote @ ('"' | '\'')) => {
+                    self.pos += 1;
+                    self.string(quote)
+                }
+                Some('`') => {
+                    self.pos += 1;
+                    self.template()
+                }
+                Some('[') => {
+                    // 计算属性名：`[name]: value`
+                    self.pos += 1;
+                    let key = self.operand();
+                    self.skip_ws();
+                    if self.peek() == Some(']') {
+                        self.pos += 1;
+                    }
+                    key.to_text().unwrap_or_default()
+                }
+                Some('.') if self.peek_at(1) == Some('.') => {
+                    // 展开语法 `...rest` 无法静态求值，跳过
+                    self.skip_expression();
+                    continue;
+                }
+                Some(_) => {
+                    let start = self.pos;
+                    while self
+                        .peek()
+                        .is_some_and(|c| !matches!(c, ':' | ',' | '}') && !c.is_whitespace())
+                    {
+                        self.pos += 1;
+                    }
+                    if self.pos == start {
+                        self.pos += 1;
+                        continue;
+                    }
+                    self.text(start, self.pos)
+                }
+            };
+
+            self.skip_ws();
+            if self.peek() == Some(':') {
+                self.pos += 1;
+                let value = self.operand();
+                entries.push((key, value));
+            } else {
+                // 简写属性 `{ body }`
+                entries.push((key.clone(), JsValue::Expr(key)));
+            }
+        }
+        JsValue::Object(entries)
+    }
+
+    fn array(&mut self) -> JsValue {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => break,
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => self.pos += 1,
+                Some(_) => {
+                    let before = self.pos;
+                    items.push(self.operand());
+                    if self.pos == before {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+        JsValue::Array(items)
+    }
+
+    /// 读取引号字符串（开头引号已消费），处理 JS 转义。
+    fn string(&mut self, quote: char) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.next() {
+            match c {
+                c if c == quote => break,
+                '\\' => self.escape(&mut out),
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// 读取模板字符串（开头反引号已消费），`${...}` 插值按原文保留。
+    fn template(&mut self) -> String {
+        let mut out = String::new();
+        while let Some(c) = self.next() {
+            match c {
+                '`' => break,
+                '\\' => self.escape(&mut out),
+                '$' if self.peek() == Some('{') => {
+                    let start = self.pos - 1;
+                    let mut depth = 0;
+                    while let Some(c) = self.next() {
+                        match c {
+                            '{' => depth += 1,
+                            '}' => {
+                                depth -= 1;
+                                if depth == 0 {
+                                    break;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    out.push_str(&self.text(start, self.pos));
+                }
+                _ => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// 处理 `\` 之后的转义序列：`\n \r \t \b \f \v \0 \xHH \uHHHH \u{H…}` 与续行。
+    fn escape(&mut self, out: &mut String) {
+        let Some(c) = self.next() else {
+            return;
+        };
+        match c {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '0' if !self.peek().is_some_and(|d| d.is_ascii_digit()) => out.push('\0'),
+            'x' => match self.hex(2) {
+                Some(code) => out.push(char::from_u32(code).unwrap_or('\u{FFFD}')),
+                None => out.push('x'),
+            },
+            'u' => {
+                let code = if self.peek() == Some('{') {
+                    self.pos += 1;
+                    let start = self.pos;
+                    while self.peek().is_some_and(|d| d.is_ascii_hexdigit()) {
+                        self.pos += 1;
+                    }
+                    let code = u32::from_str_radix(&self.text(start, self.pos), 16).ok();
+                    if self.peek() == Some('}') {
+                        self.pos += 1;
+                    }
+                    code
+                } else {
+                    self.hex(4)
+                };
+                match code {
+                    Some(high @ 0xD800..=0xDBFF) => {
+                        // UTF-16 代理对：`😀`
+                        let low = if self.peek() == Some('\\') && self.peek_at(1) == Some('u') {
+                            let saved = self.pos;
+                            self.pos += 2;
+                            match self.hex(4) {
+                                Some(low @ 0xDC00..=0xDFFF) => Some(low),
+                                _ => {
+                                    self.pos = saved;
+                                    None
+                                }
+                            }
+                        } else {
+                            None
+                        };
+                        let combined =
+                            low.map(|low| 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00));
+                        out.push(combined.and_then(char::from_u32).unwrap_or('\u{FFFD}'));
+                    }
+                    Some(code) => out.push(char::from_u32(code).unwrap_or('\u{FFFD}')),
+                    None => out.push('u'),
+                }
+            }
+            '\r' => {
+                if self.peek() == Some('\n') {
+                    self.pos += 1;
+                }
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            other => out.push(other),
+        }
+    }
+
+    /// 读取恰好 `len` 位十六进制数字，不足时不消费任何字符。
+    fn hex(&mut self, len: usize) -> Option<u32> {
+        let end = self.pos + len;
+        if end > self.chars.len() {
+            return None;
+        }
+        let digits = self.text(self.pos, end);
+        let code = u32::from_str_radix(&digits, 16).ok()?;
+        if !digits.chars().all(|d| d.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.pos = end;
+        Some(code)
+    }
+
+    fn number(&mut self) -> JsValue {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while let Some(c) = self.peek() {
+            let exponent_sign = matches!(c, '+' | '-')
+                && matches!(self.chars.get(self.pos.wrapping_sub(1)), Some('e' | 'E'));
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || exponent_sign {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        JsValue::Number(self.text(start, self.pos))
+    }
+
+    /// 关键字、`JSON.stringify(...)`、`new Headers(...)`、`new URLSearchParams(...)`，
+    /// 其余标识符表达式按原文保存。
+    fn identifier_value(&mut self) -> JsValue {
+        let start = self.pos;
+        let name = self.identifier_path();
+        match name.as_str() {
+            "true" => return JsValue::Bool(true),
+            "false" => return JsValue::Bool(false),
+            "null" | "undefined" => return JsValue::Null,
+            _ => {}
+        }
+
+        let callee = match name.as_str() {
+            "new" => {
+                self.skip_ws();
+                self.identifier_path()
+            }
+            _ => name,
+        };
+        self.skip_ws();
+        if self.peek() != Some('(') {
+            self.skip_expression();
+            return JsValue::Expr(self.text(start, self.pos).trim().to_string());
+        }
+        self.pos += 1;
+        let args = self.arguments();
+        let raw = JsValue::Expr(self.text(start, self.pos));
+
+        match callee.as_str() {
+            "JSON.stringify" => match args.first() {
+                Some(arg) if !arg.contains_expr() => JsValue::String(arg.to_json()),
+                _ => raw,
+            },
+            "Headers" => args
+                .into_iter()
+                .next()
+                .unwrap_or(JsValue::Object(Vec::new())),
+            "URLSearchParams" => match args.first() {
+                None => JsValue::SearchParams(String::new()),
+                Some(init) => match search_params(init) {
+                    Some(encoded) => JsValue::SearchParams(encoded),
+                    None => raw,
+                },
+            },
+            _ => raw,
+        }
+    }
+
+    fn identifier_path(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| is_ident_start(c) || c.is_ascii_digit() || c == '.')
+        {
+            self.pos += 1;
+        }
+        self.text(start, self.pos)
+    }
+
+    /// 跳过一个无法识别的表达式，直到同层级的 `,` / `)` / `]` / `}` / `;`。
+    fn skip_expression(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' | ',' | ';' if depth == 0 => return,
+                ')' | ']' | '}' => depth -= 1,
+                '"' | '\'' => {
+                    self.pos += 1;
+                    self.string(c);
+                    continue;
+                }
+                '`' => {
+                    self.pos += 1;
+                    self.template();
+                    continue;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+/// 按 URLSearchParams 的规则序列化初始值：字符串、对象或 `[[k, v], ...]`。
+fn search_params(init: &JsValue) -> Option<String> {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    match init {
+        JsValue::String(s) => return Some(s.strip_prefix('?').unwrap_or(s).to_string()),
+        JsValue::Object(entries) => {
+            for (key, value) in entries {
+                serializer.append_pair(key, &value.to_text()?);
+            }
+        }
+        JsValue::Array(pairs) => {
+            for pair in pairs {
+                let JsValue::Array(pair) = pair else {
+                    return None;
+                };
+                let [key, value] = pair.as_slice() else {
+                    return None;
+                };
+                serializer.append_pair(&key.to_text()?, &value.to_text()?);
+            }
+        }
+        _ => return None,
+    }
+    Some(serializer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_value(input: &str) -> Option<JsValue> {
+        Some(Reader::new(input).operand())
+    }
+
+    #[test]
+    fn test_relaxed_object_syntax() {
+        let value = parse_value(
+            "{ method: 'POST', // comment\n  headers: { 'x-a': \"1\", n: 2, }, /* c */ ok: true, }",
+        )
+        .unwrap();
+        assert_eq!(value.get("method"), Some(&JsValue::String("POST".into())));
+        assert_eq!(value.get("ok"), Some(&JsValue::Bool(true)));
+        assert_eq!(
+            value.get("headers").unwrap().to_json(),
+            r#"{"x-a":"1","n":2}"#
+        );
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let value = parse_value(
+            r"'a\'b\n\x41中\u{1F600}😀\
+c'",
+        )
+        .unwrap();
+        assert_eq!(value, JsValue::String("a'b\nA中😀😀c".into()));
+    }
+
+    #[test]
+    fn test_template_literal() {
+        let value = parse_value("`Bearer ${token}\nline`").unwrap();
+        assert_eq!(value, JsValue::String("Bearer ${token}\nline".into()));
+    }
+
+    #[test]
+    fn test_json_stringify_keeps_key_order() {
+        let value = parse_value("JSON.stringify({ b: 1, a: [1, 'x',], c: null })").unwrap();
+        assert_eq!(
+            value,
+            JsValue::String(r#"{"b":1,"a":[1,"x"],"c":null}"#.into())
+        );
+
+        let value = parse_value("JSON.stringify(payload)").unwrap();
+        assert_eq!(value, JsValue::Expr("JSON.stringify(payload)".into()));
+    }
+
+    #[test]
+    fn test_headers_and_search_params() {
+        let value = parse_value("new Headers([['a', '1'], ['b', '2']])").unwrap();
+        assert_eq!(value.to_json(), r#"[["a","1"],["b","2"]]"#);
+
+        let value = parse_value("new URLSearchParams({ q: 'a b', page: 2 })").unwrap();
+        assert_eq!(value, JsValue::SearchParams("q=a+b&page=2".into()));
+
+        let value = parse_value("new URLSearchParams('?x=1')").unwrap();
+        assert_eq!(value, JsValue::SearchParams("x=1".into()));
+    }
+
+    #[test]
+    fn test_expressions_and_concatenation() {
+        let args = parse_call_arguments(
+            "url, { headers: { auth: 'Bearer ' + 'abc', id: user.id }, body }).then(r => r.json())",
+        );
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0], JsValue::Expr("url".into()));
+        let headers = args[1].get("headers").unwrap();
+        assert_eq!(
+            headers.get("auth"),
+            Some(&JsValue::String("Bearer abc".into()))
+        );
+        assert_eq!(headers.get("id"), Some(&JsValue::Expr("user.id".into())));
+        assert_eq!(args[1].get("body"), Some(&JsValue::Expr("body".into())));
+
+        assert_eq!(parse_value("1 + 2"), Some(JsValue::Expr("1 + 2".into())));
+        assert_eq!(
+            parse_value("'v' + 1 + true"),
+            Some(JsValue::String("v1true".into()))
+        );
+        assert_eq!(
+            parse_value("'ids=' + [1, 2]"),
+            Some(JsValue::Expr("'ids=' + [1, 2]".into()))
+        );
+    }
+
+    #[test]
+    fn test_malformed_input_terminates() {
+        let args = parse_call_arguments("'a'; [;], { : }, `x");
+        assert_eq!(args[0], JsValue::String("a".into()));
+        assert_eq!(parse_call_arguments("{ a: [1, 2").len(), 1);
+    }
+}
@@ -1,38 +1,210 @@
+mod body_decoding;
+mod body_parser;
 mod clipboard;
+mod collection_parser;
+mod compound;
 mod curl_parser;
+mod curl_verbose_parser;
+mod decode_rules;
 mod decoder;
 mod detector;
 mod error;
 mod fetch_parser;
+mod har_parser;
 mod history;
+mod http_file_parser;
+mod httpie_parser;
+mod js_literal;
 mod models;
+mod multipart;
 mod parse_utils;
 mod parser;
+mod pcap_parser;
+mod powershell_parser;
+mod proxy_export_parser;
+mod py_literal;
+mod python_parser;
+mod query_tree;
+mod settings;
+mod shell_words;
+mod wget_parser;
 
+use std::fs;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-use tauri::State;
+use tauri::{Manager, State};
 
 use clipboard::ClipboardWatcherState;
+use decoder::{DecoderConfig, DecoderRegistry};
 use detector::InputFormat;
 use error::AppError;
-use models::ParseResult;
+use models::{CollectionRequest, HttpExchange, ParseResult};
+use settings::DecoderState;
 
-/// 解析 HTTP 文本，自动检测输入格式（cURL / fetch / 原始 HTTP）。
+/// 解析 HTTP 文本，自动检测输入格式（cURL / curl -v / fetch / PowerShell / HTTPie / wget / HAR / Postman / Insomnia / Burp / ZAP / Python / .http 文件 / 原始 HTTP）。
+/// 只返回第一条报文（优先请求），完整的请求/响应对请使用 `parse_exchanges`。
+/// decoder_config 覆盖已保存的解码器设置（用于预览设置改动）。
 #[tauri::command]
-fn parse_text(raw_text: String) -> Result<ParseResult, AppError> {
+fn parse_text(
+    state: State<'_, DecoderState>,
+    raw_text: String,
+    decoder_config: Option<DecoderConfig>,
+) -> Result<ParseResult, AppError> {
+    let (registry, warnings) = state.registry(decoder_config);
+    build_exchanges(&raw_text, &registry, &warnings)?
+        .into_iter()
+        .find_map(|exchange| exchange.request.or(exchange.response))
+        .ok_or_else(|| AppError::ParseError("No HTTP message found".to_string()))
+}
+
+/// 解析 HTTP 文本为请求/响应交互列表（HAR 的全部 entry、curl -v 的请求与响应、
+/// 原始 HTTP 中先后粘贴的多条报文）。
+#[tauri::command]
+fn parse_exchanges(
+    state: State<'_, DecoderState>,
+    raw_text: String,
+) -> Result<Vec<HttpExchange>, AppError> {
+    let (registry, warnings) = state.registry(None);
+    build_exchanges(&raw_text, &registry, &warnings)
+}
+
+/// 解析 HAR 文件，返回全部 entry 的请求/响应对。与 `parse_exchanges` 相同，但不做格式检测。
+/// 仅供后端调用，前端通过 `parse_exchanges` 获取。
+#[tauri::command]
+fn parse_har_text(
+    state: State<'_, DecoderState>,
+    raw_text: String,
+) -> Result<Vec<HttpExchange>, AppError> {
+    let (registry, warnings) = state.registry(None);
+    finish_exchanges(har_parser::parse_har(&raw_text), &registry, &warnings)
+}
+
+/// 解析 `curl -v` 输出记录，返回其中的请求/响应对。与 `parse_exchanges` 相同，但不做格式检测。
+/// 仅供后端调用，前端通过 `parse_exchanges` 获取。
+#[tauri::command]
+fn parse_curl_verbose_text(
+    state: State<'_, DecoderState>,
+    raw_text: String,
+) -> Result<Vec<HttpExchange>, AppError> {
+    let (registry, warnings) = state.registry(None);
+    let exchange = curl_verbose_parser::parse_curl_verbose(&raw_text);
+    finish_exchanges(vec![exchange], &registry, &warnings)
+}
+
+/// 按检测到的输入格式解析并做后处理，`parse_text` 与 `parse_exchanges` 共用。
+fn build_exchanges(
+    raw_text: &str,
+    registry: &DecoderRegistry,
+    warnings: &[String],
+) -> Result<Vec<HttpExchange>, AppError> {
     if raw_text.trim().is_empty() {
         return Err(AppError::ParseError("Input text is empty".to_string()));
     }
-    let mut result = match detector::detect_input_format(&raw_text) {
-        InputFormat::Curl => curl_parser::parse_curl(&raw_text),
-        InputFormat::Fetch => fetch_parser::parse_fetch(&raw_text),
-        InputFormat::RawHttp => parser::parse_http_text(&raw_text),
-        InputFormat::Unknown => parser::parse_http_text(&raw_text),
+    let exchanges = match detector::detect_input_format(raw_text) {
+        InputFormat::Curl => vec![HttpExchange::from_message(curl_parser::parse_curl(raw_text))],
+        InputFormat::CurlVerbose => vec![curl_verbose_parser::parse_curl_verbose(raw_text)],
+        InputFormat::Fetch => vec![HttpExchange::from_message(fetch_parser::parse_fetch(raw_text))],
+        InputFormat::PowerShell => {
+            vec![HttpExchange::from_message(powershell_parser::parse_powershell(raw_text))]
+        }
+        InputFormat::Httpie => vec![HttpExchange::from_message(httpie_parser::parse_httpie(raw_text))],
+        InputFormat::Wget => vec![HttpExchange::from_message(wget_parser::parse_wget(raw_text))],
+        InputFormat::Har => har_parser::parse_har(raw_text),
+        InputFormat::Postman => collection_parser::parse_postman(raw_text, None)
+            .into_iter()
+            .map(|item| HttpExchange::from_message(item.request))
+            .collect(),
+        InputFormat::Insomnia => collection_parser::parse_insomnia(raw_text, None)
+            .into_iter()
+            .map(|item| HttpExchange::from_message(item.request))
+            .collect(),
+        InputFormat::Burp => proxy_export_parser::parse_burp(raw_text),
+        InputFormat::Zap => proxy_export_parser::parse_zap(raw_text),
+        InputFormat::Python => vec![HttpExchange::from_message(python_parser::parse_python(raw_text))],
+        InputFormat::HttpFile => http_file_parser::parse_http_file(raw_text),
+        InputFormat::RawHttp => parser::parse_http_exchanges(raw_text),
+        InputFormat::Unknown => parser::parse_http_exchanges(raw_text),
     };
-    decoder::apply_recursive_decode(&mut result);
-    Ok(result)
+    finish_exchanges(exchanges, registry, warnings)
+}
+
+/// 去掉空的交互并对每条报文做后处理，没有任何报文时返回错误。
+fn finish_exchanges(
+    mut exchanges: Vec<HttpExchange>,
+    registry: &DecoderRegistry,
+    warnings: &[String],
+) -> Result<Vec<HttpExchange>, AppError> {
+    exchanges.retain(|e| e.request.is_some() || e.response.is_some());
+    if exchanges.is_empty() {
+        return Err(AppError::ParseError("No HTTP message found".to_string()));
+    }
+    for exchange in &mut exchanges {
+        if let Some(request) = &mut exchange.request {
+            post_process(request, registry, warnings);
+        }
+        if let Some(response) = &mut exchange.response {
+            post_process(response, registry, warnings);
+        }
+    }
+    Ok(exchanges)
+}
+
+/// 导入 Postman / Insomnia 集合，返回带文件夹路径的全部请求。
+/// environment 为可选的环境文件 JSON，其中的变量覆盖集合自带的变量。
+/// 仅供后端调用，前端尚未接入。
+#[tauri::command]
+fn import_collection(
+    state: State<'_, DecoderState>,
+    raw_text: String,
+    environment: Option<String>,
+) -> Result<Vec<CollectionRequest>, AppError> {
+    let mut requests = match detector::detect_input_format(&raw_text) {
+        InputFormat::Postman => collection_parser::parse_postman(&raw_text, environment.as_deref()),
+        InputFormat::Insomnia => {
+            collection_parser::parse_insomnia(&raw_text, environment.as_deref())
+        }
+        _ => {
+            return Err(AppError::ParseError(
+                "Not a Postman collection or Insomnia export".to_string(),
+            ))
+        }
+    };
+    if requests.is_empty() {
+        return Err(AppError::ParseError("No request found in collection".to_string()));
+    }
+    let (registry, warnings) = state.registry(None);
+    for item in &mut requests {
+        post_process(&mut item.request, &registry, &warnings);
+    }
+    Ok(requests)
+}
+
+/// 导入 pcap / pcapng 抓包文件，返回其中重组出的明文 HTTP/1.x 请求/响应对。
+/// 传入文件路径由后端直接读取，避免抓包内容以 JSON 数组形式经过 IPC。
+/// 仅供后端调用，前端尚未接入。
+#[tauri::command]
+fn import_pcap(state: State<'_, DecoderState>, path: String) -> Result<Vec<HttpExchange>, AppError> {
+    let data = fs::read(&path)
+        .map_err(|e| AppError::InternalError(format!("Failed to read capture file: {e}")))?;
+    let exchanges = pcap_parser::parse_pcap(&data)
+        .ok_or_else(|| AppError::ParseError("Not a pcap or pcapng file".to_string()))?;
+    let (registry, warnings) = state.registry(None);
+    finish_exchanges(exchanges, &registry, &warnings)
+}
+
+/// 报文后处理：传输解码（去分块、解压）→ 按 Content-Type 解析 body → 递归解码，
+/// 并附上解码器设置的警告（设置或规则文件有误时）。
+fn post_process(result: &mut ParseResult, registry: &DecoderRegistry, warnings: &[String]) {
+    body_decoding::apply_body_decoding(result);
+    body_parser::apply_body_parsing(result);
+    decoder::apply_recursive_decode(result, registry);
+    if !warnings.is_empty() {
+        result
+            .warnings
+            .get_or_insert_with(Vec::new)
+            .extend(warnings.iter().cloned());
+    }
 }
 
 /// 检测文本是否像 HTTP 数据。
@@ -66,12 +238,18 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .manage(watcher_state.clone())
         .setup(move |app| {
+            app.manage(DecoderState::load(app.handle()));
             let app_handle = app.handle().clone();
             clipboard::start_clipboard_watcher(app_handle, watcher_state.clone());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             parse_text,
+            parse_exchanges,
+            parse_har_text,
+            parse_curl_verbose_text,
+            import_collection,
+            import_pcap,
             check_http_like,
             toggle_clipboard_watcher,
             get_clipboard_watcher_status,
@@ -81,6 +259,8 @@ pub fn run() {
             history::history_rename,
             history::history_delete,
             history::history_clear,
+            settings::settings_get,
+            settings::settings_save,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// 解析树中的单个键值节点。
 /// 对应 Tech.md 中的 ParseNode 接口。
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ParseNode {
     pub key: String,
@@ -15,30 +15,90 @@ pub struct ParseNode {
     pub decoded_value: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value_type: Option<String>,
+    /// 值的解码步骤（url_encoded → base64 → json → jwt），按执行顺序排列，包含父节点的步骤
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Vec<String>>,
 }
 
 /// 检测到的 HTTP 内容类型
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum HttpContentType {
     Request,
     Response,
     HeadersOnly,
+    #[default]
     Unknown,
 }
 
 /// 解析引擎返回的完整结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ParseResult {
     pub content_type: HttpContentType,
     pub method: Option<String>,
     pub url: Option<String>,
+    /// 请求行中的原始 request-target（origin / absolute / authority / asterisk 形式）
+    pub request_target: Option<String>,
+    /// URL 结构拆解（scheme / userinfo / host / port / path / query / fragment）
+    pub url_nodes: Option<Vec<ParseNode>>,
     pub status_code: Option<u16>,
     pub status_text: Option<String>,
     pub protocol: Option<String>,
     pub headers: Vec<ParseNode>,
+    /// HTTP/2、HTTP/3 伪头部（`:method`、`:path`、`:status` 等）
+    pub pseudo_headers: Option<Vec<ParseNode>>,
     pub query_params: Option<Vec<ParseNode>>,
+    /// 按方括号 / 点号 / 重复 key 还原的嵌套查询参数，query_params 保留扁平形式
+    pub query_tree: Option<Vec<ParseNode>>,
     pub body: Option<String>,
+    /// 按 Content-Type 解析出的 body 结构（JSON / 表单 / XML / 文本）
+    pub body_nodes: Option<Vec<ParseNode>>,
+    /// 去分块 / 解压之前的原始 body，仅在 body 经过传输解码时保留
+    pub wire_body: Option<String>,
+    /// body 的解码步骤（chunked → base64 → gzip …），按执行顺序排列
+    pub body_provenance: Option<Vec<ParseNode>>,
+    /// chunked body 末尾的 trailer 字段
+    pub trailers: Option<Vec<ParseNode>>,
+    /// 不属于 HTTP 报文本身的附加信息（如连接、TLS 握手说明）
+    pub metadata: Option<Vec<ParseNode>>,
+    /// 解析过程中的提示（如未解析的变量），不影响结果本身
+    pub warnings: Option<Vec<String>>,
     pub raw_text: String,
 }
+
+/// 从 Postman / Insomnia 集合导入的单个请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionRequest {
+    /// 所在文件夹路径（如 `Users/Admin`），位于集合根目录时为空字符串
+    pub folder: String,
+    pub name: String,
+    pub request: ParseResult,
+}
+
+/// 一次 HTTP 交互：请求与响应均可缺省（例如只粘贴了响应）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpExchange {
+    pub request: Option<ParseResult>,
+    pub response: Option<ParseResult>,
+    pub started_at: Option<String>,
+    pub duration_ms: Option<f64>,
+}
+
+impl HttpExchange {
+    /// 用单条请求或响应构造交互，按 content_type 放入对应位置。
+    pub fn from_message(message: ParseResult) -> Self {
+        let (request, response) = match message.content_type {
+            HttpContentType::Response => (None, Some(message)),
+            _ => (Some(message), None),
+        };
+        HttpExchange {
+            request,
+            response,
+            started_at: None,
+            duration_ms: None,
+        }
+    }
+}
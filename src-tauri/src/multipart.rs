@@ -0,0 +1,530 @@
+use crate::models::ParseNode;
+
+/// part 内容预览的最大字符数
+const PREVIEW_LIMIT: usize = 200;
+
+/// 常见二进制格式的文件头特征：（偏移, 特征字节, MIME 类型）。
+/// 粘贴文本中非 UTF-8 字节已被替换为 U+FFFD，因此只匹配 ASCII 部分。
+const MAGIC_SIGNATURES: &[(usize, &str, &str)] = &[
+    (0, "%PDF-", "application/pdf"),
+    (0, "GIF87a", "image/gif"),
+    (0, "GIF89a", "image/gif"),
+    (0, "PK\u{3}\u{4}", "application/zip"),
+    (0, "BM", "image/bmp"),
+    (4, "ftyp", "video/mp4"),
+];
+
+/// 从 Content-Type 中提取 multipart 边界（`boundary=...`，可带引号）。
+pub fn boundary_from_content_type(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("boundary") {
+            return None;
+        }
+        let value = value.trim().trim_matches('"');
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    })
+}
+
+/// 没有 Content-Type 时，从首行 `--boundary` 推断边界。
+pub fn infer_boundary(body: &str) -> Option<String> {
+    let first_line = body.trim_start().lines().next()?.trim_end_matches('\r');
+    let boundary = first_line.strip_prefix("--")?;
+    let valid =
+        !boundary.is_empty() && boundary.len() <= 70 && !boundary.contains(char::is_whitespace);
+    valid.then(|| boundary.to_string())
+}
+
+/// 按边界拆分 multipart/form-data body，每个 part 为一个节点。
+/// 只有 Content-Disposition 的普通文本字段直接作为叶子节点（与表单字段一致）；
+/// 带文件名或其他 header 的 part 为 value_type 为 `part` 的容器，包含 filename、各 header 和 content。
+pub fn parse_multipart(body: &str, boundary: &str) -> Option<Vec<ParseNode>> {
+    let delimiter = format!("--{boundary}");
+    let mut segments = body.split(delimiter.as_str());
+    // 第一个边界之前为 preamble，忽略
+    segments.next()?;
+
+    let mut parts = Vec::new();
+    for (index, segment) in segments.enumerate() {
+        if segment.starts_with("--") {
+            break;
+        }
+        let segment = strip_line_break_prefix(segment);
+        let segment = strip_line_break_suffix(segment);
+        parts.push(parse_part(segment, index));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts)
+    }
+}
+
+fn strip_line_break_prefix(s: &str) -> &str {
+    s.strip_prefix("\r\n")
+        .or_else(|| s.strip_prefix('\n'))
+        .unwrap_or(s)
+}
+
+fn strip_line_break_suffix(s: &str) -> &str {
+    s.strip_suffix("\r\n")
+        .or_else(|| s.strip_suffix('\n'))
+        .unwrap_or(s)
+}
+
+fn parse_part(segment: &str, index: usize) -> ParseNode {
+    let (header_block, content) = split_part_headers(segment);
+
+    let headers: Vec<(String, String)> = header_block
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.trim_end_matches('\r').split_once(':')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+
+    let disposition = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-disposition"))
+        .map(|(_, value)| disposition_params(value))
+        .unwrap_or_default();
+    let param = |key: &str| {
+        disposition
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.clone())
+    };
+    let name = param("name").unwrap_or_else(|| format!("part{index}"));
+    let filename = param("filename*")
+        .map(|v| decode_ext_value(&v))
+        .or_else(|| param("filename"));
+    let declared_type = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        .map(|(_, value)| value.clone());
+
+    let binary = is_binary_text(content);
+    let has_extra_headers = headers
+        .iter()
+        .any(|(name, _)| !name.eq_ignore_ascii_case("content-disposition"));
+
+    if filename.is_none() && !has_extra_headers && !binary {
+        return leaf(name, content.to_string());
+    }
+
+    let mut children = Vec::new();
+    if let Some(filename) = &filename {
+        children.push(leaf("filename".to_string(), filename.clone()));
+    }
+    for (header_name, header_value) in &headers {
+        let mut node = leaf(header_name.clone(), header_value.clone());
+        if header_name.eq_ignore_ascii_case("content-disposition") {
+            node.value_type = Some("header".to_string());
+            node.children = Some(
+                disposition
+                    .iter()
+                    .map(|(k, v)| leaf(k.clone(), v.clone()))
+                    .collect(),
+            );
+        }
+        children.push(node);
+    }
+
+    let (preview, content_node) = if binary {
+        let node = binary_node("content", content, declared_type.as_deref());
+        (node.value.clone(), node)
+    } else {
+        (
+            preview(content),
+            leaf("content".to_string(), content.to_string()),
+        )
+    };
+    children.push(content_node);
+
+    let mut description = filename
+        .map(|f| format!("filename: {f}"))
+        .unwrap_or_default();
+    if let Some(declared) = &declared_type {
+        if !description.is_empty() {
+            description.push_str(", ");
+        }
+        description.push_str(declared);
+    }
+
+    ParseNode {
+        key: name,
+        value: preview,
+        children: Some(children),
+        description: if description.is_empty() {
+            None
+        } else {
+            Some(description)
+        },
+        value_type: Some("part".to_string()),
+        ..Default::default()
+    }
+}
+
+/// 在第一个空行处拆分 part 的 header 与内容；没有空行时整段视为内容。
+fn split_part_headers(segment: &str) -> (&str, &str) {
+    let crlf = segment.find("\r\n\r\n").map(|i| (i, 4));
+    let lf = segment.find("\n\n").map(|i| (i, 2));
+    let split = match (crlf, lf) {
+        (Some(a), Some(b)) => Some(if a.0 <= b.0 { a } else { b }),
+        (a, b) => a.or(b),
+    };
+    match split {
+        Some((pos, len)) => (&segment[..pos], &segment[pos + len..]),
+        None if segment.contains(':') && !segment.contains('\n') => (segment, ""),
+        None => ("", segment),
+    }
+}
+
+/// 解析 `form-data; name="file"; filename="a b.png"` 的参数，支持引号与 `\"` 转义。
+fn disposition_params(value: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    // 跳过 disposition 类型（form-data / attachment）
+    for c in chars.by_ref() {
+        if c == ';' {
+            break;
+        }
+    }
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace() || *c == ';') {
+            chars.next();
+        }
+        let mut key = String::new();
+        while let Some(&c) = chars.peek() {
+            if c == '=' || c == ';' {
+                break;
+            }
+            key.push(c);
+            chars.next();
+        }
+        if key.trim().is_empty() {
+            break;
+        }
+
+        let mut val = String::new();
+        if chars.peek() == Some(&'=') {
+            chars.next();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                val.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        _ => val.push(c),
+                    }
+                }
+            } else {
+                while let Some(&c) = chars.peek() {
+                    if c == ';' {
+                        break;
+                    }
+                    val.push(c);
+                    chars.next();
+                }
+            }
+        }
+        params.push((key.trim().to_string(), val.trim().to_string()));
+    }
+    params
+}
+
+/// 解码 RFC 5987 扩展值：`UTF-8''%E4%BD%A0.txt`。
+fn decode_ext_value(value: &str) -> String {
+    let encoded = value.splitn(3, '\'').nth(2).unwrap_or(value);
+    url::form_urlencoded::parse(format!("v={}", encoded.replace('+', "%2B")).as_bytes())
+        .next()
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_else(|| encoded.to_string())
+}
+
+fn leaf(key: String, value: String) -> ParseNode {
+    ParseNode {
+        key,
+        value,
+        ..Default::default()
+    }
+}
+
+fn preview(content: &str) -> String {
+    if content.chars().count() > PREVIEW_LIMIT {
+        let cut: String = content.chars().take(PREVIEW_LIMIT).collect();
+        format!("{cut}…")
+    } else {
+        content.to_string()
+    }
+}
+
+/// 判断粘贴文本是否为二进制内容：包含 NUL、U+FFFD 替换字符，或控制字符占比过高。
+pub fn is_binary_text(content: &str) -> bool {
+    if content.is_empty() {
+        return false;
+    }
+    if content.contains('\0') || content.contains('\u{FFFD}') {
+        return true;
+    }
+    let control = content
+        .chars()
+        .filter(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+        .count();
+    control * 10 > content.chars().count()
+}
+
+/// 二进制内容的摘要节点：value 为 `[binary 类型, 大小]`，description 为推断出的 MIME 类型。
+pub fn binary_node(key: &str, content: &str, declared_type: Option<&str>) -> ParseNode {
+    let mime = sniff_binary_type(content)
+        .or(declared_type)
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    ParseNode {
+        key: key.to_string(),
+        value: format!("[binary {mime}, {}]", format_size(content.len())),
+        description: Some(mime),
+        value_type: Some("binary".to_string()),
+        ..Default::default()
+    }
+}
+
+/// 按文件头特征推断二进制内容的类型。
+pub fn sniff_binary_type(content: &str) -> Option<&'static str> {
+    let bytes = content.as_bytes();
+    // PNG 首字节 0x89 会被替换为 U+FFFD（3 字节），随后为 `PNG\r\n`
+    if content.starts_with("\u{FFFD}PNG") {
+        return Some("image/png");
+    }
+    if content.starts_with("\u{1F}\u{FFFD}") {
+        return Some("application/gzip");
+    }
+    if content.starts_with("RIFF") && content.get(8..12) == Some("WEBP") {
+        return Some("image/webp");
+    }
+    MAGIC_SIGNATURES
+        .iter()
+        .find(|(offset, magic, _)| {
+            bytes.get(*offset..*offset + magic.len()) == Some(magic.as_bytes())
+        })
+        .map(|(_, _, mime)| *mime)
+        .or_else(|| {
+            // JPEG 的 `FF D8 FF E0` 同样被替换，JFIF 标记后移
+            let head: String = content.chars().take(16).collect();
+            (head.contains("JFIF") || head.contains("Exif")).then_some("image/jpeg")
+        })
+}
+
+/// 以 B / KB / MB 显示字节数。
+pub fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// 由命令行或代码片段生成 multipart body 时使用的边界（curl、requests 均为随机生成，这里固定以便复现）
+pub const FORM_BOUNDARY: &str = "------------------------7a8f3c2e1b9d4f60";
+
+/// 生成 multipart body 的一个字段（curl `-F`、requests `files=` 等）
+pub struct FormPart {
+    pub name: String,
+    pub value: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+}
+
+/// 按扩展名推断上传文件的类型（与 curl 内置的对照表一致）。
+pub fn guess_content_type(path: &str) -> &'static str {
+    let ext = path.rsplit('.').next().unwrap_or("").to_lowercase();
+    match ext.as_str() {
+        "gif" => "image/gif",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "txt" => "text/plain",
+        "htm" | "html" => "text/html",
+        "pdf" => "application/pdf",
+        "xml" => "application/xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 按字段生成 multipart/form-data body。文件内容无法读取，保留为空。
+pub fn build_multipart_body(parts: &[FormPart], boundary: &str) -> String {
+    let mut body = String::new();
+    for part in parts {
+        body.push_str(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"",
+            part.name
+        ));
+        if let Some(filename) = &part.filename {
+            body.push_str(&format!("; filename=\"{filename}\""));
+        }
+        body.push_str("\r\n");
+        if let Some(content_type) = &part.content_type {
+            body.push_str(&format!("Content-Type: {content_type}\r\n"));
+        }
+        body.push_str("\r\n");
+        body.push_str(&part.value);
+        body.push_str("\r\n");
+    }
+    body.push_str(&format!("--{boundary}--\r\n"));
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDARY: &str = "----WebKitFormBoundary7MA4YWxkTrZu0gW";
+
+    fn upload_body() -> String {
+        format!(
+            "--{b}\r\n\
+Content-Disposition: form-data; name=\"title\"\r\n\
+\r\n\
+My photo\r\n\
+--{b}\r\n\
+Content-Disposition: form-data; name=\"meta\"\r\n\
+Content-Type: application/json\r\n\
+\r\n\
+{{\"tags\":[\"a\"]}}\r\n\
+--{b}\r\n\
+Content-Disposition: form-data; name=\"file\"; filename=\"cat.png\"\r\n\
+Content-Type: image/png\r\n\
+\r\n\
+\u{FFFD}PNG\r\n\u{1A}\n\0\0\0\rIHDR\r\n\
+--{b}--\r\n",
+            b = BOUNDARY
+        )
+    }
+
+    #[test]
+    fn test_boundary_from_content_type() {
+        assert_eq!(
+            boundary_from_content_type(&format!("multipart/form-data; boundary={BOUNDARY}")),
+            Some(BOUNDARY.to_string())
+        );
+        assert_eq!(
+            boundary_from_content_type("multipart/form-data; charset=utf-8; Boundary=\"abc def\""),
+            Some("abc def".to_string())
+        );
+        assert!(boundary_from_content_type("multipart/form-data").is_none());
+    }
+
+    #[test]
+    fn test_infer_boundary() {
+        assert_eq!(infer_boundary(&upload_body()), Some(BOUNDARY.to_string()));
+        assert!(infer_boundary("-- not a boundary").is_none());
+        assert!(infer_boundary("a=1").is_none());
+    }
+
+    #[test]
+    fn test_text_field_is_leaf() {
+        let parts = parse_multipart(&upload_body(), BOUNDARY).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].key, "title");
+        assert_eq!(parts[0].value, "My photo");
+        assert!(parts[0].children.is_none());
+        assert!(parts[0].value_type.is_none());
+    }
+
+    #[test]
+    fn test_part_with_headers() {
+        let parts = parse_multipart(&upload_body(), BOUNDARY).unwrap();
+        let meta = &parts[1];
+        assert_eq!(meta.key, "meta");
+        assert_eq!(meta.value_type.as_deref(), Some("part"));
+        assert_eq!(meta.value, "{\"tags\":[\"a\"]}");
+        assert_eq!(meta.description.as_deref(), Some("application/json"));
+
+        let children = meta.children.as_ref().unwrap();
+        assert_eq!(children[0].key, "Content-Disposition");
+        assert_eq!(children[0].children.as_ref().unwrap()[0].value, "meta");
+        assert_eq!(children[1].key, "Content-Type");
+        let content = children.last().unwrap();
+        assert_eq!(content.key, "content");
+        assert!(content.value_type.is_none());
+    }
+
+    #[test]
+    fn test_binary_file_part_summarised() {
+        let parts = parse_multipart(&upload_body(), BOUNDARY).unwrap();
+        let file = &parts[2];
+        assert_eq!(file.key, "file");
+        assert_eq!(
+            file.description.as_deref(),
+            Some("filename: cat.png, image/png")
+        );
+
+        let children = file.children.as_ref().unwrap();
+        assert_eq!(children[0].key, "filename");
+        assert_eq!(children[0].value, "cat.png");
+
+        let content = children.last().unwrap();
+        assert_eq!(content.value_type.as_deref(), Some("binary"));
+        assert_eq!(content.description.as_deref(), Some("image/png"));
+        assert!(content.value.starts_with("[binary image/png, "));
+        assert!(file.value.ends_with(" B]"));
+    }
+
+    #[test]
+    fn test_lf_only_and_text_file() {
+        let body = "--XyZ\nContent-Disposition: form-data; name=\"doc\"; filename*=UTF-8''%E4%BD%A0%E5%A5%BD.txt\n\nhello\nworld\n--XyZ--";
+        let parts = parse_multipart(body, "XyZ").unwrap();
+        assert_eq!(parts.len(), 1);
+        let children = parts[0].children.as_ref().unwrap();
+        assert_eq!(children[0].value, "你好.txt");
+        assert_eq!(children.last().unwrap().value, "hello\nworld");
+    }
+
+    #[test]
+    fn test_unnamed_part_and_escaped_quotes() {
+        let body =
+            "--b\r\nContent-Disposition: form-data; filename=\"a \\\"q\\\".txt\"\r\n\r\nx\r\n--b--";
+        let parts = parse_multipart(body, "b").unwrap();
+        assert_eq!(parts[0].key, "part0");
+        assert_eq!(parts[0].children.as_ref().unwrap()[0].value, "a \"q\".txt");
+    }
+
+    #[test]
+    fn test_wrong_boundary() {
+        assert!(parse_multipart("--a\r\n\r\nx\r\n--a--", "zzz").is_none());
+    }
+
+    #[test]
+    fn test_sniff_binary_type() {
+        assert_eq!(
+            sniff_binary_type("%PDF-1.7\n\u{FFFD}"),
+            Some("application/pdf")
+        );
+        assert_eq!(sniff_binary_type("GIF89a\u{1}\0"), Some("image/gif"));
+        assert_eq!(
+            sniff_binary_type("\u{FFFD}\u{FFFD}\u{FFFD}\u{FFFD}\0\u{10}JFIF\0"),
+            Some("image/jpeg")
+        );
+        assert!(sniff_binary_type("plain").is_none());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(12), "12 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
@@ -1,43 +1,206 @@
 use regex::Regex;
 use std::sync::LazyLock;
 
-use crate::models::{HttpContentType, ParseNode, ParseResult};
+use crate::models::{HttpContentType, HttpExchange, ParseNode, ParseResult};
 use crate::parse_utils;
+use crate::query_tree;
 
+/// 请求行：method 为 RFC 9110 token，可选 HTTP 版本。
 static RE_REQUEST_LINE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"^(GET|POST|PUT|DELETE|PATCH|HEAD|OPTIONS|TRACE|CONNECT)\s+(\S+)(?:\s+HTTP/(\d\.\d))?\s*$",
-    )
-    .unwrap()
+    Regex::new(r"^([!#$%&'*+.^_`|~0-9A-Za-z-]+)\s+(\S+)(?:\s+HTTP/(\d(?:\.\d)?))?\s*$").unwrap()
 });
 
+static RE_AUTHORITY_FORM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\[[0-9A-Fa-f:.]+\]|[A-Za-z0-9.-]+):\d+$").unwrap());
+
 static RE_RESPONSE_LINE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^HTTP/(\d\.\d)\s+(\d{3})\s*(.*)$").unwrap());
+    LazyLock::new(|| Regex::new(r"^HTTP/(\d(?:\.\d)?)\s+(\d{3})\s*(.*)$").unwrap());
 
 static RE_HEADER_LINE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^([\w-]+):\s*(.*)$").unwrap());
+    LazyLock::new(|| Regex::new(r"^(:?[\w-]+):\s*(.*)$").unwrap());
 
 /// 将原始 HTTP 文本解析为结构化的 ParseResult。
+/// 文本包含多条报文（如请求后紧跟响应）时只返回第一条，完整列表见 `parse_http_messages`。
 pub fn parse_http_text(raw: &str) -> ParseResult {
+    let segments = split_http_messages(raw);
+    match segments.first() {
+        Some(first) if segments.len() > 1 => parse_single_message(first),
+        _ => parse_single_message(raw),
+    }
+}
+
+/// 将原始 HTTP 文本切分为一条或多条报文并分别解析。
+pub fn parse_http_messages(raw: &str) -> Vec<ParseResult> {
+    let segments = split_http_messages(raw);
+    if segments.len() <= 1 {
+        return vec![parse_single_message(raw)];
+    }
+    segments.into_iter().map(parse_single_message).collect()
+}
+
+/// 将原始 HTTP 文本解析为交互列表：每个请求与紧随其后的响应配对。
+pub fn parse_http_exchanges(raw: &str) -> Vec<HttpExchange> {
+    let mut exchanges: Vec<HttpExchange> = Vec::new();
+    for message in parse_http_messages(raw) {
+        if let HttpContentType::Response = message.content_type {
+            if let Some(last) = exchanges.last_mut() {
+                if last.request.is_some() && last.response.is_none() {
+                    last.response = Some(message);
+                    continue;
+                }
+            }
+        }
+        exchanges.push(HttpExchange::from_message(message));
+    }
+    exchanges
+}
+
+/// 按起始行切分报文：首行之后出现的响应状态行，或带协议版本的请求行，视为新报文的开始。
+fn split_http_messages(raw: &str) -> Vec<&str> {
+    let trimmed = raw.trim();
+    let mut starts = vec![0];
+    let mut offset = 0;
+
+    for (idx, line) in trimmed.split_inclusive('\n').enumerate() {
+        let content = line.trim_end();
+        if idx > 0 && (RE_RESPONSE_LINE.is_match(content) || is_versioned_request_line(content)) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(trimmed.len());
+            trimmed[start..end].trim()
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// 解析请求行，返回 (method, request-target, HTTP 版本)。
+/// 带版本号时接受任意 token method；不带版本号时要求 method 为大写且 target 为
+/// 四种合法形式之一，避免把普通英文句子误识别为请求行。
+fn parse_request_line(line: &str) -> Option<(String, String, Option<String>)> {
+    let caps = RE_REQUEST_LINE.captures(line)?;
+    let method = caps[1].to_string();
+    let target = caps[2].to_string();
+    let version = caps.get(3).map(|m| m.as_str().to_string());
+
+    if version.is_none() {
+        let upper_method = method
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c == '-' || c == '_');
+        let known_form = target.starts_with('/')
+            || target == "*"
+            || target.contains("://")
+            || RE_AUTHORITY_FORM.is_match(&target);
+        if !upper_method || !known_form {
+            return None;
+        }
+    }
+
+    Some((method, target, version))
+}
+
+/// 由 request-target 得到完整 URL：
+/// - absolute-form（`http://host/path`）原样返回
+/// - origin-form（`/path?q`）结合 `Host`（或 `:authority`）补全为 `scheme://host/path?q`
+/// - asterisk-form（`OPTIONS *`）补全为 `scheme://host`
+/// - authority-form（`CONNECT host:port`）以及缺少 Host 的情况原样返回
+fn resolve_request_url(
+    target: &str,
+    headers: &[ParseNode],
+    pseudo_headers: Option<&[ParseNode]>,
+) -> String {
+    if !target.starts_with('/') && target != "*" {
+        return target.to_string();
+    }
+
+    let host = headers
+        .iter()
+        .find(|h| h.key.eq_ignore_ascii_case("host"))
+        .map(|h| h.value.trim())
+        .or_else(|| pseudo_headers.and_then(|p| parse_utils::pseudo_header_value(p, ":authority")))
+        .filter(|h| !h.is_empty());
+
+    let host = match host {
+        Some(h) => h,
+        None => return target.to_string(),
+    };
+
+    let scheme = infer_scheme(host, headers, pseudo_headers);
+    if target == "*" {
+        format!("{scheme}://{host}")
+    } else {
+        format!("{scheme}://{host}{target}")
+    }
+}
+
+/// 推断 origin-form 请求的 scheme：优先 `:scheme`，其次同 host 的 `Origin` / `Referer`，
+/// 端口为 443 时为 https，否则默认 http。
+fn infer_scheme(host: &str, headers: &[ParseNode], pseudo_headers: Option<&[ParseNode]>) -> String {
+    if let Some(scheme) =
+        pseudo_headers.and_then(|p| parse_utils::pseudo_header_value(p, ":scheme"))
+    {
+        return scheme.to_lowercase();
+    }
+
+    for name in ["origin", "referer"] {
+        let same_host_scheme = headers
+            .iter()
+            .filter(|h| h.key.eq_ignore_ascii_case(name))
+            .filter_map(|h| url::Url::parse(h.value.trim()).ok())
+            .find(|u| {
+                let authority = match u.port() {
+                    Some(port) => format!("{}:{port}", u.host_str().unwrap_or("")),
+                    None => u.host_str().unwrap_or("").to_string(),
+                };
+                authority.eq_ignore_ascii_case(host)
+            })
+            .map(|u| u.scheme().to_string());
+        if let Some(scheme) = same_host_scheme {
+            return scheme;
+        }
+    }
+
+    if host.ends_with(":443") {
+        "https".to_string()
+    } else {
+        "http".to_string()
+    }
+}
+
+fn is_versioned_request_line(line: &str) -> bool {
+    RE_REQUEST_LINE
+        .captures(line)
+        .is_some_and(|caps| caps.get(3).is_some())
+}
+
+/// 解析单条 HTTP 报文（请求、响应或仅 headers）。
+fn parse_single_message(raw: &str) -> ParseResult {
     let raw_text = raw.to_string();
     let trimmed = raw.trim();
     let mut lines = trimmed.lines();
 
     let first_line = lines.next().unwrap_or("");
 
-    let content_type;
+    let mut content_type;
     let mut method = None;
     let mut url_str = None;
+    let mut request_target: Option<String> = None;
     let mut status_code = None;
     let mut status_text = None;
     let mut protocol = None;
     let header_start_lines: Vec<&str>;
 
-    if let Some(caps) = RE_REQUEST_LINE.captures(first_line) {
+    if let Some((m, target, version)) = parse_request_line(first_line) {
         content_type = HttpContentType::Request;
-        method = Some(caps[1].to_string());
-        url_str = Some(caps[2].to_string());
-        protocol = caps.get(3).map(|m| format!("HTTP/{}", m.as_str()));
+        method = Some(m);
+        request_target = Some(target);
+        protocol = version.map(|v| format!("HTTP/{v}"));
         header_start_lines = lines.collect();
     } else if let Some(caps) = RE_RESPONSE_LINE.captures(first_line) {
         content_type = HttpContentType::Response;
@@ -78,16 +241,51 @@ pub fn parse_http_text(raw: &str) -> ParseResult {
                     key,
                     value,
                     children,
-                    description: None,
-                    decoded_value: None,
-                    value_type: None,
+                    ..Default::default()
                 }
             })
         })
         .collect();
 
+    // HTTP/2、HTTP/3 伪头部：单独保留，并补全缺失的 method / url / status
+    let (headers, pseudo_headers) = parse_utils::split_pseudo_headers(headers);
+    if let Some(pseudo) = &pseudo_headers {
+        if method.is_none() {
+            method = parse_utils::pseudo_header_value(pseudo, ":method").map(|m| m.to_string());
+        }
+        if request_target.is_none() {
+            request_target =
+                parse_utils::pseudo_header_value(pseudo, ":path").map(|p| p.to_string());
+            url_str = parse_utils::url_from_pseudo_headers(pseudo);
+        }
+        if status_code.is_none() {
+            status_code = parse_utils::pseudo_header_value(pseudo, ":status")
+                .and_then(|s| s.parse::<u16>().ok());
+        }
+        if matches!(content_type, HttpContentType::HeadersOnly) {
+            if method.is_some() {
+                content_type = HttpContentType::Request;
+            } else if status_code.is_some() {
+                content_type = HttpContentType::Response;
+            }
+        }
+        // 只有伪头部时无法区分 HTTP/2 与 HTTP/3，按 HTTP/2 处理；起始行写明的版本保持原样
+        if protocol.is_none() {
+            protocol = Some("HTTP/2".to_string());
+        }
+    }
+
+    if url_str.is_none() {
+        url_str = request_target
+            .as_deref()
+            .map(|target| resolve_request_url(target, &headers, pseudo_headers.as_deref()));
+    }
+
     let query_params = url_str.as_ref().and_then(|u| parse_utils::parse_query_params(u));
 
+    let query_tree = query_params.as_deref().and_then(query_tree::build_query_tree);
+    let url_nodes = url_str.as_ref().and_then(|u| parse_utils::parse_url_nodes(u));
+
     let body = if body_lines.is_empty() {
         None
     } else {
@@ -98,13 +296,18 @@ pub fn parse_http_text(raw: &str) -> ParseResult {
         content_type,
         method,
         url: url_str,
+        request_target,
+        url_nodes,
         status_code,
         status_text,
         protocol,
         headers,
+        pseudo_headers,
         query_params,
+        query_tree,
         body,
         raw_text,
+        ..Default::default()
     }
 }
 
@@ -119,7 +322,11 @@ mod tests {
 
         assert!(matches!(result.content_type, HttpContentType::Request));
         assert_eq!(result.method.as_deref(), Some("GET"));
-        assert_eq!(result.url.as_deref(), Some("/api/users?page=1&limit=10"));
+        assert_eq!(
+            result.url.as_deref(),
+            Some("http://example.com/api/users?page=1&limit=10")
+        );
+        assert_eq!(result.request_target.as_deref(), Some("/api/users?page=1&limit=10"));
         assert_eq!(result.protocol.as_deref(), Some("HTTP/1.1"));
         assert_eq!(result.headers.len(), 2);
         assert_eq!(result.headers[0].key, "Host");
@@ -222,6 +429,233 @@ mod tests {
         let result = parse_http_text(input);
 
         assert!(result.query_params.is_none());
+        assert!(result.query_tree.is_none());
+    }
+
+    #[test]
+    fn test_parse_nested_query_tree() {
+        let input = "GET /issues?filter[status][]=open&filter[owner]=me&sort[0]=name HTTP/1.1\nHost: example.com";
+        let result = parse_http_text(input);
+
+        let params = result.query_params.unwrap();
+        assert_eq!(params.len(), 3);
+        assert_eq!(params[0].key, "filter[status][]");
+
+        let tree = result.query_tree.unwrap();
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].key, "filter");
+        assert_eq!(tree[0].children.as_ref().unwrap().len(), 2);
+        assert_eq!(tree[1].key, "sort");
+    }
+
+    #[test]
+    fn test_request_followed_by_response_split() {
+        let input = "POST /api/login HTTP/1.1\nHost: example.com\nContent-Type: application/json\n\n{\"user\":\"alice\"}\n\nHTTP/1.1 200 OK\nContent-Type: application/json\n\n{\"ok\":true}";
+        let result = parse_http_text(input);
+
+        assert!(matches!(result.content_type, HttpContentType::Request));
+        assert_eq!(result.body.as_deref(), Some("{\"user\":\"alice\"}"));
+
+        let messages = parse_http_messages(input);
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[1].content_type, HttpContentType::Response));
+        assert_eq!(messages[1].status_code, Some(200));
+        assert_eq!(messages[1].body.as_deref(), Some("{\"ok\":true}"));
+    }
+
+    #[test]
+    fn test_response_without_blank_line_split() {
+        let input = "GET / HTTP/1.1\nHost: example.com\nHTTP/1.1 304 Not Modified\nETag: \"abc\"";
+        let messages = parse_http_messages(input);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].headers.len(), 1);
+        assert_eq!(messages[1].status_code, Some(304));
+    }
+
+    #[test]
+    fn test_unversioned_request_line_in_body_not_split() {
+        let input = "POST /notes HTTP/1.1\nHost: example.com\n\nGET milk\nDELETE spam";
+        let messages = parse_http_messages(input);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].body.as_deref(), Some("GET milk\nDELETE spam"));
+    }
+
+    #[test]
+    fn test_exchanges_pairing() {
+        let input = "GET /a HTTP/1.1\nHost: example.com\n\nHTTP/1.1 200 OK\nServer: nginx\n\nGET /b HTTP/1.1\nHost: example.com\n\nHTTP/1.1 404 Not Found\nServer: nginx";
+        let exchanges = parse_http_exchanges(input);
+
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(
+            exchanges[0].request.as_ref().unwrap().url.as_deref(),
+            Some("http://example.com/a")
+        );
+        assert_eq!(exchanges[0].response.as_ref().unwrap().status_code, Some(200));
+        assert_eq!(
+            exchanges[1].request.as_ref().unwrap().url.as_deref(),
+            Some("http://example.com/b")
+        );
+        assert_eq!(exchanges[1].response.as_ref().unwrap().status_code, Some(404));
+    }
+
+    #[test]
+    fn test_exchanges_response_only() {
+        let exchanges = parse_http_exchanges("HTTP/1.1 500 Internal Server Error\nServer: nginx");
+        assert_eq!(exchanges.len(), 1);
+        assert!(exchanges[0].request.is_none());
+        assert_eq!(exchanges[0].response.as_ref().unwrap().status_code, Some(500));
+    }
+
+    #[test]
+    fn test_exchanges_consecutive_requests() {
+        let exchanges =
+            parse_http_exchanges("GET /a HTTP/1.1\nHost: a.com\n\nGET /b HTTP/1.1\nHost: b.com");
+        assert_eq!(exchanges.len(), 2);
+        assert!(exchanges[0].response.is_none());
+        assert!(exchanges[1].response.is_none());
+    }
+
+    #[test]
+    fn test_parse_http2_request_line() {
+        let input = "GET /api/users HTTP/2\nHost: example.com";
+        let result = parse_http_text(input);
+        assert!(matches!(result.content_type, HttpContentType::Request));
+        assert_eq!(result.protocol.as_deref(), Some("HTTP/2"));
+    }
+
+    #[test]
+    fn test_parse_http2_response() {
+        let input = "HTTP/2 200\ncontent-type: application/json\nserver: nginx";
+        let result = parse_http_text(input);
+        assert!(matches!(result.content_type, HttpContentType::Response));
+        assert_eq!(result.status_code, Some(200));
+        assert_eq!(result.status_text.as_deref(), Some(""));
+        assert_eq!(result.protocol.as_deref(), Some("HTTP/2"));
+        assert_eq!(result.headers.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_http3_request_line() {
+        let result = parse_http_text("GET /api HTTP/3\nHost: example.com");
+        assert_eq!(result.protocol.as_deref(), Some("HTTP/3"));
+
+        let input = "GET /api HTTP/3\n:authority: example.com\n:method: GET\n:path: /api";
+        let result = parse_http_text(input);
+        assert_eq!(result.protocol.as_deref(), Some("HTTP/3"));
+        let exchanges = crate::parser::parse_http_exchanges(input);
+        assert_eq!(exchanges[0].request.as_ref().unwrap().protocol.as_deref(), Some("HTTP/3"));
+    }
+
+    #[test]
+    fn test_parse_http3_response() {
+        let result = parse_http_text("HTTP/3 404\nserver: cloudflare");
+        assert_eq!(result.status_code, Some(404));
+        assert_eq!(result.protocol.as_deref(), Some("HTTP/3"));
+    }
+
+    #[test]
+    fn test_parse_pseudo_header_request() {
+        let input = ":authority: example.com\n:method: POST\n:path: /api/items?id=7\n:scheme: https\naccept: */*\ncontent-type: application/json";
+        let result = parse_http_text(input);
+
+        assert!(matches!(result.content_type, HttpContentType::Request));
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api/items?id=7"));
+        assert_eq!(result.request_target.as_deref(), Some("/api/items?id=7"));
+        assert_eq!(result.protocol.as_deref(), Some("HTTP/2"));
+        assert_eq!(result.headers.len(), 2);
+        assert_eq!(result.query_params.unwrap()[0].key, "id");
+
+        let pseudo = result.pseudo_headers.unwrap();
+        assert_eq!(pseudo.len(), 4);
+        assert_eq!(pseudo[0].key, ":authority");
+        assert_eq!(pseudo[0].value, "example.com");
+    }
+
+    #[test]
+    fn test_parse_pseudo_header_response() {
+        let input = ":status: 301\nlocation: https://example.com/\ncache-control: no-cache";
+        let result = parse_http_text(input);
+
+        assert!(matches!(result.content_type, HttpContentType::Response));
+        assert_eq!(result.status_code, Some(301));
+        assert_eq!(result.headers.len(), 2);
+        assert_eq!(result.pseudo_headers.unwrap()[0].key, ":status");
+    }
+
+    #[test]
+    fn test_http2_request_then_response_split() {
+        let input = "GET / HTTP/2\nhost: example.com\n\nHTTP/2 200\ncontent-type: text/html";
+        let messages = parse_http_messages(input);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].status_code, Some(200));
+    }
+
+    #[test]
+    fn test_parse_extension_methods() {
+        for method in ["PROPFIND", "MKCOL", "REPORT", "PURGE", "X-CUSTOM_op"] {
+            let input = format!("{method} /dav/files/ HTTP/1.1\nHost: dav.example.com");
+            let result = parse_http_text(&input);
+            assert!(matches!(result.content_type, HttpContentType::Request));
+            assert_eq!(result.method.as_deref(), Some(method));
+        }
+    }
+
+    #[test]
+    fn test_unversioned_extension_method() {
+        let result = parse_http_text("PURGE /cache/item\nHost: cdn.example.com");
+        assert_eq!(result.method.as_deref(), Some("PURGE"));
+        assert_eq!(result.url.as_deref(), Some("http://cdn.example.com/cache/item"));
+    }
+
+    #[test]
+    fn test_prose_first_line_not_request() {
+        let result = parse_http_text("Hello world\nX-Token: abc");
+        assert!(matches!(result.content_type, HttpContentType::HeadersOnly));
+        assert!(result.method.is_none());
+    }
+
+    #[test]
+    fn test_absolute_form() {
+        let input = "GET http://proxy.example.com/status?x=1 HTTP/1.1\nHost: other.example.com";
+        let result = parse_http_text(input);
+        assert_eq!(result.url.as_deref(), Some("http://proxy.example.com/status?x=1"));
+        assert_eq!(result.query_params.unwrap()[0].key, "x");
+    }
+
+    #[test]
+    fn test_authority_form() {
+        let result = parse_http_text("CONNECT example.com:443 HTTP/1.1\nHost: example.com:443");
+        assert_eq!(result.method.as_deref(), Some("CONNECT"));
+        assert_eq!(result.url.as_deref(), Some("example.com:443"));
+        assert_eq!(result.request_target.as_deref(), Some("example.com:443"));
+    }
+
+    #[test]
+    fn test_asterisk_form() {
+        let result = parse_http_text("OPTIONS * HTTP/1.1\nHost: example.com");
+        assert_eq!(result.method.as_deref(), Some("OPTIONS"));
+        assert_eq!(result.request_target.as_deref(), Some("*"));
+        assert_eq!(result.url.as_deref(), Some("http://example.com"));
+    }
+
+    #[test]
+    fn test_scheme_from_origin_header() {
+        let input = "POST /api HTTP/1.1\nHost: example.com\nOrigin: https://example.com";
+        let result = parse_http_text(input);
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api"));
+    }
+
+    #[test]
+    fn test_scheme_from_port_443() {
+        let result = parse_http_text("GET /x HTTP/1.1\nHost: example.com:443");
+        assert_eq!(result.url.as_deref(), Some("https://example.com:443/x"));
+    }
+
+    #[test]
+    fn test_origin_form_without_host() {
+        let result = parse_http_text("GET /only/path HTTP/1.1\nAccept: */*");
+        assert_eq!(result.url.as_deref(), Some("/only/path"));
     }
 
     #[test]
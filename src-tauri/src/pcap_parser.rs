@@ -0,0 +1,986 @@
+use chrono::{DateTime, SecondsFormat};
+use regex::bytes::Regex;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
+
+use crate::models::HttpExchange;
+use crate::parse_utils::{self, metadata_node};
+use crate::parser;
+
+/// HTTP/1.x 请求起始行（用于在抓包中途开始的流中重新定位报文边界）
+static RE_REQUEST_START: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[A-Z][A-Z_-]* [!-~]+ HTTP/1\.[01]\r?$").unwrap());
+
+/// HTTP/1.x 响应状态行
+static RE_RESPONSE_START: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^HTTP/1\.[01] \d{3}").unwrap());
+
+/// 链路层类型（https://www.tcpdump.org/linktypes.html）
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW_BSD: u32 = 12;
+const LINKTYPE_RAW_OPENBSD: u32 = 14;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LOOP: u32 = 108;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const TCP_SYN: u8 = 0x02;
+const TCP_ACK: u8 = 0x10;
+
+type Endpoint = (IpAddr, u16);
+
+/// 抓包中的一帧：时间戳（纳秒）、链路层类型与原始数据
+struct Frame<'a> {
+    ts_nanos: u64,
+    link_type: u32,
+    data: &'a [u8],
+}
+
+/// 一个 TCP 段
+struct Segment<'a> {
+    src: Endpoint,
+    dst: Endpoint,
+    seq: u32,
+    flags: u8,
+    payload: &'a [u8],
+    ts_nanos: u64,
+}
+
+/// 单向字节流的待重组数据
+#[derive(Default)]
+struct Direction<'a> {
+    /// SYN 的序号，已知时作为流的起点
+    isn: Option<u32>,
+    /// （序号, 时间戳, 数据）
+    segments: Vec<(u32, u64, &'a [u8])>,
+}
+
+/// 一条 TCP 连接：a → b 与 b → a 两个方向
+struct Connection<'a> {
+    a: Endpoint,
+    b: Endpoint,
+    /// 发起连接（发送不带 ACK 的 SYN）的一端
+    initiator: Option<Endpoint>,
+    forward: Direction<'a>,
+    backward: Direction<'a>,
+}
+
+/// 重组后的字节流，`marks` 记录每段数据在流中的起始偏移与到达时间
+struct Stream {
+    bytes: Vec<u8>,
+    marks: Vec<(usize, u64)>,
+}
+
+impl Stream {
+    /// 流中某个字节到达的时间
+    fn time_at(&self, offset: usize) -> u64 {
+        let idx = self.marks.partition_point(|(start, _)| *start <= offset);
+        self.marks
+            .get(idx.saturating_sub(1))
+            .map(|(_, ts)| *ts)
+            .unwrap_or(0)
+    }
+
+    /// `[start, end)` 范围内的数据全部到达的时间（分段可能乱序到达）
+    fn completed_at(&self, start: usize, end: usize) -> u64 {
+        let first = self
+            .marks
+            .partition_point(|(offset, _)| *offset <= start)
+            .saturating_sub(1);
+        self.marks[first..]
+            .iter()
+            .take_while(|(offset, _)| *offset < end)
+            .map(|(_, ts)| *ts)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// HTTP 报文在流中的范围
+struct Message {
+    start: usize,
+    end: usize,
+}
+
+/// 解析 `tcpdump -w` 等工具保存的 pcap / pcapng 抓包：重组 TCP 流，提取明文 HTTP/1.x 报文，
+/// 按连接内的先后顺序配对请求与响应，并附上时间戳与两端地址。
+/// 不是抓包文件时返回 None。
+pub fn parse_pcap(data: &[u8]) -> Option<Vec<HttpExchange>> {
+    let frames = read_frames(data)?;
+
+    let mut finished: Vec<Connection> = Vec::new();
+    let mut connections: HashMap<(Endpoint, Endpoint), Connection> = HashMap::new();
+    for frame in &frames {
+        let Some(segment) = decode_frame(frame) else {
+            continue;
+        };
+        let key = if segment.src <= segment.dst {
+            (segment.src, segment.dst)
+        } else {
+            (segment.dst, segment.src)
+        };
+
+        // 同一四元组上出现新的 SYN：旧连接已结束，端口被复用
+        let is_syn = segment.flags & TCP_SYN != 0 && segment.flags & TCP_ACK == 0;
+        if is_syn {
+            if let Some(old) = connections.remove(&key) {
+                if old.forward.segments.is_empty() && old.backward.segments.is_empty() {
+                    connections.insert(key, old);
+                } else {
+                    finished.push(old);
+                }
+            }
+        }
+
+        let connection = connections.entry(key).or_insert_with(|| Connection {
+            a: segment.src,
+            b: segment.dst,
+            initiator: None,
+            forward: Direction::default(),
+            backward: Direction::default(),
+        });
+        if is_syn {
+            connection.initiator = Some(segment.src);
+        }
+        let direction = if segment.src == connection.a {
+            &mut connection.forward
+        } else {
+            &mut connection.backward
+        };
+        if segment.flags & TCP_SYN != 0 {
+            direction.isn = Some(segment.seq);
+        }
+        if !segment.payload.is_empty() {
+            direction
+                .segments
+                .push((segment.seq, segment.ts_nanos, segment.payload));
+        }
+    }
+    finished.extend(connections.into_values());
+
+    let mut exchanges: Vec<(u64, HttpExchange)> = finished
+        .into_iter()
+        .flat_map(connection_exchanges)
+        .collect();
+    exchanges.sort_by_key(|(ts, _)| *ts);
+    Some(exchanges.into_iter().map(|(_, e)| e).collect())
+}
+
+/// 读取 pcap 或 pcapng 文件中的全部帧。
+fn read_frames(data: &[u8]) -> Option<Vec<Frame<'_>>> {
+    match data.get(..4)? {
+        [0xd4, 0xc3, 0xb2, 0xa1] => read_pcap(data, true, 1_000),
+        [0xa1, 0xb2, 0xc3, 0xd4] => read_pcap(data, false, 1_000),
+        [0x4d, 0x3c, 0xb2, 0xa1] => read_pcap(data, true, 1),
+        [0xa1, 0xb2, 0x3c, 0x4d] => read_pcap(data, false, 1),
+        [0x0a, 0x0d, 0x0d, 0x0a] => read_pcapng(data),
+        _ => None,
+    }
+}
+
+/// 经典 pcap：24 字节文件头 + 每帧 16 字节记录头。`frac_nanos` 为时间戳小数部分的单位（微秒 / 纳秒）。
+fn read_pcap(data: &[u8], little: bool, frac_nanos: u64) -> Option<Vec<Frame<'_>>> {
+    let link_type = read_u32(data, 20, little)? & 0x0fff_ffff;
+    let mut frames = Vec::new();
+    let mut offset = 24;
+    while let (Some(secs), Some(frac), Some(len)) = (
+        read_u32(data, offset, little),
+        read_u32(data, offset + 4, little),
+        read_u32(data, offset + 8, little),
+    ) {
+        let start = offset + 16;
+        let Some(frame) = data.get(start..start + len as usize) else {
+            break;
+        };
+        frames.push(Frame {
+            ts_nanos: secs as u64 * 1_000_000_000 + frac as u64 * frac_nanos,
+            link_type,
+            data: frame,
+        });
+        offset = start + len as usize;
+    }
+    Some(frames)
+}
+
+/// pcapng：按块读取。SHB 决定字节序，IDB 给出链路层类型与时间戳精度，EPB / SPB / PB 为数据帧。
+fn read_pcapng(data: &[u8]) -> Option<Vec<Frame<'_>>> {
+    let mut frames = Vec::new();
+    // 每个接口的（链路层类型, 时间戳精度）
+    let mut interfaces: Vec<(u32, TsResolution)> = Vec::new();
+    let mut little = true;
+    let mut offset = 0;
+
+    while offset + 12 <= data.len() {
+        if data[offset..offset + 4] == [0x0a, 0x0d, 0x0d, 0x0a] {
+            little = match data.get(offset + 8..offset + 12)? {
+                [0x4d, 0x3c, 0x2b, 0x1a] => true,
+                [0x1a, 0x2b, 0x3c, 0x4d] => false,
+                _ => return None,
+            };
+            interfaces.clear();
+        }
+        let block_type = read_u32(data, offset, little)?;
+        let total_len = read_u32(data, offset + 4, little)? as usize;
+        if total_len < 12 || offset + total_len > data.len() {
+            break;
+        }
+        let block = &data[offset..offset + total_len];
+
+        match block_type {
+            // Interface Description Block
+            1 => {
+                let link_type = read_u16(block, 8, little).unwrap_or(0) as u32;
+                interfaces.push((link_type, idb_resolution(block, little)));
+            }
+            // Enhanced Packet Block / 已废弃的 Packet Block
+            6 | 2 => {
+                let interface = if block_type == 6 {
+                    read_u32(block, 8, little)
+                } else {
+                    read_u16(block, 8, little).map(u32::from)
+                };
+                let (Some(interface), Some(high), Some(low), Some(len)) = (
+                    interface,
+                    read_u32(block, 12, little),
+                    read_u32(block, 16, little),
+                    read_u32(block, 20, little),
+                ) else {
+                    break;
+                };
+                if let (Some(&(link_type, resolution)), Some(frame)) = (
+                    interfaces.get(interface as usize),
+                    block.get(28..28 + len as usize),
+                ) {
+                    frames.push(Frame {
+                        ts_nanos: resolution.to_nanos((high as u64) << 32 | low as u64),
+                        link_type,
+                        data: frame,
+                    });
+                }
+            }
+            // Simple Packet Block：没有时间戳，属于第一个接口
+            3 => {
+                let original = read_u32(block, 8, little)? as usize;
+                let captured = original.min(total_len.saturating_sub(16));
+                if let (Some(&(link_type, _)), Some(frame)) =
+                    (interfaces.first(), block.get(12..12 + captured))
+                {
+                    frames.push(Frame {
+                        ts_nanos: 0,
+                        link_type,
+                        data: frame,
+                    });
+                }
+            }
+            _ => {}
+        }
+        offset += total_len;
+    }
+    Some(frames)
+}
+
+/// pcapng 接口的时间戳精度（`if_tsresol`）：最高位为 0 时单位是 10^-n 秒，否则为 2^-n 秒
+#[derive(Clone, Copy)]
+enum TsResolution {
+    Decimal(u32),
+    Binary(u32),
+}
+
+impl TsResolution {
+    fn to_nanos(self, ts: u64) -> u64 {
+        match self {
+            TsResolution::Decimal(n) if n <= 9 => ts.saturating_mul(10u64.pow(9 - n)),
+            TsResolution::Decimal(n) => ts / 10u64.saturating_pow(n - 9),
+            TsResolution::Binary(n) => ((ts as u128 * 1_000_000_000) >> n.min(127)) as u64,
+        }
+    }
+}
+
+/// 读取 IDB 选项中的 `if_tsresol`（选项码 9），缺省为微秒。
+fn idb_resolution(block: &[u8], little: bool) -> TsResolution {
+    let mut offset = 16;
+    let end = block.len().saturating_sub(4);
+    while offset + 4 <= end {
+        let (Some(code), Some(len)) = (
+            read_u16(block, offset, little),
+            read_u16(block, offset + 2, little),
+        ) else {
+            break;
+        };
+        if code == 0 {
+            break;
+        }
+        if code == 9 {
+            if let Some(&value) = block.get(offset + 4) {
+                return if value & 0x80 == 0 {
+                    TsResolution::Decimal(value as u32)
+                } else {
+                    TsResolution::Binary((value & 0x7f) as u32)
+                };
+            }
+        }
+        offset += 4 + (len as usize).div_ceil(4) * 4;
+    }
+    TsResolution::Decimal(6)
+}
+
+/// 逐层剥离链路层 / IP 头，返回 TCP 段；其他协议与 IP 分片返回 None。
+fn decode_frame<'a>(frame: &Frame<'a>) -> Option<Segment<'a>> {
+    let ip = link_payload(frame.link_type, frame.data)?;
+    let (src_ip, dst_ip, tcp) = ip_payload(ip)?;
+
+    let src_port = u16::from_be_bytes([*tcp.first()?, *tcp.get(1)?]);
+    let dst_port = u16::from_be_bytes([*tcp.get(2)?, *tcp.get(3)?]);
+    let seq = u32::from_be_bytes(tcp.get(4..8)?.try_into().ok()?);
+    let header_len = ((*tcp.get(12)? >> 4) as usize) * 4;
+    let flags = *tcp.get(13)?;
+    let payload = tcp.get(header_len..)?;
+
+    Some(Segment {
+        src: (src_ip, src_port),
+        dst: (dst_ip, dst_port),
+        seq,
+        flags,
+        payload,
+        ts_nanos: frame.ts_nanos,
+    })
+}
+
+/// 去掉链路层头部，返回 IP 包。
+fn link_payload(link_type: u32, data: &[u8]) -> Option<&[u8]> {
+    let ip = match link_type {
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            let mut ethertype = u16::from_be_bytes([*data.get(offset)?, *data.get(offset + 1)?]);
+            // 802.1Q / 802.1ad VLAN 标签
+            while matches!(ethertype, 0x8100 | 0x88a8 | 0x9100) {
+                offset += 4;
+                ethertype = u16::from_be_bytes([*data.get(offset)?, *data.get(offset + 1)?]);
+            }
+            if !matches!(ethertype, 0x0800 | 0x86dd) {
+                return None;
+            }
+            data.get(offset + 2..)?
+        }
+        // BSD loopback：4 字节协议族
+        LINKTYPE_NULL | LINKTYPE_LOOP => data.get(4..)?,
+        LINKTYPE_RAW | LINKTYPE_RAW_BSD | LINKTYPE_RAW_OPENBSD | LINKTYPE_IPV4 | LINKTYPE_IPV6 => {
+            data
+        }
+        // Linux cooked capture：v1 为 16 字节头，v2 为 20 字节头
+        LINKTYPE_LINUX_SLL => data.get(16..)?,
+        LINKTYPE_LINUX_SLL2 => data.get(20..)?,
+        _ => return None,
+    };
+    Some(ip)
+}
+
+/// 解析 IPv4 / IPv6 头，返回（源地址, 目的地址, TCP 段）。
+fn ip_payload(ip: &[u8]) -> Option<(IpAddr, IpAddr, &[u8])> {
+    match ip.first()? >> 4 {
+        4 => {
+            let header_len = ((ip[0] & 0x0f) as usize) * 4;
+            let total_len = u16::from_be_bytes([*ip.get(2)?, *ip.get(3)?]) as usize;
+            // 分片的 IP 包不做重组
+            let fragment = u16::from_be_bytes([*ip.get(6)?, *ip.get(7)?]);
+            if fragment & 0x3fff != 0 || *ip.get(9)? != 6 {
+                return None;
+            }
+            let src: [u8; 4] = ip.get(12..16)?.try_into().ok()?;
+            let dst: [u8; 4] = ip.get(16..20)?.try_into().ok()?;
+            // 开启 TSO 时 total length 可能为 0
+            let end = if total_len == 0 {
+                ip.len()
+            } else {
+                total_len.min(ip.len())
+            };
+            Some((
+                IpAddr::V4(Ipv4Addr::from(src)),
+                IpAddr::V4(Ipv4Addr::from(dst)),
+                ip.get(header_len..end)?,
+            ))
+        }
+        6 => {
+            let payload_len = u16::from_be_bytes([*ip.get(4)?, *ip.get(5)?]) as usize;
+            let src: [u8; 16] = ip.get(8..24)?.try_into().ok()?;
+            let dst: [u8; 16] = ip.get(24..40)?.try_into().ok()?;
+            let end = if payload_len == 0 {
+                ip.len()
+            } else {
+                (40 + payload_len).min(ip.len())
+            };
+            // 跳过 hop-by-hop / routing / destination options 扩展头
+            let mut next = *ip.get(6)?;
+            let mut offset = 40;
+            while matches!(next, 0 | 43 | 60) {
+                next = *ip.get(offset)?;
+                offset += (*ip.get(offset + 1)? as usize + 1) * 8;
+            }
+            if next != 6 {
+                return None;
+            }
+            Some((
+                IpAddr::V6(Ipv6Addr::from(src)),
+                IpAddr::V6(Ipv6Addr::from(dst)),
+                ip.get(offset..end)?,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// 按序号重组单向字节流：丢弃重传，裁掉重叠部分，缺失的数据直接跳过。
+fn reassemble(direction: &Direction) -> Stream {
+    let mut stream = Stream {
+        bytes: Vec::new(),
+        marks: Vec::new(),
+    };
+    let Some(first) = direction.segments.first() else {
+        return stream;
+    };
+
+    // 以 SYN 之后的序号为起点；没有抓到握手时以第一个数据段为起点（序号可能回绕）
+    let base = direction
+        .isn
+        .map(|isn| isn.wrapping_add(1))
+        .unwrap_or(first.0);
+    let mut ordered: Vec<(i64, u64, &[u8])> = direction
+        .segments
+        .iter()
+        .map(|(seq, ts, data)| (seq.wrapping_sub(base) as i32 as i64, *ts, *data))
+        .collect();
+    ordered.sort_by_key(|(offset, _, _)| *offset);
+
+    let mut end = ordered[0].0.min(0);
+    for (offset, ts, data) in ordered {
+        let data_end = offset + data.len() as i64;
+        if data_end <= end {
+            continue;
+        }
+        let skip = (end - offset).max(0) as usize;
+        stream.marks.push((stream.bytes.len(), ts));
+        stream.bytes.extend_from_slice(&data[skip..]);
+        end = data_end;
+    }
+    stream
+}
+
+/// 将一条连接转换为（请求时间, 交互）列表。发起连接的一端为客户端；
+/// 没有抓到握手时，以能找到请求行的一端为客户端。
+fn connection_exchanges(connection: Connection) -> Vec<(u64, HttpExchange)> {
+    let forward = reassemble(&connection.forward);
+    let backward = reassemble(&connection.backward);
+
+    let forward_is_client = match connection.initiator {
+        Some(initiator) => initiator == connection.a,
+        None => RE_REQUEST_START.is_match(&forward.bytes),
+    };
+    let (client, server, requests_stream, responses_stream) = if forward_is_client {
+        (connection.a, connection.b, forward, backward)
+    } else {
+        (connection.b, connection.a, backward, forward)
+    };
+
+    let requests = split_messages(&requests_stream.bytes, false, &[]);
+    if requests.is_empty() {
+        return Vec::new();
+    }
+    let head_requests: Vec<bool> = requests
+        .iter()
+        .map(|m| requests_stream.bytes[m.start..m.end].starts_with(b"HEAD "))
+        .collect();
+    let responses = split_messages(&responses_stream.bytes, true, &head_requests);
+
+    requests
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            let started = requests_stream.time_at(message.start);
+            let mut request = parser::parse_http_text(&message_text(
+                &requests_stream.bytes[message.start..message.end],
+            ));
+            // 缺少 Host header 时用服务端地址补全 URL
+            if let Some(target) = request.request_target.clone().filter(|t| {
+                t.starts_with('/') && !request.url.as_deref().unwrap_or("").contains("://")
+            }) {
+                let url = format!("http://{}{target}", format_endpoint(server));
+                request.url_nodes = parse_utils::parse_url_nodes(&url);
+                request.url = Some(url);
+            }
+            request.metadata.get_or_insert_with(Vec::new).extend([
+                metadata_node("client", &format_endpoint(client)),
+                metadata_node("server", &format_endpoint(server)),
+            ]);
+
+            let response = responses.get(i);
+            let duration_ms = response.map(|r| {
+                let finished = responses_stream.completed_at(r.start, r.end);
+                finished.saturating_sub(started) as f64 / 1_000_000.0
+            });
+            let response = response.map(|r| {
+                parser::parse_http_text(&message_text(&responses_stream.bytes[r.start..r.end]))
+            });
+
+            (
+                started,
+                HttpExchange {
+                    request: Some(request),
+                    response,
+                    started_at: format_timestamp(started),
+                    duration_ms,
+                },
+            )
+        })
+        .collect()
+}
+
+/// 在字节流中切分 HTTP/1.x 报文。body 长度依次按 chunked、Content-Length 判断；
+/// 响应两者都没有时读到连接结束（HEAD 的响应、1xx / 204 / 304 没有 body）。
+/// 1xx 中间响应不参与配对，101 之后的数据不再是 HTTP。
+fn split_messages(bytes: &[u8], responses: bool, head_requests: &[bool]) -> Vec<Message> {
+    let start_pattern: &Regex = if responses {
+        &RE_RESPONSE_START
+    } else {
+        &RE_REQUEST_START
+    };
+    let mut messages = Vec::new();
+    let mut pos = 0;
+
+    // 在剩余数据上重新匹配，使上一条报文结束处也算作行首
+    while let Some(found) = start_pattern.find(&bytes[pos..]) {
+        let start = pos + found.start();
+        let Some(header_end) = find_header_end(bytes, start) else {
+            messages.push(Message {
+                start,
+                end: bytes.len(),
+            });
+            break;
+        };
+        let head = String::from_utf8_lossy(&bytes[start..header_end]);
+        let status: Option<u16> = responses
+            .then(|| head.get(9..12).and_then(|s| s.parse().ok()))
+            .flatten();
+        let header = |name: &str| {
+            head.lines().skip(1).find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.trim()
+                    .eq_ignore_ascii_case(name)
+                    .then(|| value.trim().to_string())
+            })
+        };
+        let chunked =
+            header("transfer-encoding").is_some_and(|v| v.to_ascii_lowercase().contains("chunked"));
+        let content_length = header("content-length").and_then(|v| v.parse::<usize>().ok());
+
+        let is_head = head_requests.get(messages.len()).copied().unwrap_or(false);
+        let end = if matches!(status, Some(100..=199 | 204 | 304)) || is_head {
+            header_end
+        } else if chunked {
+            chunked_end(bytes, header_end)
+        } else if let Some(length) = content_length {
+            header_end.saturating_add(length).min(bytes.len())
+        } else if responses {
+            bytes.len()
+        } else {
+            header_end
+        };
+
+        match status {
+            Some(101) => {
+                messages.push(Message { start, end });
+                break;
+            }
+            Some(100..=199) => {}
+            _ => messages.push(Message { start, end }),
+        }
+        pos = end.max(start + 1);
+    }
+    messages
+}
+
+/// 头部结束位置（空行之后）。
+fn find_header_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let rest = &bytes[start..];
+    rest.windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|pos| start + pos + 4)
+        .or_else(|| {
+            rest.windows(2)
+                .position(|w| w == b"\n\n")
+                .map(|pos| start + pos + 2)
+        })
+}
+
+/// chunked body 结束的位置（最后一个 0 长度块与 trailer 之后），数据不完整时为流末尾。
+fn chunked_end(bytes: &[u8], mut pos: usize) -> usize {
+    loop {
+        let Some(line_end) = find_line_end(bytes, pos) else {
+            return bytes.len();
+        };
+        let line = String::from_utf8_lossy(&bytes[pos..line_end]);
+        let Ok(size) = usize::from_str_radix(line.split(';').next().unwrap_or("").trim(), 16)
+        else {
+            return bytes.len();
+        };
+        pos = line_end + 1;
+        if size == 0 {
+            // trailer，直到空行
+            while let Some(end) = find_line_end(bytes, pos) {
+                let empty = bytes[pos..end].iter().all(|b| *b == b'\r');
+                pos = end + 1;
+                if empty {
+                    return pos;
+                }
+            }
+            return bytes.len();
+        }
+        // 块长度来自抓包数据，可能是任意值
+        pos = pos.saturating_add(size).min(bytes.len());
+        if bytes[pos..].starts_with(b"\r\n") {
+            pos += 2;
+        } else if bytes[pos..].starts_with(b"\n") {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return bytes.len();
+        }
+    }
+}
+
+fn find_line_end(bytes: &[u8], pos: usize) -> Option<usize> {
+    bytes
+        .get(pos..)?
+        .iter()
+        .position(|b| *b == b'\n')
+        .map(|i| pos + i)
+}
+
+/// 报文字节转为文本。chunked 的二进制 body（如 gzip）先去分块，否则 base64 之后无法再去分块。
+fn message_text(bytes: &[u8]) -> String {
+    let Some(header_end) = find_header_end(bytes, 0) else {
+        return parse_utils::http_message_text(bytes);
+    };
+    let (head, body) = bytes.split_at(header_end);
+    let chunked = String::from_utf8_lossy(head)
+        .to_ascii_lowercase()
+        .contains("transfer-encoding: chunked");
+    if !chunked || std::str::from_utf8(body).is_ok() {
+        return parse_utils::http_message_text(bytes);
+    }
+    let mut dechunked = head.to_vec();
+    let mut pos = 0;
+    while let Some(line_end) = find_line_end(body, pos) {
+        let line = String::from_utf8_lossy(&body[pos..line_end]);
+        let Ok(size) = usize::from_str_radix(line.split(';').next().unwrap_or("").trim(), 16)
+        else {
+            break;
+        };
+        let start = line_end + 1;
+        if size == 0 || start >= body.len() {
+            break;
+        }
+        let end = start.saturating_add(size).min(body.len());
+        dechunked.extend_from_slice(&body[start..end]);
+        pos = end;
+        if body[pos..].starts_with(b"\r\n") {
+            pos += 2;
+        } else if body[pos..].starts_with(b"\n") {
+            pos += 1;
+        }
+    }
+    parse_utils::http_message_text(&dechunked)
+}
+
+/// `10.0.0.1:80` / `[::1]:8080`
+fn format_endpoint((ip, port): Endpoint) -> String {
+    match ip {
+        IpAddr::V4(v4) => format!("{v4}:{port}"),
+        IpAddr::V6(v6) => format!("[{v6}]:{port}"),
+    }
+}
+
+/// 与 HAR 的 startedDateTime 一致的 ISO 8601 时间；没有时间戳（SPB）时为 None。
+fn format_timestamp(ts_nanos: u64) -> Option<String> {
+    if ts_nanos == 0 {
+        return None;
+    }
+    let secs = (ts_nanos / 1_000_000_000) as i64;
+    let nanos = (ts_nanos % 1_000_000_000) as u32;
+    DateTime::from_timestamp(secs, nanos).map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+fn read_u16(data: &[u8], offset: usize, little: bool) -> Option<u16> {
+    let bytes: [u8; 2] = data.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if little {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+fn read_u32(data: &[u8], offset: usize, little: bool) -> Option<u32> {
+    let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if little {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TCP_FIN: u8 = 0x01;
+    const CLIENT: [u8; 4] = [192, 168, 1, 10];
+    const SERVER: [u8; 4] = [93, 184, 216, 34];
+
+    /// 构造 Ethernet + IPv4 + TCP 帧
+    fn tcp_frame(
+        src: [u8; 4],
+        dst: [u8; 4],
+        ports: (u16, u16),
+        seq: u32,
+        flags: u8,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        let mut frame = vec![0u8; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        let total_len = (20 + 20 + payload.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0]);
+        frame.extend_from_slice(&total_len.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0x40, 0, 64, 6, 0, 0]);
+        frame.extend_from_slice(&src);
+        frame.extend_from_slice(&dst);
+        frame.extend_from_slice(&ports.0.to_be_bytes());
+        frame.extend_from_slice(&ports.1.to_be_bytes());
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0, 0, 0x50, flags, 0xff, 0xff, 0, 0, 0, 0]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn pcap_file(frames: &[(u32, Vec<u8>)]) -> Vec<u8> {
+        let mut file = vec![0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0];
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&LINKTYPE_ETHERNET.to_le_bytes());
+        for (millis, frame) in frames {
+            file.extend_from_slice(&(1_767_225_600 + millis / 1000).to_le_bytes());
+            file.extend_from_slice(&((millis % 1000) * 1000).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(frame);
+        }
+        file
+    }
+
+    fn sample_capture() -> Vec<Vec<u8>> {
+        let c = (51000, 80);
+        let s = (80, 51000);
+        let request = b"GET /items?id=1 HTTP/1.1\r\nHost: example.com\r\n\r\n";
+        let body_part1 = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\n\r\n{\"ok\":";
+        let body_part2 = b"true}";
+        let post = b"POST /items HTTP/1.1\r\nHost: example.com\r\nContent-Length: 7\r\n\r\nname=aa";
+        let created =
+            b"HTTP/1.1 201 Created\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhi\r\n0\r\n\r\n";
+
+        let req_seq = 1001;
+        let resp_seq = 5001;
+        vec![
+            tcp_frame(CLIENT, SERVER, c, 1000, TCP_SYN, b""),
+            tcp_frame(SERVER, CLIENT, s, 5000, TCP_SYN | TCP_ACK, b""),
+            tcp_frame(CLIENT, SERVER, c, req_seq, TCP_ACK, request),
+            // 响应的两个分段乱序到达，且第二段被重传
+            tcp_frame(
+                SERVER,
+                CLIENT,
+                s,
+                resp_seq + body_part1.len() as u32,
+                TCP_ACK,
+                body_part2,
+            ),
+            tcp_frame(SERVER, CLIENT, s, resp_seq, TCP_ACK, body_part1),
+            tcp_frame(
+                SERVER,
+                CLIENT,
+                s,
+                resp_seq + body_part1.len() as u32,
+                TCP_ACK,
+                body_part2,
+            ),
+            tcp_frame(
+                CLIENT,
+                SERVER,
+                c,
+                req_seq + request.len() as u32,
+                TCP_ACK,
+                post,
+            ),
+            tcp_frame(
+                SERVER,
+                CLIENT,
+                s,
+                resp_seq + (body_part1.len() + body_part2.len()) as u32,
+                TCP_ACK | TCP_FIN,
+                created,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_reassemble_and_pair() {
+        let frames: Vec<(u32, Vec<u8>)> = sample_capture()
+            .into_iter()
+            .enumerate()
+            .map(|(i, f)| (i as u32 * 10, f))
+            .collect();
+        let exchanges = parse_pcap(&pcap_file(&frames)).unwrap();
+        assert_eq!(exchanges.len(), 2);
+
+        let first = &exchanges[0];
+        let request = first.request.as_ref().unwrap();
+        assert_eq!(
+            request.url.as_deref(),
+            Some("http://example.com/items?id=1")
+        );
+        let metadata = request.metadata.as_ref().unwrap();
+        assert_eq!(metadata[0].value, "192.168.1.10:51000");
+        assert_eq!(metadata[1].value, "93.184.216.34:80");
+        assert_eq!(
+            first.started_at.as_deref(),
+            Some("2026-01-01T00:00:00.020Z")
+        );
+        assert_eq!(first.duration_ms, Some(20.0));
+        let response = first.response.as_ref().unwrap();
+        assert_eq!(response.status_code, Some(200));
+        assert_eq!(response.body.as_deref(), Some("{\"ok\":true}"));
+
+        let second = &exchanges[1];
+        assert_eq!(
+            second.request.as_ref().unwrap().body.as_deref(),
+            Some("name=aa")
+        );
+        let created = second.response.as_ref().unwrap();
+        assert_eq!(created.status_code, Some(201));
+        assert_eq!(created.body.as_deref(), Some("2\nhi\n0"));
+    }
+
+    #[test]
+    fn test_pcapng_with_nanosecond_resolution() {
+        let mut file = Vec::new();
+        let mut block = |block_type: u32, body: Vec<u8>| {
+            let total = (12 + body.len()) as u32;
+            file.extend_from_slice(&block_type.to_le_bytes());
+            file.extend_from_slice(&total.to_le_bytes());
+            file.extend_from_slice(&body);
+            file.extend_from_slice(&total.to_le_bytes());
+        };
+        // SHB
+        let mut shb = 0x1a2b3c4du32.to_le_bytes().to_vec();
+        shb.extend_from_slice(&[1, 0, 0, 0]);
+        shb.extend_from_slice(&u64::MAX.to_le_bytes());
+        block(0x0a0d0d0a, shb);
+        // IDB，if_tsresol = 9（纳秒）
+        let mut idb = (LINKTYPE_ETHERNET as u16).to_le_bytes().to_vec();
+        idb.extend_from_slice(&[0, 0]);
+        idb.extend_from_slice(&0u32.to_le_bytes());
+        idb.extend_from_slice(&[9, 0, 1, 0, 9, 0, 0, 0, 0, 0, 0, 0]);
+        block(1, idb);
+
+        let ts: u64 = 1_767_225_600_500_000_000;
+        for (offset, frame) in sample_capture().into_iter().enumerate() {
+            let ts = ts + offset as u64 * 1_000_000;
+            let mut epb = 0u32.to_le_bytes().to_vec();
+            epb.extend_from_slice(&((ts >> 32) as u32).to_le_bytes());
+            epb.extend_from_slice(&(ts as u32).to_le_bytes());
+            epb.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            epb.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            epb.extend_from_slice(&frame);
+            epb.resize(epb.len().div_ceil(4) * 4, 0);
+            block(6, epb);
+        }
+
+        let exchanges = parse_pcap(&file).unwrap();
+        assert_eq!(exchanges.len(), 2);
+        assert_eq!(
+            exchanges[0].started_at.as_deref(),
+            Some("2026-01-01T00:00:00.502Z")
+        );
+        assert_eq!(exchanges[0].duration_ms, Some(2.0));
+    }
+
+    #[test]
+    fn test_capture_without_handshake() {
+        // 抓包从连接中途开始：客户端流以半截请求开头
+        let c = (51000, 8080);
+        let s = (8080, 51000);
+        let frames = vec![
+            (
+                0,
+                tcp_frame(
+                    CLIENT,
+                    SERVER,
+                    c,
+                    77,
+                    TCP_ACK,
+                    b"tail of previous body\r\nHEAD /ping HTTP/1.1\r\n\r\n",
+                ),
+            ),
+            (
+                5,
+                tcp_frame(
+                    SERVER,
+                    CLIENT,
+                    s,
+                    900,
+                    TCP_ACK,
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 42\r\n\r\n",
+                ),
+            ),
+        ];
+        let exchanges = parse_pcap(&pcap_file(&frames)).unwrap();
+        assert_eq!(exchanges.len(), 1);
+        let request = exchanges[0].request.as_ref().unwrap();
+        assert_eq!(request.method.as_deref(), Some("HEAD"));
+        assert_eq!(
+            request.url.as_deref(),
+            Some("http://93.184.216.34:8080/ping")
+        );
+        assert_eq!(
+            exchanges[0].response.as_ref().unwrap().status_code,
+            Some(200)
+        );
+    }
+
+    #[test]
+    fn test_malformed_lengths() {
+        let c = (51000, 80);
+        let s = (80, 51000);
+        let request =
+            b"POST /a HTTP/1.1\r\nHost: example.com\r\nContent-Length: 18446744073709551615\r\n\r\nab";
+        let response =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffffff\r\n\xff\xfe\r\n0\r\n\r\n";
+        let frames = vec![
+            (0, tcp_frame(CLIENT, SERVER, c, 1, TCP_ACK, request)),
+            (5, tcp_frame(SERVER, CLIENT, s, 1, TCP_ACK, response)),
+        ];
+        let exchanges = parse_pcap(&pcap_file(&frames)).unwrap();
+        assert_eq!(exchanges.len(), 1);
+        let request = exchanges[0].request.as_ref().unwrap();
+        assert_eq!(request.body.as_deref(), Some("ab"));
+        assert_eq!(
+            exchanges[0].response.as_ref().unwrap().status_code,
+            Some(200)
+        );
+    }
+
+    #[test]
+    fn test_not_a_capture() {
+        assert!(parse_pcap(b"GET / HTTP/1.1\r\n\r\n").is_none());
+    }
+}
@@ -0,0 +1,925 @@
+use std::collections::HashMap;
+
+use crate::models::{HttpContentType, ParseNode, ParseResult};
+use crate::multipart::{self, FormPart, FORM_BOUNDARY};
+use crate::parse_utils::{self, empty_result, header_node, metadata_node};
+use crate::query_tree;
+use crate::shell_words;
+
+/// 发起请求的命令（含 PowerShell 内置别名）
+const INVOKE_COMMANDS: &[&str] = &["invoke-webrequest", "invoke-restmethod", "iwr", "irm"];
+
+/// 不带参数值的开关参数
+const SWITCH_PARAMETERS: &[&str] = &[
+    "usebasicparsing",
+    "skipcertificatecheck",
+    "skipheadervalidation",
+    "skiphttperrorcheck",
+    "disablekeepalive",
+    "allowunencryptedauthentication",
+    "usedefaultcredentials",
+    "passthru",
+    "resume",
+];
+
+/// 不影响请求本身、但值得保留的参数：（参数名, metadata key）
+const TRANSPORT_PARAMETERS: &[(&str, &str)] = &[
+    ("skipcertificatecheck", "insecure"),
+    ("timeoutsec", "timeout"),
+    ("operationtimeoutseconds", "timeout"),
+    ("maximumredirection", "max-redirect"),
+    ("maximumretrycount", "retry"),
+    ("proxy", "proxy"),
+    ("outfile", "output"),
+    ("infile", "upload-file"),
+    ("httpversion", "http-version"),
+];
+
+/// Chrome "Copy as PowerShell" 会把 HTTP/2 伪头部写成普通 key
+const PSEUDO_HEADER_KEYS: &[&str] = &["authority", "method", "path", "scheme"];
+
+/// 脚本中出现的值
+#[derive(Debug, Clone)]
+enum PsValue {
+    Null,
+    Str(String),
+    Hashtable(Vec<(String, PsValue)>),
+    Array(Vec<PsValue>),
+    /// `$name` 引用（名称已转小写）
+    Var(String),
+    Cookie(Cookie),
+    Session,
+    /// 无法静态求值的表达式
+    Expr,
+}
+
+/// `New-Object System.Net.Cookie(name, value, path, domain)`
+#[derive(Debug, Clone)]
+struct Cookie {
+    name: String,
+    value: String,
+}
+
+/// 脚本执行过程中收集的状态：变量与 WebRequestSession 的内容
+#[derive(Default)]
+struct Script {
+    variables: HashMap<String, PsValue>,
+    user_agent: Option<String>,
+    cookies: Vec<Cookie>,
+    session_headers: Vec<(String, String)>,
+    /// 第一个 Invoke-WebRequest / Invoke-RestMethod 调用：（命令名, 参数, 位置参数）
+    invocation: Option<Invocation>,
+}
+
+struct Invocation {
+    command: String,
+    parameters: Vec<(String, PsValue)>,
+    positional: Vec<PsValue>,
+}
+
+/// 解析 Chrome "Copy as PowerShell" 与手写的 `Invoke-WebRequest` / `Invoke-RestMethod` 脚本：
+/// WebRequestSession 的 UserAgent 与 `Cookies.Add(...)`、`-Headers @{...}` 哈希表、`-Method`、
+/// `-ContentType`、`-Body`（含 `[Text.Encoding]::UTF8.GetBytes(...)`）以及常见的传输参数。
+pub fn parse_powershell(input: &str) -> ParseResult {
+    let raw_text = input.to_string();
+    let mut script = Script::default();
+    let mut reader = Reader::new(input);
+    reader.run(&mut script);
+
+    let Some(invocation) = script.invocation.take() else {
+        return empty_result(raw_text);
+    };
+    let resolve = |value: &PsValue| script.resolve(value);
+
+    let mut method: Option<String> = None;
+    let mut url_str: Option<String> = None;
+    let mut headers: Vec<ParseNode> = Vec::new();
+    let mut metadata: Vec<ParseNode> = vec![metadata_node("command", &invocation.command)];
+    let mut body: Option<PsValue> = None;
+    let mut content_type: Option<String> = None;
+    let mut user_agent: Option<String> = None;
+    let mut use_session = false;
+    let mut authentication: Option<String> = None;
+    let mut token: Option<String> = None;
+
+    for (name, value) in &invocation.parameters {
+        let value = resolve(value);
+        match name.as_str() {
+            "uri" => url_str = value.text(),
+            "method" | "custommethod" => method = value.text().map(|m| m.to_uppercase()),
+            "headers" => {
+                if let PsValue::Hashtable(entries) = &value {
+                    for (key, val) in entries {
+                        if let Some(val) = resolve(val).text() {
+                            headers.push(header_node(key, &val));
+                        }
+                    }
+                }
+            }
+            "body" => body = Some(value),
+            "contenttype" => content_type = value.text(),
+            "useragent" => user_agent = value.text(),
+            "websession" => use_session = true,
+            "authentication" => authentication = value.text(),
+            "token" => token = value.text(),
+            "form" => body = Some(value),
+            _ => {
+                if let Some((_, key)) = TRANSPORT_PARAMETERS.iter().find(|(p, _)| p == name) {
+                    let text = if SWITCH_PARAMETERS.contains(&name.as_str()) {
+                        Some("true".to_string())
+                    } else {
+                        value.text()
+                    };
+                    if let Some(text) = text {
+                        metadata.push(metadata_node(key, &text));
+                    }
+                }
+            }
+        }
+    }
+    if url_str.is_none() {
+        url_str = invocation
+            .positional
+            .first()
+            .and_then(|v| resolve(v).text());
+    }
+    let Some(mut url_str) = url_str else {
+        return empty_result(raw_text);
+    };
+    let is_form = invocation.parameters.iter().any(|(name, _)| name == "form");
+
+    let has_header = |headers: &[ParseNode], name: &str| {
+        headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+    };
+    if use_session {
+        for (key, value) in &script.session_headers {
+            if !has_header(&headers, key) {
+                headers.push(header_node(key, value));
+            }
+        }
+        if user_agent.is_none() {
+            user_agent = script.user_agent.clone();
+        }
+        if !script.cookies.is_empty() && !has_header(&headers, "cookie") {
+            let cookie = script
+                .cookies
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; ");
+            headers.push(header_node("Cookie", &cookie));
+        }
+    }
+    if let Some(agent) = &user_agent {
+        if !has_header(&headers, "user-agent") {
+            headers.push(header_node("User-Agent", agent));
+        }
+    }
+    if let (Some(scheme), Some(token)) = (&authentication, &token) {
+        if matches!(scheme.to_lowercase().as_str(), "bearer" | "oauth")
+            && !has_header(&headers, "authorization")
+        {
+            headers.push(header_node("Authorization", &format!("Bearer {token}")));
+        }
+    }
+
+    let method = method.unwrap_or_else(|| "GET".to_string());
+    let body = match body {
+        // 哈希表 body：GET 时拼接为查询字符串，否则按表单编码
+        Some(PsValue::Hashtable(entries)) => {
+            let pairs: Vec<(String, String)> = entries
+                .iter()
+                .filter_map(|(k, v)| Some((k.clone(), resolve(v).text()?)))
+                .collect();
+            if is_form {
+                let parts: Vec<FormPart> = pairs
+                    .into_iter()
+                    .map(|(name, value)| FormPart {
+                        name,
+                        value,
+                        filename: None,
+                        content_type: None,
+                    })
+                    .collect();
+                content_type = Some(format!("multipart/form-data; boundary={FORM_BOUNDARY}"));
+                Some(multipart::build_multipart_body(&parts, FORM_BOUNDARY))
+            } else {
+                let encoded = url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(&pairs)
+                    .finish();
+                if method == "GET" {
+                    if !encoded.is_empty() {
+                        url_str.push(if url_str.contains('?') { '&' } else { '?' });
+                        url_str.push_str(&encoded);
+                    }
+                    None
+                } else {
+                    content_type
+                        .get_or_insert_with(|| "application/x-www-form-urlencoded".to_string());
+                    Some(encoded)
+                }
+            }
+        }
+        Some(value) => value.text(),
+        None => None,
+    };
+    if let Some(content_type) = &content_type {
+        if !has_header(&headers, "content-type") {
+            headers.push(header_node("Content-Type", content_type));
+        }
+    }
+
+    // Chrome 输出的 authority / method / path / scheme 实为 HTTP/2 伪头部
+    for header in headers.iter_mut() {
+        if PSEUDO_HEADER_KEYS.contains(&header.key.to_lowercase().as_str()) {
+            header.key = format!(":{}", header.key.to_lowercase());
+        }
+    }
+    let (headers, pseudo_headers) = parse_utils::split_pseudo_headers(headers);
+
+    let query_params = parse_utils::parse_query_params(&url_str);
+    let query_tree = query_params
+        .as_deref()
+        .and_then(query_tree::build_query_tree);
+    let url_nodes = parse_utils::parse_url_nodes(&url_str);
+
+    ParseResult {
+        content_type: HttpContentType::Request,
+        method: Some(method),
+        url: Some(url_str),
+        url_nodes,
+        headers,
+        pseudo_headers,
+        query_params,
+        query_tree,
+        body,
+        metadata: Some(metadata),
+        raw_text,
+        ..Default::default()
+    }
+}
+
+impl PsValue {
+    /// 标量值的文本形式；`$null`、哈希表等返回 None。
+    fn text(&self) -> Option<String> {
+        match self {
+            PsValue::Str(s) => Some(s.clone()),
+            PsValue::Array(items) => {
+                let parts: Vec<String> = items.iter().filter_map(PsValue::text).collect();
+                Some(parts.join(" "))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Script {
+    /// 展开 `$变量` 引用（最多 8 层，防止循环引用）。
+    fn resolve(&self, value: &PsValue) -> PsValue {
+        let mut current = value.clone();
+        for _ in 0..8 {
+            match &current {
+                PsValue::Var(name) => {
+                    current = self.variables.get(name).cloned().unwrap_or(PsValue::Null);
+                }
+                _ => break,
+            }
+        }
+        current
+    }
+}
+
+/// PowerShell 脚本的简化读取器：只识别请求相关的语句与字面量。
+struct Reader<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(src: &'a str) -> Self {
+        Reader { src, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 跳过同一语句内的空白：空格、制表符、反引号续行与 `<# #>` 注释。
+    fn skip_inline(&mut self) {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with([' ', '\t']) {
+                self.pos += 1;
+            } else if rest.starts_with("`\r\n") {
+                self.pos += 3;
+            } else if rest.starts_with("`\n") {
+                self.pos += 2;
+            } else if rest.starts_with("<#") {
+                self.pos += rest.find("#>").map(|i| i + 2).unwrap_or(rest.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// 跳过空白、换行与 `#` 注释。
+    fn skip_all(&mut self) {
+        loop {
+            self.skip_inline();
+            match self.peek() {
+                Some('\r' | '\n') => self.pos += 1,
+                Some('#') => {
+                    let rest = self.rest();
+                    self.pos += rest.find('\n').unwrap_or(rest.len());
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn at_statement_end(&self) -> bool {
+        matches!(
+            self.peek(),
+            None | Some('\r' | '\n' | ';' | '|' | '}' | ')' | '#')
+        )
+    }
+
+    /// 裸词：直到空白或分隔符。
+    fn read_word(&mut self) -> String {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "(){}[];,=|`\"'".contains(c))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        rest[..len].to_string()
+    }
+
+    /// 逐条执行语句，记录变量赋值、Session 操作与第一个请求调用。
+    fn run(&mut self, script: &mut Script) {
+        loop {
+            self.skip_all();
+            if self.peek().is_none() {
+                break;
+            }
+            let start = self.pos;
+            self.statement(script);
+            self.skip_rest_of_statement();
+            if self.pos == start {
+                self.bump();
+            }
+        }
+    }
+
+    fn statement(&mut self, script: &mut Script) {
+        if self.peek() == Some('$') {
+            self.bump();
+            let name = self.read_name();
+            self.skip_inline();
+            if self.rest().starts_with('=') && !self.rest().starts_with("==") {
+                self.bump();
+                self.skip_inline();
+                let value = self.expression(script);
+                let lower = name.to_lowercase();
+                if lower.ends_with(".useragent") {
+                    script.user_agent = script.resolve(&value).text();
+                } else if !lower.contains('.') {
+                    script.variables.insert(lower, value);
+                }
+            } else if self.peek() == Some('(') {
+                let args = self.arguments(script);
+                let lower = name.to_lowercase();
+                if lower.ends_with(".cookies.add") {
+                    for arg in &args {
+                        if let PsValue::Cookie(cookie) = script.resolve(arg) {
+                            script.cookies.push(cookie);
+                        }
+                    }
+                } else if lower.ends_with(".headers.add") {
+                    if let [key, value, ..] = args.as_slice() {
+                        if let (Some(key), Some(value)) =
+                            (script.resolve(key).text(), script.resolve(value).text())
+                        {
+                            script.session_headers.push((key, value));
+                        }
+                    }
+                }
+            }
+            return;
+        }
+        self.expression(script);
+    }
+
+    /// 语句右侧：命令调用（Invoke-WebRequest / New-Object / 其他命令）或值。
+    fn expression(&mut self, script: &mut Script) -> PsValue {
+        self.skip_inline();
+        let starts_with_word = self.peek().is_some_and(|c| c.is_ascii_alphabetic());
+        if !starts_with_word {
+            return self.value(script);
+        }
+
+        let start = self.pos;
+        let word = self.read_word();
+        let lower = word.to_lowercase();
+        if INVOKE_COMMANDS.contains(&lower.as_str()) {
+            let invocation = self.invocation(&word, script);
+            script.invocation.get_or_insert(invocation);
+            return PsValue::Expr;
+        }
+        if lower == "new-object" {
+            return self.new_object(script);
+        }
+        // 其他命令（如 ConvertTo-SecureString "x" -AsPlainText）：取第一个位置参数
+        if self.rest().starts_with([' ', '\t']) && lower.contains('-') {
+            let mut first = None;
+            while !self.at_statement_end() {
+                self.skip_inline();
+                if self.at_statement_end() {
+                    break;
+                }
+                if self.peek() == Some('-') {
+                    self.read_word();
+                    continue;
+                }
+                let before = self.pos;
+                let value = self.value(script);
+                first.get_or_insert(value);
+                if self.pos == before {
+                    break;
+                }
+            }
+            return first.unwrap_or(PsValue::Expr);
+        }
+        self.pos = start;
+        self.value(script)
+    }
+
+    /// 读取命令参数：`-Name value`、`-Name:value`、开关参数与位置参数。
+    fn invocation(&mut self, command: &str, script: &mut Script) -> Invocation {
+        let mut parameters = Vec::new();
+        let mut positional = Vec::new();
+        loop {
+            self.skip_inline();
+            if self.at_statement_end() {
+                break;
+            }
+            let before = self.pos;
+            let is_parameter = self.peek() == Some('-')
+                && self.rest()[1..].starts_with(|c: char| c.is_ascii_alphabetic());
+            if is_parameter {
+                self.bump();
+                let word = self.read_word();
+                let (name, inline) = match word.split_once(':') {
+                    Some((name, value)) => (name.to_lowercase(), Some(value.to_string())),
+                    None => (word.to_lowercase(), None),
+                };
+                let value = if let Some(inline) = inline.filter(|v| !v.is_empty()) {
+                    PsValue::Str(inline)
+                } else if word.ends_with(':') {
+                    self.value(script)
+                } else if SWITCH_PARAMETERS.contains(&name.as_str()) {
+                    PsValue::Str("true".to_string())
+                } else {
+                    self.skip_inline();
+                    self.value(script)
+                };
+                parameters.push((name, value));
+            } else {
+                positional.push(self.value(script));
+            }
+            if self.pos == before {
+                break;
+            }
+        }
+        Invocation {
+            command: canonical_command(command),
+            parameters,
+            positional,
+        }
+    }
+
+    /// `New-Object Type(args)`、`New-Object Type -ArgumentList a, b` 与 `-Property @{...}`。
+    fn new_object(&mut self, script: &mut Script) -> PsValue {
+        self.skip_inline();
+        let mut type_name = String::new();
+        let mut args: Vec<PsValue> = Vec::new();
+        let mut properties: Vec<(String, PsValue)> = Vec::new();
+        loop {
+            self.skip_inline();
+            if self.at_statement_end() {
+                break;
+            }
+            let before = self.pos;
+            if self.peek() == Some('-') {
+                self.bump();
+                match self.read_word().to_lowercase().as_str() {
+                    "typename" => {
+                        self.skip_inline();
+                        type_name = self.read_word();
+                    }
+                    "argumentlist" => {
+                        self.skip_inline();
+                        args = match self.list(script) {
+                            PsValue::Array(items) => items,
+                            other => vec![other],
+                        };
+                    }
+                    "property" => {
+                        self.skip_inline();
+                        if let PsValue::Hashtable(entries) = self.value(script) {
+                            properties = entries;
+                        }
+                    }
+                    _ => {}
+                }
+            } else if type_name.is_empty() {
+                type_name = self.read_word();
+                if self.peek() == Some('(') {
+                    args = self.arguments(script);
+                }
+            } else {
+                self.value(script);
+            }
+            if self.pos == before {
+                break;
+            }
+        }
+
+        let lower = type_name.to_lowercase();
+        if lower.ends_with("webrequestsession") {
+            return PsValue::Session;
+        }
+        if lower.ends_with("net.cookie") || lower == "cookie" {
+            let property = |key: &str| {
+                properties
+                    .iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                    .and_then(|(_, v)| script.resolve(v).text())
+            };
+            let arg = |i: usize| args.get(i).and_then(|v| script.resolve(v).text());
+            if let Some(name) = arg(0).or_else(|| property("Name")) {
+                return PsValue::Cookie(Cookie {
+                    name,
+                    value: arg(1).or_else(|| property("Value")).unwrap_or_default(),
+                });
+            }
+        }
+        PsValue::Expr
+    }
+
+    /// 逗号分隔的值列表（`a, b, c`），只有一个值时直接返回该值。
+    fn list(&mut self, script: &mut Script) -> PsValue {
+        let mut items = vec![self.value(script)];
+        loop {
+            self.skip_inline();
+            if !self.eat(",") {
+                break;
+            }
+            self.skip_all();
+            items.push(self.value(script));
+        }
+        if items.len() == 1 {
+            items.remove(0)
+        } else {
+            PsValue::Array(items)
+        }
+    }
+
+    /// 方法调用的参数列表 `(a, b, c)`。
+    fn arguments(&mut self, script: &mut Script) -> Vec<PsValue> {
+        let mut args = Vec::new();
+        if !self.eat("(") {
+            return args;
+        }
+        loop {
+            self.skip_all();
+            if self.peek().is_none() || self.eat(")") {
+                break;
+            }
+            let before = self.pos;
+            args.push(self.expression(script));
+            self.skip_all();
+            self.eat(",");
+            if self.pos == before {
+                self.bump();
+            }
+        }
+        args
+    }
+
+    /// 单个值：字符串、here-string、哈希表、数组、变量、括号表达式、`[类型]::方法(...)` 或裸词。
+    fn value(&mut self, script: &mut Script) -> PsValue {
+        self.skip_inline();
+        let rest = self.rest();
+        if let Some((text, consumed)) = shell_words::read_powershell_string(rest) {
+            self.pos += consumed;
+            return PsValue::Str(text);
+        }
+        if rest.starts_with("@\"") || rest.starts_with("@'") {
+            return self.here_string();
+        }
+        if rest.starts_with("@{") {
+            return self.hashtable(script);
+        }
+        if rest.starts_with("@(") {
+            self.pos += 1;
+            return PsValue::Array(self.arguments(script));
+        }
+        match self.peek() {
+            Some('$') => {
+                self.bump();
+                let name = self.read_name().to_lowercase();
+                match name.as_str() {
+                    "null" => PsValue::Null,
+                    "true" | "false" => PsValue::Str(name),
+                    _ => {
+                        if self.peek() == Some('(') {
+                            self.arguments(script);
+                            return PsValue::Expr;
+                        }
+                        PsValue::Var(name)
+                    }
+                }
+            }
+            Some('(') => {
+                self.bump();
+                self.skip_all();
+                let value = self.expression(script);
+                self.skip_balanced(')');
+                value
+            }
+            Some('[') => self.type_expression(script),
+            Some('{') => {
+                self.bump();
+                self.skip_balanced('}');
+                PsValue::Expr
+            }
+            Some(_) => {
+                let word = self.read_word();
+                if word.is_empty() {
+                    PsValue::Expr
+                } else {
+                    PsValue::Str(word)
+                }
+            }
+            None => PsValue::Null,
+        }
+    }
+
+    /// `[System.Text.Encoding]::UTF8.GetBytes("...")` 取其参数；`[string]$x` 等类型转换取后面的值。
+    fn type_expression(&mut self, script: &mut Script) -> PsValue {
+        self.bump();
+        let rest = self.rest();
+        let end = rest.find(']').unwrap_or(rest.len());
+        self.pos += (end + 1).min(rest.len());
+        if !self.eat("::") {
+            return self.value(script);
+        }
+        let member = self.read_word();
+        if self.peek() != Some('(') {
+            return PsValue::Expr;
+        }
+        let args = self.arguments(script);
+        if member.to_lowercase().ends_with("getbytes") {
+            return args.into_iter().next().unwrap_or(PsValue::Null);
+        }
+        PsValue::Expr
+    }
+
+    /// `@{ key = value; "key" = value }`，条目以换行或分号分隔。
+    fn hashtable(&mut self, script: &mut Script) -> PsValue {
+        self.pos += 2;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_all();
+            while self.eat(";") {
+                self.skip_all();
+            }
+            if self.peek().is_none() || self.eat("}") {
+                break;
+            }
+            let before = self.pos;
+            let key = match shell_words::read_powershell_string(self.rest()) {
+                Some((text, consumed)) => {
+                    self.pos += consumed;
+                    text
+                }
+                None => self.read_word(),
+            };
+            self.skip_inline();
+            if self.eat("=") {
+                self.skip_inline();
+                let value = self.expression(script);
+                entries.push((key, value));
+            }
+            if self.pos == before {
+                self.bump();
+            }
+        }
+        PsValue::Hashtable(entries)
+    }
+
+    /// `@"` ... `"@` / `@'` ... `'@`：结束标记位于行首，内容不含首尾换行。
+    fn here_string(&mut self) -> PsValue {
+        let quote = self.rest()[1..2].to_string();
+        self.pos += 2;
+        let rest = self.rest();
+        let body_start = rest.find('\n').map(|i| i + 1).unwrap_or(rest.len());
+        let terminator = format!("\n{quote}@");
+        let body = &rest[body_start..];
+        let (content, consumed) = match body.find(&terminator) {
+            Some(end) => (&body[..end], body_start + end + terminator.len()),
+            None => (body, rest.len()),
+        };
+        self.pos += consumed;
+        PsValue::Str(content.trim_end_matches('\r').to_string())
+    }
+
+    /// 变量名，包括成员访问（`session.Cookies.Add`）与作用域前缀（`script:x`）。
+    fn read_name(&mut self) -> String {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == ':'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        rest[..len].to_string()
+    }
+
+    /// 跳到与已消费的左括号匹配的 `close`，忽略字符串中的括号。
+    fn skip_balanced(&mut self, close: char) {
+        let open = if close == ')' { '(' } else { '{' };
+        let mut depth = 1;
+        while depth > 0 {
+            if let Some((_, consumed)) = shell_words::read_powershell_string(self.rest()) {
+                self.pos += consumed;
+                continue;
+            }
+            match self.bump() {
+                Some(c) if c == open => depth += 1,
+                Some(c) if c == close => depth -= 1,
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+
+    /// 跳过语句剩余部分（管道后的命令等），直到换行或分号。
+    fn skip_rest_of_statement(&mut self) {
+        loop {
+            self.skip_inline();
+            match self.peek() {
+                None | Some('\r' | '\n' | ';') => break,
+                Some('(') => {
+                    self.bump();
+                    self.skip_balanced(')');
+                }
+                Some('{') => {
+                    self.bump();
+                    self.skip_balanced('}');
+                }
+                Some('#') => {
+                    let rest = self.rest();
+                    self.pos += rest.find('\n').unwrap_or(rest.len());
+                }
+                Some(_) => {
+                    if let Some((_, consumed)) = shell_words::read_powershell_string(self.rest()) {
+                        self.pos += consumed;
+                    } else {
+                        self.bump();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 别名统一为完整命令名。
+fn canonical_command(command: &str) -> String {
+    match command.to_lowercase().as_str() {
+        "iwr" | "invoke-webrequest" => "Invoke-WebRequest".to_string(),
+        "irm" | "invoke-restmethod" => "Invoke-RestMethod".to_string(),
+        _ => command.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME: &str = r#"$session = New-Object Microsoft.PowerShell.Commands.WebRequestSession
+$session.UserAgent = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) Chrome/120.0.0.0 Safari/537.36"
+$session.Cookies.Add((New-Object System.Net.Cookie("_ga", "GA1.1.42", "/", ".example.com")))
+$session.Cookies.Add((New-Object System.Net.Cookie("sid", "a=b", "/", "example.com")))
+Invoke-WebRequest -UseBasicParsing -Uri "https://example.com/api/items?page=2" `
+-Method "POST" `
+-WebSession $session `
+-Headers @{
+"authority"="example.com"
+  "method"="POST"
+  "path"="/api/items?page=2"
+  "scheme"="https"
+  "accept"="application/json, text/plain, */*"
+  "sec-ch-ua"="`"Chromium`";v=`"120`""
+  "x-requested-with"='XMLHttpRequest'
+} `
+-ContentType "application/json;charset=UTF-8" `
+-Body ([System.Text.Encoding]::UTF8.GetBytes("{`"name`":`"$`$x`",`"qty`":2}"))"#;
+
+    #[test]
+    fn test_chrome_copy_as_powershell() {
+        let result = parse_powershell(CHROME);
+
+        assert!(matches!(result.content_type, HttpContentType::Request));
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(
+            result.url.as_deref(),
+            Some("https://example.com/api/items?page=2")
+        );
+
+        let pseudo = result.pseudo_headers.as_ref().unwrap();
+        assert_eq!(pseudo.len(), 4);
+        assert_eq!(pseudo[0].key, ":authority");
+
+        let header = |name: &str| parse_utils::header_value(&result.headers, name);
+        assert_eq!(header("sec-ch-ua"), Some(r#""Chromium";v="120""#));
+        assert_eq!(header("x-requested-with"), Some("XMLHttpRequest"));
+        assert!(header("User-Agent").unwrap().contains("Chrome/120"));
+        assert_eq!(header("Cookie"), Some("_ga=GA1.1.42; sid=a=b"));
+        assert_eq!(
+            header("Content-Type"),
+            Some("application/json;charset=UTF-8")
+        );
+        assert_eq!(result.body.as_deref(), Some(r#"{"name":"$$x","qty":2}"#));
+        assert_eq!(result.metadata.unwrap()[0].value, "Invoke-WebRequest");
+    }
+
+    #[test]
+    fn test_invoke_rest_method_with_variables() {
+        let input = r#"
+# fetch the profile
+$headers = @{ Authorization = "Bearer abc"; 'X-Trace' = 42 }
+$body = @{ name = 'Ann'; city = 'Paris' }
+$resp = Invoke-RestMethod -Uri 'https://api.example.com/users' -Method Put -Headers $headers -Body $body -TimeoutSec 30 -SkipCertificateCheck | ConvertTo-Json
+"#;
+        let result = parse_powershell(input);
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+        assert_eq!(result.headers[0].value, "Bearer abc");
+        assert_eq!(result.headers[1].value, "42");
+        assert_eq!(result.headers[2].value, "application/x-www-form-urlencoded");
+        assert_eq!(result.body.as_deref(), Some("name=Ann&city=Paris"));
+
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata[0].value, "Invoke-RestMethod");
+        assert_eq!(metadata[1].key, "timeout");
+        assert_eq!(metadata[1].value, "30");
+        assert_eq!(metadata[2].key, "insecure");
+    }
+
+    #[test]
+    fn test_positional_uri_and_get_hashtable_body() {
+        let result = parse_powershell(
+            "iwr https://example.com/search -Body @{q='rust lang'} -UserAgent 'Bot/1.0'",
+        );
+        assert_eq!(result.method.as_deref(), Some("GET"));
+        assert_eq!(
+            result.url.as_deref(),
+            Some("https://example.com/search?q=rust+lang")
+        );
+        assert!(result.body.is_none());
+        assert_eq!(result.headers[0].value, "Bot/1.0");
+    }
+
+    #[test]
+    fn test_here_string_body_and_bearer_token() {
+        let input = "$json = @\"\n{\n  \"a\": 1\n}\n\"@\nInvoke-WebRequest -Uri https://example.com/x -Method POST -Body $json -Authentication Bearer -Token (ConvertTo-SecureString 'tok' -AsPlainText -Force)";
+        let result = parse_powershell(input);
+        assert_eq!(result.body.as_deref(), Some("{\n  \"a\": 1\n}"));
+        assert_eq!(result.headers[0].value, "Bearer tok");
+    }
+
+    #[test]
+    fn test_missing_invocation() {
+        let result = parse_powershell(
+            "$session = New-Object Microsoft.PowerShell.Commands.WebRequestSession",
+        );
+        assert!(matches!(result.content_type, HttpContentType::Unknown));
+    }
+}
@@ -0,0 +1,281 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::models::{HttpContentType, HttpExchange, ParseNode, ParseResult};
+use crate::parse_utils::{self, metadata_node};
+use crate::parser;
+
+/// ZAP 导出消息的分隔行：`==== 12 ==========`
+static RE_ZAP_SEPARATOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^==== \d+ =+[ \t]*\r?$").unwrap());
+
+static RE_RESPONSE_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^HTTP/\d(\.\d)?\s+\d{3}(\s+.*)?$").unwrap());
+
+/// 解析 Burp Suite "Save items" 导出的 XML：每个 `<item>` 的 request / response
+/// （通常为 base64）交给 `parser::parse_http_text`，并用 protocol / host / port 补全绝对 URL。
+pub fn parse_burp(input: &str) -> Vec<HttpExchange> {
+    // Burp 导出文件带内部 DTD 声明
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let Ok(doc) = roxmltree::Document::parse_with_options(input.trim(), options) else {
+        return Vec::new();
+    };
+
+    doc.root_element()
+        .children()
+        .filter(|n| n.has_tag_name("item"))
+        .filter_map(burp_item)
+        .collect()
+}
+
+/// 将单个 `<item>` 转换为请求/响应对，缺少请求时跳过。
+fn burp_item(item: roxmltree::Node) -> Option<HttpExchange> {
+    let child = |name: &str| item.children().find(|n| n.has_tag_name(name));
+    let text = |name: &str| {
+        child(name)
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    };
+    let message = |name: &str| {
+        let node = child(name)?;
+        let raw = node.text()?;
+        let text = if node.attribute("base64") == Some("true") {
+            let bytes = general_purpose::STANDARD
+                .decode(raw.split_whitespace().collect::<String>())
+                .ok()?;
+            parse_utils::http_message_text(&bytes)
+        } else {
+            raw.to_string()
+        };
+        (!text.trim().is_empty()).then_some(text)
+    };
+
+    let mut request = parser::parse_http_text(&message("request")?);
+    if let (Some(protocol), Some(host)) = (text("protocol"), text("host")) {
+        let port = text("port").and_then(|p| p.parse::<u16>().ok());
+        apply_origin(&mut request, protocol, host, port);
+    }
+
+    let mut metadata: Vec<ParseNode> = Vec::new();
+    if let Some(ip) = child("host")
+        .and_then(|n| n.attribute("ip"))
+        .filter(|ip| !ip.is_empty())
+    {
+        metadata.push(metadata_node("ip", ip));
+    }
+    if let Some(comment) = text("comment") {
+        metadata.push(metadata_node("comment", comment));
+    }
+    if !metadata.is_empty() {
+        request
+            .metadata
+            .get_or_insert_with(Vec::new)
+            .extend(metadata);
+    }
+
+    let response = message("response")
+        .map(|raw| parser::parse_http_text(&raw))
+        .filter(|r| matches!(r.content_type, HttpContentType::Response));
+
+    Some(HttpExchange {
+        request: Some(request),
+        response,
+        started_at: text("time").map(str::to_string),
+        duration_ms: None,
+    })
+}
+
+/// 解析 ZAP "Export Messages to File" 导出的文本：以 `==== N ==========` 分隔，
+/// 每段依次为请求与响应（请求行为绝对 URL）。
+pub fn parse_zap(input: &str) -> Vec<HttpExchange> {
+    RE_ZAP_SEPARATOR
+        .split(input)
+        .map(str::trim)
+        .filter(|block| !block.is_empty())
+        .filter_map(|block| {
+            let (request_text, response_text) = split_zap_block(block);
+            let request = parser::parse_http_text(request_text);
+            if !matches!(request.content_type, HttpContentType::Request) {
+                return None;
+            }
+            let response = response_text
+                .map(parser::parse_http_text)
+                .filter(|r| matches!(r.content_type, HttpContentType::Response));
+            Some(HttpExchange {
+                request: Some(request),
+                response,
+                started_at: None,
+                duration_ms: None,
+            })
+        })
+        .collect()
+}
+
+/// 在第一个响应状态行处把 ZAP 消息切分为请求与响应。
+fn split_zap_block(block: &str) -> (&str, Option<&str>) {
+    let mut offset = 0;
+    for (idx, line) in block.split_inclusive('\n').enumerate() {
+        if idx > 0 && RE_RESPONSE_LINE.is_match(line.trim_end()) {
+            return (block[..offset].trim(), Some(block[offset..].trim()));
+        }
+        offset += line.len();
+    }
+    (block, None)
+}
+
+/// 用导出文件记录的连接信息补全 origin-form 请求的 URL（Host header 可能缺失或与实际连接不符）。
+fn apply_origin(request: &mut ParseResult, protocol: &str, host: &str, port: Option<u16>) {
+    let Some(target) = request
+        .request_target
+        .as_deref()
+        .filter(|t| t.starts_with('/') || *t == "*")
+    else {
+        return;
+    };
+
+    let protocol = protocol.to_lowercase();
+    let default_port = match protocol.as_str() {
+        "https" => 443,
+        _ => 80,
+    };
+    let authority = match port {
+        Some(port) if port != default_port => format!("{host}:{port}"),
+        _ => host.to_string(),
+    };
+    let url = if target == "*" {
+        format!("{protocol}://{authority}")
+    } else {
+        format!("{protocol}://{authority}{target}")
+    };
+
+    request.url_nodes = parse_utils::parse_url_nodes(&url);
+    request.url = Some(url);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn burp_export(request: &str, response: &[u8]) -> String {
+        format!(
+            r#"<?xml version="1.0"?>
+<!DOCTYPE items [
+<!ELEMENT items (item*)>
+<!ATTLIST items burpVersion CDATA "">
+]>
+<items burpVersion="2024.1.1" exportTime="Mon Jan 08 10:00:00 CET 2024">
+  <item>
+    <time>Mon Jan 08 09:59:58 CET 2024</time>
+    <url><![CDATA[https://shop.example.com:8443/api/cart?id=7]]></url>
+    <host ip="203.0.113.5">shop.example.com</host>
+    <port>8443</port>
+    <protocol>https</protocol>
+    <method><![CDATA[POST]]></method>
+    <path><![CDATA[/api/cart?id=7]]></path>
+    <request base64="true"><![CDATA[{}]]></request>
+    <status>200</status>
+    <response base64="true"><![CDATA[{}]]></response>
+    <comment>checkout flow</comment>
+  </item>
+  <item>
+    <host ip="">example.com</host>
+    <port>80</port>
+    <protocol>http</protocol>
+    <request base64="false"><![CDATA[GET /plain HTTP/1.1
+Host: example.com
+
+]]></request>
+    <response base64="true"></response>
+  </item>
+</items>"#,
+            general_purpose::STANDARD.encode(request),
+            general_purpose::STANDARD.encode(response),
+        )
+    }
+
+    #[test]
+    fn test_burp_items() {
+        let request = "POST /api/cart?id=7 HTTP/1.1\r\nHost: shop.example.com\r\nContent-Type: application/json\r\n\r\n{\"qty\":2}";
+        let response = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"ok\":true}";
+        let exchanges = parse_burp(&burp_export(request, response));
+        assert_eq!(exchanges.len(), 2);
+
+        let first = &exchanges[0];
+        assert_eq!(
+            first.started_at.as_deref(),
+            Some("Mon Jan 08 09:59:58 CET 2024")
+        );
+        let req = first.request.as_ref().unwrap();
+        assert_eq!(req.method.as_deref(), Some("POST"));
+        assert_eq!(
+            req.url.as_deref(),
+            Some("https://shop.example.com:8443/api/cart?id=7")
+        );
+        assert_eq!(req.body.as_deref(), Some("{\"qty\":2}"));
+        let metadata = req.metadata.as_ref().unwrap();
+        assert_eq!(metadata[0].value, "203.0.113.5");
+        assert_eq!(metadata[1].value, "checkout flow");
+
+        let resp = first.response.as_ref().unwrap();
+        assert_eq!(resp.status_code, Some(200));
+        assert_eq!(resp.body.as_deref(), Some("{\"ok\":true}"));
+
+        let second = &exchanges[1];
+        let req = second.request.as_ref().unwrap();
+        assert_eq!(req.url.as_deref(), Some("http://example.com/plain"));
+        assert!(req.metadata.is_none());
+        assert!(second.response.is_none());
+    }
+
+    #[test]
+    fn test_burp_binary_body_kept_as_base64() {
+        let mut response = b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\r\n".to_vec();
+        response.extend_from_slice(&[0x89, b'P', b'N', b'G', 0xff, 0x00]);
+        let exchanges = parse_burp(&burp_export("GET / HTTP/1.1\r\nHost: a\r\n\r\n", &response));
+        let resp = exchanges[0].response.as_ref().unwrap();
+        assert_eq!(resp.body.as_deref(), Some("iVBOR/8A"));
+    }
+
+    #[test]
+    fn test_zap_messages() {
+        let input = "==== 3 ==========\n\
+GET http://example.com/search?q=zap HTTP/1.1\n\
+User-Agent: Mozilla/5.0\n\
+Host: example.com\n\
+\n\
+\n\
+HTTP/1.1 200 OK\n\
+Content-Type: text/html\n\
+\n\
+<html></html>\n\
+==== 4 ==========\n\
+POST http://example.com/login HTTP/1.1\n\
+Host: example.com\n\
+Content-Type: application/x-www-form-urlencoded\n\
+\n\
+user=a&pass=b\n";
+        let exchanges = parse_zap(input);
+        assert_eq!(exchanges.len(), 2);
+
+        let req = exchanges[0].request.as_ref().unwrap();
+        assert_eq!(req.url.as_deref(), Some("http://example.com/search?q=zap"));
+        let resp = exchanges[0].response.as_ref().unwrap();
+        assert_eq!(resp.status_code, Some(200));
+        assert_eq!(resp.body.as_deref(), Some("<html></html>"));
+
+        let login = exchanges[1].request.as_ref().unwrap();
+        assert_eq!(login.body.as_deref(), Some("user=a&pass=b"));
+        assert!(exchanges[1].response.is_none());
+    }
+
+    #[test]
+    fn test_invalid_burp_xml() {
+        assert!(parse_burp("<items><item>").is_empty());
+    }
+}
@@ -0,0 +1,681 @@
+use crate::js_literal::JsValue;
+
+/// 一次 Python 函数调用：位置参数与关键字参数。
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyCall {
+    pub args: Vec<JsValue>,
+    pub kwargs: Vec<(String, JsValue)>,
+    /// 调用文本（含右括号）在输入中占用的字节数
+    pub consumed: usize,
+}
+
+/// 读取调用参数，`input` 从 `(` 之后开始，到匹配的 `)` 为止。
+pub fn parse_call_arguments(input: &str) -> PyCall {
+    let mut reader = Reader::new(input);
+    let (args, kwargs) = reader.arguments();
+    PyCall {
+        args,
+        kwargs,
+        consumed: reader.consumed_bytes(),
+    }
+}
+
+/// 收集模块级（行首、无缩进）的 `name = value` 赋值，后出现的赋值覆盖先出现的。
+pub fn parse_assignments(source: &str) -> Vec<(String, JsValue)> {
+    let mut assignments: Vec<(String, JsValue)> = Vec::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        let name_len = line
+            .char_indices()
+            .find(|(i, c)| !(c.is_alphanumeric() || *c == '_') || (*i == 0 && c.is_ascii_digit()))
+            .map_or(line.len(), |(i, _)| i);
+        if name_len == 0 {
+            continue;
+        }
+        let rest = line[name_len..].trim_start_matches([' ', '\t']);
+        let Some(value) = rest.strip_prefix('=') else {
+            continue;
+        };
+        if value.starts_with('=') {
+            continue;
+        }
+
+        let name = line[..name_len].to_string();
+        let value_start = start + (line.len() - value.len());
+        let mut reader = Reader::new(&source[value_start..]);
+        let value = reader.operand();
+        assignments.retain(|(k, _)| *k != name);
+        assignments.push((name, value));
+    }
+    assignments
+}
+
+/// 将 `a + "b" + c` 形式的表达式拆为各个操作数，至少两个操作数时返回。
+pub fn split_concatenation(expr: &str) -> Option<Vec<JsValue>> {
+    let mut reader = Reader::new(expr);
+    let mut pieces = vec![reader.value()];
+    loop {
+        reader.skip_ws();
+        match reader.peek() {
+            None => break,
+            Some('+') => {
+                reader.pos += 1;
+                pieces.push(reader.value());
+            }
+            Some(_) => return None,
+        }
+    }
+    (pieces.len() > 1).then_some(pieces)
+}
+
+/// 序列化为 Python `json.dumps` 的输出：`compact` 为 `separators=(",", ":")` 且不转义非 ASCII（httpx），
+/// 否则为默认的 `", "` / `": "` 分隔并转义非 ASCII（requests）。
+pub fn json_dumps(value: &JsValue, compact: bool) -> String {
+    let (item_sep, key_sep) = if compact { (",", ":") } else { (", ", ": ") };
+    match value {
+        JsValue::Null => "null".to_string(),
+        JsValue::Bool(b) => b.to_string(),
+        JsValue::Number(n) => n.replace('_', ""),
+        JsValue::String(s) | JsValue::SearchParams(s) | JsValue::Expr(s) => {
+            json_string(s, !compact)
+        }
+        JsValue::Array(items) => {
+            let items: Vec<String> = items.iter().map(|v| json_dumps(v, compact)).collect();
+            format!("[{}]", items.join(item_sep))
+        }
+        JsValue::Object(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}{key_sep}{}",
+                        json_string(k, !compact),
+                        json_dumps(v, compact)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", entries.join(item_sep))
+        }
+    }
+}
+
+fn json_string(s: &str, ensure_ascii: bool) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 || (ensure_ascii && !c.is_ascii()) => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{unit:04x}"));
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+    /// 括号嵌套层数；括号外换行结束语句，括号内换行只是空白
+    nesting: usize,
+}
+
+impl Reader {
+    fn new(input: &str) -> Self {
+        Reader {
+            chars: input.chars().collect(),
+            pos: 0,
+            nesting: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.chars[start..end].iter().collect()
+    }
+
+    fn consumed_bytes(&self) -> usize {
+        self.chars[..self.pos].iter().map(|c| c.len_utf8()).sum()
+    }
+
+    /// 跳过空白、`#` 注释与 `\` 续行（括号内的换行在 Python 中本就允许）。
+    fn skip_ws(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() && (c != '\n' || self.nesting > 0) => self.pos += 1,
+                Some('\\') if matches!(self.peek_at(1), Some('\n' | '\r')) => self.pos += 2,
+                Some('#') => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn arguments(&mut self) -> (Vec<JsValue>, Vec<(String, JsValue)>) {
+        let mut args = Vec::new();
+        let mut kwargs = Vec::new();
+        self.nesting += 1;
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => break,
+                Some(')') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => self.pos += 1,
+                Some(_) => {
+                    let before = self.pos;
+                    if let Some(name) = self.keyword_name() {
+                        let value = self.operand();
+                        kwargs.push((name, value));
+                    } else {
+                        args.push(self.operand());
+                    }
+                    if self.pos == before {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+        self.nesting -= 1;
+        (args, kwargs)
+    }
+
+    /// 读取 `name=`（不含 `==`），不是关键字参数时不消费任何字符。
+    fn keyword_name(&mut self) -> Option<String> {
+        let start = self.pos;
+        let mut end = start;
+        while self
+            .chars
+            .get(end)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            end += 1;
+        }
+        if end == start || self.chars[start].is_ascii_digit() {
+            return None;
+        }
+        let mut eq = end;
+        while self.chars.get(eq).is_some_and(|c| *c == ' ' || *c == '\t') {
+            eq += 1;
+        }
+        if self.chars.get(eq) != Some(&'=') || self.chars.get(eq + 1) == Some(&'=') {
+            return None;
+        }
+        self.pos = eq + 1;
+        Some(self.text(start, end))
+    }
+
+    /// 读取一个值并处理其后的 `+` 拼接；其余运算符连同整个表达式按原文保存。
+    fn operand(&mut self) -> JsValue {
+        let start = self.pos;
+        let mut value = self.value();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None | Some(',' | ')' | ']' | '}' | ':' | '\n') => return value,
+                Some('+') => {
+                    self.pos += 1;
+                    self.skip_ws();
+                    let rhs = self.value();
+                    value = match (&value, &rhs) {
+                        (JsValue::String(l), JsValue::String(r)) => {
+                            JsValue::String(format!("{l}{r}"))
+                        }
+                        _ => JsValue::Expr(self.text(start, self.pos).trim().to_string()),
+                    };
+                }
+                Some(_) => {
+                    self.skip_expression();
+                    return JsValue::Expr(self.text(start, self.pos).trim().to_string());
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> JsValue {
+        self.skip_ws();
+        match self.peek() {
+            Some('{') => self.dict(),
+            Some('[') => {
+                self.pos += 1;
+                JsValue::Array(self.sequence(']'))
+            }
+            Some('(') => {
+                // 元组；单个元素且无逗号时只是括号表达式
+                self.pos += 1;
+                let start = self.pos;
+                let items = self.sequence(')');
+                let has_comma = self.chars[start..self.pos].contains(&',');
+                match items.len() {
+                    1 if !has_comma => items.into_iter().next().unwrap_or(JsValue::Null),
+                    _ => JsValue::Array(items),
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' || c == '-' => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                if let Some(value) = self.strings() {
+                    return value;
+                }
+                self.identifier_value()
+            }
+            Some('"' | '\'') => self.strings().unwrap_or(JsValue::Null),
+            _ => {
+                let start = self.pos;
+                self.skip_expression();
+                JsValue::Expr(self.text(start, self.pos).trim().to_string())
+            }
+        }
+    }
+
+    fn dict(&mut self) -> JsValue {
+        self.pos += 1;
+        self.nesting += 1;
+        let mut entries = Vec::new();
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => break,
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                Some('*') if self.peek_at(1) == Some('*') => {
+                    // `**base` 展开，key 记为 `**`，由调用方按变量表合并
+                    self.pos += 2;
+                    entries.push(("**".to_string(), self.operand()));
+                    continue;
+                }
+                Some(_) => {}
+            }
+
+            let before = self.pos;
+            let key = self.operand();
+            self.skip_ws();
+            if self.peek() == Some(':') {
+                self.pos += 1;
+                let value = self.operand();
+                entries.push((key.to_text().unwrap_or_default(), value));
+            } else {
+                // 集合字面量 `{a, b}`
+                items.push(key);
+                if self.pos == before {
+                    self.pos += 1;
+                }
+            }
+        }
+        self.nesting -= 1;
+        if entries.is_empty() && !items.is_empty() {
+            JsValue::Array(items)
+        } else {
+            JsValue::Object(entries)
+        }
+    }
+
+    fn sequence(&mut self, close: char) -> Vec<JsValue> {
+        let mut items = Vec::new();
+        self.nesting += 1;
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => break,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => self.pos += 1,
+                Some(_) => {
+                    let before = self.pos;
+                    items.push(self.operand());
+                    if self.pos == before {
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+        self.nesting -= 1;
+        items
+    }
+
+    /// 读取一个或多个相邻的字符串字面量（Python 会将其拼接），支持 `r` / `b` / `u` / `f` 前缀与三引号。
+    /// 当前位置不是字符串时返回 None 且不消费字符。
+    fn strings(&mut self) -> Option<JsValue> {
+        let mut out = String::new();
+        let mut found = false;
+        loop {
+            let saved = self.pos;
+            let mut raw = false;
+            let mut prefix_len = 0;
+            while prefix_len < 2 {
+                match self.peek_at(prefix_len) {
+                    Some('r' | 'R') => raw = true,
+                    Some('b' | 'B' | 'u' | 'U' | 'f' | 'F') => {}
+                    _ => break,
+                }
+                prefix_len += 1;
+            }
+            let Some(quote @ ('"' | '\'')) = self.peek_at(prefix_len) else {
+                self.pos = saved;
+                break;
+            };
+            self.pos += prefix_len;
+            out.push_str(&self.string(quote, raw));
+            found = true;
+            self.skip_inline_ws();
+        }
+        found.then_some(JsValue::String(out))
+    }
+
+    /// 字符串拼接只跨越同一逻辑行内的空白（括号内可跨行）。
+    fn skip_inline_ws(&mut self) {
+        let saved = self.pos;
+        self.skip_ws();
+        let next_is_string = match self.peek() {
+            Some('"' | '\'') => true,
+            Some('r' | 'R' | 'b' | 'B' | 'u' | 'U' | 'f' | 'F') => {
+                matches!(self.peek_at(1), Some('"' | '\''))
+                    || (matches!(self.peek_at(1), Some(c) if c.is_alphabetic())
+                        && matches!(self.peek_at(2), Some('"' | '\'')))
+            }
+            _ => false,
+        };
+        if !next_is_string {
+            self.pos = saved;
+        }
+    }
+
+    fn string(&mut self, quote: char, raw: bool) -> String {
+        let triple = self.peek_at(1) == Some(quote) && self.peek_at(2) == Some(quote);
+        self.pos += if triple { 3 } else { 1 };
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            if c == quote
+                && (!triple || (self.peek_at(1) == Some(quote) && self.peek_at(2) == Some(quote)))
+            {
+                self.pos += if triple { 3 } else { 1 };
+                break;
+            }
+            if !triple && c == '\n' {
+                break;
+            }
+            self.pos += 1;
+            if c == '\\' {
+                if raw {
+                    out.push('\\');
+                    if let Some(next) = self.peek() {
+                        out.push(next);
+                        self.pos += 1;
+                    }
+                } else {
+                    self.escape(&mut out);
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    /// 处理 `\` 之后的转义：`\n \r \t \a \b \f \v \0`、八进制、`\xHH`、`\uHHHH`、`\UHHHHHHHH` 与续行。
+    fn escape(&mut self, out: &mut String) {
+        let Some(c) = self.peek() else {
+            out.push('\\');
+            return;
+        };
+        self.pos += 1;
+        match c {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            'a' => out.push('\u{7}'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '\\' | '\'' | '"' => out.push(c),
+            '\n' => {}
+            '0'..='7' => {
+                let mut code = c.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match self.peek().and_then(|d| d.to_digit(8)) {
+                        Some(d) => {
+                            code = code * 8 + d;
+                            self.pos += 1;
+                        }
+                        None => break,
+                    }
+                }
+                out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            'x' | 'u' | 'U' => {
+                let len = match c {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8,
+                };
+                let digits: String = self.chars[self.pos..]
+                    .iter()
+                    .take(len)
+                    .take_while(|d| d.is_ascii_hexdigit())
+                    .collect();
+                match u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| digits.len() == len)
+                {
+                    Some(code) => {
+                        self.pos += len;
+                        out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                    }
+                    None => {
+                        out.push('\\');
+                        out.push(c);
+                    }
+                }
+            }
+            other => {
+                // 未知转义在 Python 中保留反斜杠
+                out.push('\\');
+                out.push(other);
+            }
+        }
+    }
+
+    fn number(&mut self) -> JsValue {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while let Some(c) = self.peek() {
+            let exponent_sign = matches!(c, '+' | '-')
+                && matches!(self.chars.get(self.pos.wrapping_sub(1)), Some('e' | 'E'));
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || exponent_sign {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        JsValue::Number(self.text(start, self.pos))
+    }
+
+    /// `True` / `False` / `None`、`json.dumps(...)`、`dict(...)`，其余标识符与调用按原文保存。
+    fn identifier_value(&mut self) -> JsValue {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            self.pos += 1;
+        }
+        let name = self.text(start, self.pos);
+        match name.as_str() {
+            "True" => return JsValue::Bool(true),
+            "False" => return JsValue::Bool(false),
+            "None" => return JsValue::Null,
+            _ => {}
+        }
+
+        self.skip_ws();
+        if self.peek() != Some('(') {
+            return JsValue::Expr(name);
+        }
+        self.pos += 1;
+        let (args, kwargs) = self.arguments();
+        let raw = JsValue::Expr(self.text(start, self.pos));
+        match name.as_str() {
+            "json.dumps" if kwargs.is_empty() => match args.first() {
+                Some(arg) if !contains_expr(arg) => JsValue::String(json_dumps(arg, false)),
+                _ => raw,
+            },
+            "dict" if args.is_empty() => JsValue::Object(kwargs),
+            _ => raw,
+        }
+    }
+
+    /// 跳过一个无法识别的表达式，直到同层级的 `,` / `)` / `]` / `}` / `:` 或换行。
+    fn skip_expression(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' | ',' | ':' if depth == 0 => return,
+                '\n' if depth == 0 && self.nesting == 0 => return,
+                ')' | ']' | '}' => depth -= 1,
+                '"' | '\'' => {
+                    self.string(c, false);
+                    continue;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+fn contains_expr(value: &JsValue) -> bool {
+    match value {
+        JsValue::Expr(_) => true,
+        JsValue::Array(items) => items.iter().any(contains_expr),
+        JsValue::Object(entries) => entries.iter().any(|(_, v)| contains_expr(v)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(input: &str) -> JsValue {
+        Reader::new(input).operand()
+    }
+
+    #[test]
+    fn test_dict_and_literals() {
+        let v = value("{'a': True, \"b\": None, 'c': [1, 2.5,], 'd': (1, 'x'), 'e': ('y'),}");
+        assert_eq!(
+            v.to_json(),
+            r#"{"a":true,"b":null,"c":[1,2.5],"d":[1,"x"],"e":"y"}"#
+        );
+    }
+
+    #[test]
+    fn test_string_forms() {
+        assert_eq!(value(r"r'\d+'"), JsValue::String(r"\d+".into()));
+        assert_eq!(value(r"'\x41中\101'"), JsValue::String("A中A".into()));
+        assert_eq!(value("'''a\n\"b\"'''"), JsValue::String("a\n\"b\"".into()));
+        assert_eq!(
+            value("('Bearer '\n  'abc')"),
+            JsValue::String("Bearer abc".into())
+        );
+        assert_eq!(value("f'id={uid}'"), JsValue::String("id={uid}".into()));
+        assert_eq!(value("b'raw'"), JsValue::String("raw".into()));
+    }
+
+    #[test]
+    fn test_calls_and_expressions() {
+        assert_eq!(
+            value("json.dumps({'a': 1, 'b': 'é'})"),
+            JsValue::String(r#"{"a": 1, "b": "\u00e9"}"#.into())
+        );
+        assert_eq!(value("dict(a=1, b='x')").to_json(), r#"{"a":1,"b":"x"}"#);
+        assert_eq!(
+            value("open('a.txt', 'rb')"),
+            JsValue::Expr("open('a.txt', 'rb')".into())
+        );
+        assert_eq!(value("'a' + token"), JsValue::Expr("'a' + token".into()));
+        assert_eq!(
+            value("{**base, 'x': 1}").get("**"),
+            Some(&JsValue::Expr("base".into()))
+        );
+        assert_eq!(
+            split_concatenation("BASE + '/a' + path"),
+            Some(vec![
+                JsValue::Expr("BASE".into()),
+                JsValue::String("/a".into()),
+                JsValue::Expr("path".into()),
+            ])
+        );
+        assert_eq!(split_concatenation("BASE"), None);
+    }
+
+    #[test]
+    fn test_call_arguments() {
+        let call = parse_call_arguments("'https://x', data={'a': 1}, timeout=5) # done");
+        assert_eq!(call.args, vec![JsValue::String("https://x".into())]);
+        assert_eq!(
+            call.kwargs[1],
+            ("timeout".into(), JsValue::Number("5".into()))
+        );
+        assert_eq!(
+            call.consumed,
+            "'https://x', data={'a': 1}, timeout=5)".len()
+        );
+    }
+
+    #[test]
+    fn test_module_assignments() {
+        let source = "import requests\n\nheaders = {\n    'accept': '*/*',  # comment\n}\nif x == 1:\n    inner = 2\nurl = 'https://a'\nurl = 'https://b'\n";
+        let assignments = parse_assignments(source);
+        let names: Vec<&str> = assignments.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(names, vec!["headers", "url"]);
+        assert_eq!(assignments[1].1, JsValue::String("https://b".into()));
+    }
+
+    #[test]
+    fn test_json_dumps_styles() {
+        let v = value("{'name': '中', 'list': [1, None]}");
+        assert_eq!(
+            json_dumps(&v, false),
+            r#"{"name": "\u4e2d", "list": [1, null]}"#
+        );
+        assert_eq!(json_dumps(&v, true), r#"{"name":"中","list":[1,null]}"#);
+    }
+}
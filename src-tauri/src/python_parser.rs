@@ -0,0 +1,638 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::js_literal::JsValue;
+use crate::models::{HttpContentType, ParseNode, ParseResult};
+use crate::multipart::{self, FormPart, FORM_BOUNDARY};
+use crate::parse_utils::{self, empty_result, header_node, metadata_node};
+use crate::py_literal::{self, PyCall};
+use crate::query_tree;
+
+/// 请求调用：`requests.post(`、`client.get(`、`httpx.request(` 等
+static RE_REQUEST_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|[^\w.])([A-Za-z_]\w*)\.(get|post|put|patch|delete|head|options|request|stream)\s*\(",
+    )
+    .unwrap()
+});
+
+/// 赋值创建的会话：`s = requests.Session(`、`client = httpx.Client(`
+static RE_CLIENT_ASSIGN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"([A-Za-z_]\w*)\s*=\s*(?:await\s+)?(requests|httpx)\.(?:Session|session|Client|AsyncClient)\s*\(",
+    )
+    .unwrap()
+});
+
+/// with 语句创建的会话：`with httpx.Client(...) as client:`
+static RE_CLIENT_WITH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\bwith\s+(requests|httpx)\.(?:Session|session|Client|AsyncClient)\s*\(").unwrap()
+});
+
+static RE_WITH_AS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*as\s+([A-Za-z_]\w*)").unwrap());
+
+/// 会话级 header 更新：`s.headers.update({...})`
+static RE_HEADERS_UPDATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([A-Za-z_]\w*)\.headers\.update\s*\(").unwrap());
+
+/// 会话（或模块本身）：所属库与构造时的默认参数
+struct Client {
+    library: &'static str,
+    defaults: Vec<(String, JsValue)>,
+}
+
+/// 解析 Python `requests` / `httpx` 代码片段，结果与 `curl_parser` 的形式一致。
+/// 支持会话对象（`Session()` / `Client()`）的默认参数，以及模块级赋值（如 `headers = {...}`）的按名引用。
+pub fn parse_python(input: &str) -> ParseResult {
+    let raw_text = input.to_string();
+    let env: HashMap<String, JsValue> = py_literal::parse_assignments(input).into_iter().collect();
+    let clients = collect_clients(input, &env);
+
+    // 找到第一个由 requests / httpx / 会话对象发起的请求调用
+    let Some((client, verb, call)) = RE_REQUEST_CALL.captures_iter(input).find_map(|caps| {
+        let client = clients.get(&caps[1])?;
+        let start = caps.get(0).map_or(0, |m| m.end());
+        Some((
+            client,
+            caps[2].to_string(),
+            py_literal::parse_call_arguments(&input[start..]),
+        ))
+    }) else {
+        return empty_result(raw_text);
+    };
+
+    let resolved_args: Vec<JsValue> = call.args.iter().map(|v| resolve(v, &env, 0)).collect();
+    let mut kwargs: Vec<(String, JsValue)> = call
+        .kwargs
+        .iter()
+        .map(|(k, v)| (k.clone(), resolve(v, &env, 0)))
+        .collect();
+
+    // 位置参数：request / stream 为 (method, url)，其余为 url 加上各自的可选位置参数
+    let mut positional = resolved_args.into_iter();
+    let method = if verb == "request" || verb == "stream" {
+        positional
+            .next()
+            .and_then(|m| m.to_text())
+            .map(|m| m.to_uppercase())
+    } else {
+        Some(verb.to_uppercase())
+    };
+    let url_value = positional.next();
+    let extra_names: &[&str] = match (client.library, verb.as_str()) {
+        ("requests", "get") => &["params"],
+        ("requests", "post") => &["data", "json"],
+        ("requests", "put" | "patch") => &["data"],
+        _ => &[],
+    };
+    for (name, value) in extra_names.iter().zip(positional) {
+        kwargs.push((name.to_string(), value));
+    }
+    let arg = |name: &str| {
+        kwargs
+            .iter()
+            .find(|(k, _)| k == name)
+            .or_else(|| client.defaults.iter().find(|(k, _)| k == name))
+            .map(|(_, v)| v)
+    };
+    let merged = |name: &str| -> Vec<(String, JsValue)> {
+        let mut entries: Vec<(String, JsValue)> = Vec::new();
+        for source in [&client.defaults, &kwargs] {
+            if let Some((_, JsValue::Object(items))) = source.iter().find(|(k, _)| k == name) {
+                for (key, value) in items {
+                    entries.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+                    entries.push((key.clone(), value.clone()));
+                }
+            }
+        }
+        entries
+    };
+
+    // URL：httpx 的 base_url 与相对路径拼接，params 追加到查询字符串
+    let mut url_str = url_value
+        .or_else(|| arg("url").cloned())
+        .and_then(|u| u.to_text());
+    if let (Some(url), Some(base)) = (&mut url_str, arg("base_url").and_then(JsValue::to_text)) {
+        if !url.contains("://") {
+            *url = format!(
+                "{}/{}",
+                base.trim_end_matches('/'),
+                url.trim_start_matches('/')
+            );
+        }
+    }
+    let params = match arg("params") {
+        Some(JsValue::Object(_)) => form_encode(&JsValue::Object(merged("params"))),
+        Some(value) => form_encode(value),
+        None => None,
+    };
+    if let (Some(url), Some(query)) = (&mut url_str, params.filter(|q| !q.is_empty())) {
+        url.push(if url.contains('?') { '&' } else { '?' });
+        url.push_str(&query);
+    }
+
+    // headers：值为 None 的 header 不会发送
+    let mut headers: Vec<ParseNode> = merged("headers")
+        .iter()
+        .filter_map(|(key, value)| Some(header_node(key, &value.to_text()?)))
+        .collect();
+    let has_header = |headers: &[ParseNode], name: &str| {
+        headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+    };
+
+    let cookies: Vec<String> = merged("cookies")
+        .iter()
+        .filter_map(|(key, value)| Some(format!("{key}={}", value.to_text()?)))
+        .collect();
+    if !cookies.is_empty() && !has_header(&headers, "cookie") {
+        headers.push(header_node("Cookie", &cookies.join("; ")));
+    }
+
+    let mut metadata: Vec<ParseNode> = Vec::new();
+
+    // auth：元组或 HTTPBasicAuth / BasicAuth 生成 Basic 认证头，Digest 记入元数据
+    if let Some((scheme, user, password)) = arg("auth").and_then(auth_credentials) {
+        let credentials = format!("{user}:{password}");
+        if scheme == "basic" {
+            if !has_header(&headers, "authorization") {
+                let encoded = general_purpose::STANDARD.encode(credentials);
+                headers.push(header_node("Authorization", &format!("Basic {encoded}")));
+            }
+        } else {
+            metadata.push(metadata_node(scheme, &credentials));
+        }
+    }
+
+    // body：json= 序列化为 JSON，files= 生成 multipart，data= 为字典时按表单编码
+    let mut body: Option<String> = None;
+    let mut implied_type: Option<String> = None;
+    if let Some(files) = arg("files") {
+        let mut parts: Vec<FormPart> = Vec::new();
+        if let Some(JsValue::Object(fields)) = arg("data") {
+            for (name, value) in fields {
+                parts.push(FormPart {
+                    name: name.clone(),
+                    value: value.to_text().unwrap_or_default(),
+                    filename: None,
+                    content_type: None,
+                });
+            }
+        }
+        parts.extend(file_parts(files));
+        implied_type = Some(format!("multipart/form-data; boundary={FORM_BOUNDARY}"));
+        body = Some(multipart::build_multipart_body(&parts, FORM_BOUNDARY));
+    } else if let Some(json) = arg("json").filter(|v| **v != JsValue::Null) {
+        body = Some(py_literal::json_dumps(json, client.library == "httpx"));
+        implied_type = Some("application/json".to_string());
+    } else if let Some(data) = arg("data").or_else(|| arg("content")) {
+        match data {
+            JsValue::Object(_) | JsValue::Array(_) => {
+                body = form_encode(data);
+                implied_type = Some("application/x-www-form-urlencoded".to_string());
+            }
+            other => body = other.to_text(),
+        }
+    }
+    if let Some(content_type) = implied_type {
+        if !has_header(&headers, "content-type") {
+            headers.push(header_node("Content-Type", &content_type));
+        }
+    }
+
+    for (name, key) in [
+        ("timeout", "timeout"),
+        ("verify", "insecure"),
+        ("cert", "cert"),
+        ("allow_redirects", "follow-redirects"),
+        ("follow_redirects", "follow-redirects"),
+        ("proxies", "proxy"),
+        ("proxy", "proxy"),
+    ] {
+        let Some(value) = arg(name) else {
+            continue;
+        };
+        match (name, value) {
+            ("verify", JsValue::Bool(false)) => metadata.push(metadata_node(key, "true")),
+            ("verify", JsValue::String(path)) => metadata.push(metadata_node("cacert", path)),
+            ("verify", _) => {}
+            (_, value) => {
+                if let Some(text) = value.to_text() {
+                    metadata.push(metadata_node(key, &text));
+                }
+            }
+        }
+    }
+    metadata.push(metadata_node("library", client.library));
+
+    let method = method.or_else(|| Some("GET".to_string()));
+
+    // 解析 URL query params
+    let query_params = url_str
+        .as_ref()
+        .and_then(|u| parse_utils::parse_query_params(u));
+    let query_tree = query_params
+        .as_deref()
+        .and_then(query_tree::build_query_tree);
+    let url_nodes = url_str
+        .as_ref()
+        .and_then(|u| parse_utils::parse_url_nodes(u));
+
+    ParseResult {
+        content_type: HttpContentType::Request,
+        method,
+        url: url_str,
+        url_nodes,
+        headers,
+        query_params,
+        query_tree,
+        body,
+        metadata: Some(metadata),
+        raw_text,
+        ..Default::default()
+    }
+}
+
+/// 收集可发起请求的对象：`requests`、`httpx` 模块本身，以及赋值或 with 语句创建的会话。
+fn collect_clients(input: &str, env: &HashMap<String, JsValue>) -> HashMap<String, Client> {
+    let mut clients: HashMap<String, Client> = HashMap::new();
+    for library in ["requests", "httpx"] {
+        clients.insert(
+            library.to_string(),
+            Client {
+                library,
+                defaults: Vec::new(),
+            },
+        );
+    }
+
+    let library_name = |name: &str| if name == "httpx" { "httpx" } else { "requests" };
+    let resolve_kwargs = |call: &PyCall| -> Vec<(String, JsValue)> {
+        call.kwargs
+            .iter()
+            .map(|(k, v)| (k.clone(), resolve(v, env, 0)))
+            .collect()
+    };
+
+    for caps in RE_CLIENT_ASSIGN.captures_iter(input) {
+        let end = caps.get(0).map_or(0, |m| m.end());
+        let call = py_literal::parse_call_arguments(&input[end..]);
+        clients.insert(
+            caps[1].to_string(),
+            Client {
+                library: library_name(&caps[2]),
+                defaults: resolve_kwargs(&call),
+            },
+        );
+    }
+    for caps in RE_CLIENT_WITH.captures_iter(input) {
+        let end = caps.get(0).map_or(0, |m| m.end());
+        let call = py_literal::parse_call_arguments(&input[end..]);
+        if let Some(alias) = RE_WITH_AS.captures(&input[end + call.consumed..]) {
+            clients.insert(
+                alias[1].to_string(),
+                Client {
+                    library: library_name(&caps[1]),
+                    defaults: resolve_kwargs(&call),
+                },
+            );
+        }
+    }
+
+    for caps in RE_HEADERS_UPDATE.captures_iter(input) {
+        let end = caps.get(0).map_or(0, |m| m.end());
+        let call = py_literal::parse_call_arguments(&input[end..]);
+        let Some(JsValue::Object(update)) = call.args.first().map(|v| resolve(v, env, 0)) else {
+            continue;
+        };
+        let Some(client) = clients.get_mut(&caps[1]) else {
+            continue;
+        };
+        match client.defaults.iter_mut().find(|(k, _)| k == "headers") {
+            Some((_, JsValue::Object(entries))) => entries.extend(update),
+            _ => client
+                .defaults
+                .push(("headers".to_string(), JsValue::Object(update))),
+        }
+    }
+
+    clients
+}
+
+/// 将按名引用的模块级变量替换为其值，并展开字典中的 `**name`。
+fn resolve(value: &JsValue, env: &HashMap<String, JsValue>, depth: usize) -> JsValue {
+    if depth > 8 {
+        return value.clone();
+    }
+    match value {
+        JsValue::Expr(expr) => {
+            if let Some(bound) = env.get(expr) {
+                return resolve(bound, env, depth + 1);
+            }
+            // 字符串拼接：`BASE + "/path"`
+            let joined = py_literal::split_concatenation(expr).and_then(|pieces| {
+                pieces
+                    .iter()
+                    .map(|piece| match resolve(piece, env, depth + 1) {
+                        JsValue::String(s) => Some(s),
+                        _ => None,
+                    })
+                    .collect::<Option<String>>()
+            });
+            match joined {
+                Some(s) => JsValue::String(s),
+                None => value.clone(),
+            }
+        }
+        JsValue::Array(items) => {
+            JsValue::Array(items.iter().map(|v| resolve(v, env, depth + 1)).collect())
+        }
+        JsValue::Object(entries) => {
+            let mut resolved: Vec<(String, JsValue)> = Vec::new();
+            for (key, item) in entries {
+                let item = resolve(item, env, depth + 1);
+                match (key.as_str(), item) {
+                    ("**", JsValue::Object(spread)) => {
+                        for (k, v) in spread {
+                            resolved.retain(|(existing, _)| *existing != k);
+                            resolved.push((k, v));
+                        }
+                    }
+                    ("**", _) => {}
+                    (_, item) => {
+                        resolved.retain(|(existing, _)| existing != key);
+                        resolved.push((key.clone(), item));
+                    }
+                }
+            }
+            JsValue::Object(resolved)
+        }
+        _ => value.clone(),
+    }
+}
+
+/// 按 `urlencode` 规则编码字典或 `[(key, value), ...]`；列表值展开为重复 key，None 被忽略。
+fn form_encode(value: &JsValue) -> Option<String> {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    let mut append = |key: &str, value: &JsValue| match value {
+        JsValue::Array(items) => {
+            for item in items {
+                if let Some(text) = item.to_text() {
+                    serializer.append_pair(key, &text);
+                }
+            }
+        }
+        other => {
+            if let Some(text) = other.to_text() {
+                serializer.append_pair(key, &text);
+            }
+        }
+    };
+    match value {
+        JsValue::Object(entries) => {
+            for (key, value) in entries {
+                append(key, value);
+            }
+        }
+        JsValue::Array(pairs) => {
+            for pair in pairs {
+                if let JsValue::Array(pair) = pair {
+                    if let [key, value] = pair.as_slice() {
+                        append(&key.to_text().unwrap_or_default(), value);
+                    }
+                }
+            }
+        }
+        other => return other.to_text(),
+    }
+    Some(serializer.finish())
+}
+
+/// 读取 `auth=` 的认证方式与凭据：`("user", "pass")`、`HTTPBasicAuth(...)`、`HTTPDigestAuth(...)`。
+fn auth_credentials(value: &JsValue) -> Option<(&'static str, String, String)> {
+    let (scheme, args) = match value {
+        JsValue::Array(items) => ("basic", items.clone()),
+        JsValue::Expr(expr) => {
+            let (callee, rest) = expr.split_once('(')?;
+            let scheme = match callee.rsplit('.').next()? {
+                "HTTPBasicAuth" | "BasicAuth" => "basic",
+                "HTTPDigestAuth" | "DigestAuth" => "digest",
+                _ => return None,
+            };
+            (scheme, py_literal::parse_call_arguments(rest).args)
+        }
+        _ => return None,
+    };
+    match args.as_slice() {
+        [user, password] => Some((scheme, user.to_text()?, password.to_text()?)),
+        _ => None,
+    }
+}
+
+/// `files=` 的各字段：`open(path)`、`(filename, content[, type])` 或直接给出的内容。
+fn file_parts(files: &JsValue) -> Vec<FormPart> {
+    let JsValue::Object(entries) = files else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .map(|(name, spec)| {
+            let mut part = FormPart {
+                name: name.clone(),
+                value: String::new(),
+                filename: Some(name.clone()),
+                content_type: None,
+            };
+            match spec {
+                JsValue::Array(items) => {
+                    part.filename = items.first().and_then(JsValue::to_text);
+                    if let Some(content) = items.get(1) {
+                        part.value = file_content(content);
+                    }
+                    part.content_type = items.get(2).and_then(JsValue::to_text);
+                }
+                JsValue::Expr(expr) => {
+                    if let Some(path) = opened_path(expr) {
+                        part.filename = path.rsplit(['/', '\\']).next().map(|f| f.to_string());
+                    }
+                }
+                other => part.value = other.to_text().unwrap_or_default(),
+            }
+            if part.content_type.is_none() {
+                if let Some(filename) = part.filename.as_deref().filter(|f| f.contains('.')) {
+                    part.content_type = Some(multipart::guess_content_type(filename).to_string());
+                }
+            }
+            part
+        })
+        .collect()
+}
+
+/// 文件内容：字符串原样使用，`open(...)` 等无法读取的表达式留空。
+fn file_content(content: &JsValue) -> String {
+    match content {
+        JsValue::Expr(_) => String::new(),
+        other => other.to_text().unwrap_or_default(),
+    }
+}
+
+/// 从 `open('path', 'rb')` 中取出路径。
+fn opened_path(expr: &str) -> Option<String> {
+    let rest = expr.strip_prefix("open(")?;
+    py_literal::parse_call_arguments(rest)
+        .args
+        .first()
+        .and_then(JsValue::to_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header<'a>(result: &'a ParseResult, name: &str) -> Option<&'a str> {
+        parse_utils::header_value(&result.headers, name)
+    }
+
+    #[test]
+    fn test_requests_post_with_kwargs() {
+        let input = r#"import requests
+
+response = requests.post(
+    "https://example.com/api/items?v=1",
+    headers={"Accept": "application/json", "X-Skip": None},
+    params={"page": 2, "tag": ["a", "b"]},
+    json={"name": "中文", "ok": True, "n": None},
+    cookies={"sid": "abc", "theme": "dark"},
+)
+print(response.json())"#;
+
+        let result = parse_python(input);
+
+        assert!(matches!(result.content_type, HttpContentType::Request));
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(
+            result.url.as_deref(),
+            Some("https://example.com/api/items?v=1&page=2&tag=a&tag=b")
+        );
+        assert_eq!(result.headers.len(), 3);
+        assert_eq!(header(&result, "accept"), Some("application/json"));
+        assert_eq!(header(&result, "cookie"), Some("sid=abc; theme=dark"));
+        assert_eq!(header(&result, "content-type"), Some("application/json"));
+        assert_eq!(
+            result.body.as_deref(),
+            Some(r#"{"name": "\u4e2d\u6587", "ok": true, "n": null}"#)
+        );
+        assert_eq!(result.query_params.unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_module_level_variables() {
+        let input = r#"import requests
+
+BASE = "https://example.com"
+headers = {
+    'user-agent': 'Mozilla/5.0',  # copied from browser
+    'authorization': 'Bearer ' + 'abc',
+}
+common = {**headers, 'accept': '*/*'}
+data = {'q': 'a b', 'lang': 'zh'}
+
+r = requests.post(BASE + '/search', headers=common, data=data)"#;
+
+        let result = parse_python(input);
+
+        assert_eq!(result.url.as_deref(), Some("https://example.com/search"));
+        assert_eq!(result.headers[1].value, "Bearer abc");
+        assert_eq!(result.headers[2].key, "accept");
+        assert_eq!(
+            header(&result, "content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(result.body.as_deref(), Some("q=a+b&lang=zh"));
+    }
+
+    #[test]
+    fn test_httpx_client_defaults() {
+        let input = r#"import httpx
+
+async with httpx.AsyncClient(base_url="https://api.example.com/v1/", headers={"x-app": "demo"}) as client:
+    resp = await client.put("/users/7", headers={"x-req": "1"}, json={"name": "中"}, timeout=5)"#;
+
+        let result = parse_python(input);
+
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+        assert_eq!(
+            result.url.as_deref(),
+            Some("https://api.example.com/v1/users/7")
+        );
+        assert_eq!(result.headers[0].key, "x-app");
+        assert_eq!(result.headers[1].key, "x-req");
+        assert_eq!(result.body.as_deref(), Some(r#"{"name":"中"}"#));
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata[0].key, "timeout");
+        assert_eq!(metadata[1].value, "httpx");
+    }
+
+    #[test]
+    fn test_session_headers_update_and_auth() {
+        let input = r#"import requests
+from requests.auth import HTTPDigestAuth
+
+s = requests.Session()
+s.headers.update({'X-Token': 'abc'})
+s.get('https://example.com/a', auth=('user', 'pass'), verify=False)"#;
+
+        let result = parse_python(input);
+
+        assert_eq!(header(&result, "x-token"), Some("abc"));
+        assert_eq!(header(&result, "authorization"), Some("Basic dXNlcjpwYXNz"));
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata[0].key, "insecure");
+
+        let digest =
+            parse_python("requests.get('https://example.com', auth=HTTPDigestAuth('u', 'p'))");
+        assert!(header(&digest, "authorization").is_none());
+        assert_eq!(digest.metadata.unwrap()[0].value, "u:p");
+    }
+
+    #[test]
+    fn test_files_build_multipart() {
+        let input = r#"requests.post(
+    "https://example.com/upload",
+    data={"title": "report"},
+    files={
+        "file": open("/tmp/report.pdf", "rb"),
+        "meta": ("meta.json", '{"a": 1}', "application/json"),
+    },
+)"#;
+
+        let result = parse_python(input);
+
+        let content_type = header(&result, "content-type").unwrap();
+        assert!(content_type.starts_with("multipart/form-data; boundary="));
+        let body = result.body.unwrap();
+        let parts = multipart::parse_multipart(&body, FORM_BOUNDARY).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].value, "report");
+        assert!(body.contains("filename=\"report.pdf\"\r\nContent-Type: application/pdf"));
+        assert!(body.contains("Content-Type: application/json\r\n\r\n{\"a\": 1}"));
+    }
+
+    #[test]
+    fn test_request_with_method_argument() {
+        let result =
+            parse_python("requests.request('patch', 'https://example.com/x', data='raw=1')");
+        assert_eq!(result.method.as_deref(), Some("PATCH"));
+        assert_eq!(result.body.as_deref(), Some("raw=1"));
+        assert!(header(&result, "content-type").is_none());
+    }
+
+    #[test]
+    fn test_no_request_call() {
+        let result = parse_python("import requests\nprint('hello')");
+        assert!(matches!(result.content_type, HttpContentType::Unknown));
+    }
+}
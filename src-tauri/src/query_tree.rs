@@ -0,0 +1,241 @@
+use serde_json::{Map, Value};
+
+use crate::models::ParseNode;
+
+/// 查询参数树的中间结构，叶子为原始字符串值，分支按首次出现顺序保存子项。
+enum QueryValue {
+    Leaf(String),
+    Branch(Vec<(String, QueryValue)>),
+}
+
+/// 将扁平的查询参数还原为嵌套结构（PHP / Rails / qs 风格）。
+/// 支持方括号（`filter[status][]=open`、`sort[0]=name`）、点号（`a.b.c=1`）和重复 key（`tag=a&tag=b`）。
+/// 没有任何嵌套或重复 key 时返回 None，此时扁平的 query_params 已足够。
+pub fn build_query_tree(params: &[ParseNode]) -> Option<Vec<ParseNode>> {
+    let mut structured = false;
+    let mut root: Vec<(String, QueryValue)> = Vec::new();
+
+    for param in params {
+        let segments = split_key(&param.key);
+        if segments.len() > 1 || root.iter().any(|(k, _)| *k == segments[0]) {
+            structured = true;
+        }
+        insert(&mut root, &segments, param.value.clone());
+    }
+
+    if !structured {
+        return None;
+    }
+    Some(root.into_iter().map(|(k, v)| to_node(k, v)).collect())
+}
+
+/// 拆分 key 路径：`filter[status][]` → `["filter", "status", ""]`，`a.b[c]` → `["a", "b", "c"]`。
+/// 方括号不成对或以 `[` 开头时按普通 key 处理。
+fn split_key(key: &str) -> Vec<String> {
+    let (base, mut rest) = match key.find('[') {
+        Some(0) => return vec![key.to_string()],
+        Some(pos) => (&key[..pos], &key[pos..]),
+        None => (key, ""),
+    };
+
+    let mut segments: Vec<String> = if base.split('.').all(|s| !s.is_empty()) {
+        base.split('.').map(|s| s.to_string()).collect()
+    } else {
+        vec![base.to_string()]
+    };
+
+    while !rest.is_empty() {
+        let Some(inner) = rest.strip_prefix('[') else {
+            return vec![key.to_string()];
+        };
+        let Some(end) = inner.find(']') else {
+            return vec![key.to_string()];
+        };
+        segments.push(inner[..end].to_string());
+        rest = &inner[end + 1..];
+    }
+    segments
+}
+
+fn insert(entries: &mut Vec<(String, QueryValue)>, segments: &[String], value: String) {
+    let key = if segments[0].is_empty() {
+        entries.len().to_string()
+    } else {
+        segments[0].clone()
+    };
+    let rest = &segments[1..];
+    let existing = entries.iter().position(|(k, _)| *k == key);
+
+    if rest.is_empty() {
+        match existing {
+            None => entries.push((key, QueryValue::Leaf(value))),
+            Some(i) => match &mut entries[i].1 {
+                QueryValue::Branch(children) => {
+                    let index = children.len().to_string();
+                    children.push((index, QueryValue::Leaf(value)));
+                }
+                leaf @ QueryValue::Leaf(_) => {
+                    // 重复 key：将已有的值与新值组成数组
+                    let old = std::mem::replace(leaf, QueryValue::Branch(Vec::new()));
+                    *leaf = QueryValue::Branch(vec![
+                        ("0".to_string(), old),
+                        ("1".to_string(), QueryValue::Leaf(value)),
+                    ]);
+                }
+            },
+        }
+        return;
+    }
+
+    let index = match existing {
+        Some(i) => i,
+        None => {
+            entries.push((key, QueryValue::Branch(Vec::new())));
+            entries.len() - 1
+        }
+    };
+    let slot = &mut entries[index].1;
+    if let QueryValue::Leaf(_) = slot {
+        let old = std::mem::replace(slot, QueryValue::Branch(Vec::new()));
+        *slot = QueryValue::Branch(vec![("0".to_string(), old)]);
+    }
+    if let QueryValue::Branch(children) = slot {
+        insert(children, rest, value);
+    }
+}
+
+/// 子项 key 依次为 `0..n` 时视为数组。
+fn is_array(children: &[(String, QueryValue)]) -> bool {
+    children
+        .iter()
+        .enumerate()
+        .all(|(i, (k, _))| *k == i.to_string())
+}
+
+fn to_json(value: &QueryValue) -> Value {
+    match value {
+        QueryValue::Leaf(s) => Value::String(s.clone()),
+        QueryValue::Branch(children) if is_array(children) => {
+            Value::Array(children.iter().map(|(_, v)| to_json(v)).collect())
+        }
+        QueryValue::Branch(children) => Value::Object(
+            children
+                .iter()
+                .map(|(k, v)| (k.clone(), to_json(v)))
+                .collect::<Map<String, Value>>(),
+        ),
+    }
+}
+
+/// 分支节点的 value 为子树的 JSON 表示，value_type 为 `object` / `array`，解码器只处理其叶子。
+fn to_node(key: String, value: QueryValue) -> ParseNode {
+    match value {
+        QueryValue::Leaf(s) => ParseNode {
+            key,
+            value: s,
+            ..Default::default()
+        },
+        branch @ QueryValue::Branch(_) => {
+            let json = to_json(&branch);
+            let value_type = if json.is_array() { "array" } else { "object" };
+            let QueryValue::Branch(children) = branch else {
+                unreachable!()
+            };
+            ParseNode {
+                key,
+                value: json.to_string(),
+                children: Some(children.into_iter().map(|(k, v)| to_node(k, v)).collect()),
+                value_type: Some(value_type.to_string()),
+                ..Default::default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(query: &str) -> Vec<ParseNode> {
+        url::form_urlencoded::parse(query.as_bytes())
+            .map(|(k, v)| ParseNode {
+                key: k.into_owned(),
+                value: v.into_owned(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bracket_notation() {
+        let tree =
+            build_query_tree(&params("filter[status][]=open&filter[owner]=me&sort[0]=name"))
+                .unwrap();
+        assert_eq!(tree.len(), 2);
+
+        let filter = &tree[0];
+        assert_eq!(filter.key, "filter");
+        assert_eq!(filter.value_type.as_deref(), Some("object"));
+        assert_eq!(filter.value, r#"{"owner":"me","status":["open"]}"#);
+        let children = filter.children.as_ref().unwrap();
+        assert_eq!(children[0].key, "status");
+        assert_eq!(children[0].value_type.as_deref(), Some("array"));
+        assert_eq!(children[0].children.as_ref().unwrap()[0].value, "open");
+        assert_eq!(children[1].key, "owner");
+        assert_eq!(children[1].value, "me");
+
+        let sort = &tree[1];
+        assert_eq!(sort.value_type.as_deref(), Some("array"));
+        assert_eq!(sort.children.as_ref().unwrap()[0].value, "name");
+    }
+
+    #[test]
+    fn test_encoded_brackets() {
+        let tree = build_query_tree(&params("user%5Bname%5D=bob&user%5Bage%5D=3")).unwrap();
+        assert_eq!(tree[0].key, "user");
+        assert_eq!(tree[0].children.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dotted_notation() {
+        let tree = build_query_tree(&params("a.b.c=1&a.b.d=2&a.e=3")).unwrap();
+        assert_eq!(tree.len(), 1);
+        let a = &tree[0];
+        assert_eq!(a.value, r#"{"b":{"c":"1","d":"2"},"e":"3"}"#);
+        let b = &a.children.as_ref().unwrap()[0];
+        assert_eq!(b.key, "b");
+        assert_eq!(b.children.as_ref().unwrap()[1].key, "d");
+    }
+
+    #[test]
+    fn test_repeated_keys() {
+        let tree = build_query_tree(&params("tag=a&tag=b&tag=c&q=x")).unwrap();
+        let tag = &tree[0];
+        assert_eq!(tag.value_type.as_deref(), Some("array"));
+        assert_eq!(tag.value, r#"["a","b","c"]"#);
+        assert_eq!(tree[1].key, "q");
+        assert!(tree[1].value_type.is_none());
+    }
+
+    #[test]
+    fn test_array_of_objects() {
+        let tree =
+            build_query_tree(&params("items[0][id]=1&items[0][qty]=2&items[1][id]=7")).unwrap();
+        assert_eq!(tree[0].value, r#"[{"id":"1","qty":"2"},{"id":"7"}]"#);
+    }
+
+    #[test]
+    fn test_flat_query_returns_none() {
+        assert!(build_query_tree(&params("a=1&b=2")).is_none());
+        assert!(build_query_tree(&[]).is_none());
+    }
+
+    #[test]
+    fn test_malformed_keys_kept_flat() {
+        assert_eq!(split_key("[a]"), vec!["[a]"]);
+        assert_eq!(split_key("a[b"), vec!["a[b"]);
+        assert_eq!(split_key("a[b]c"), vec!["a[b]c"]);
+        assert_eq!(split_key("a..b"), vec!["a..b"]);
+        assert_eq!(split_key(".a"), vec![".a"]);
+    }
+}
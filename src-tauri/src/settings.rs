@@ -0,0 +1,204 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::decode_rules::{self, DecodeRule};
+use crate::decoder::{DecoderConfig, DecoderRegistry};
+use crate::error::AppError;
+
+/// 用户设置（保存在应用数据目录的 settings.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// 解码器的启用状态、优先级与作用范围
+    pub decoders: DecoderConfig,
+    /// 自定义解码规则文件（TOML / JSON），未设置时使用应用数据目录下的 decode-rules.toml
+    pub rules_file: Option<String>,
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::InternalError(format!("Failed to get app data dir: {e}")))?;
+    Ok(dir.join("settings.json"))
+}
+
+/// 读取设置，文件不存在时返回默认值。
+fn load_settings(app: &AppHandle) -> Result<Settings, AppError> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+    let data = fs::read_to_string(&path)
+        .map_err(|e| AppError::InternalError(format!("Failed to read settings: {e}")))?;
+    serde_json::from_str(&data)
+        .map_err(|e| AppError::InternalError(format!("Failed to parse settings: {e}")))
+}
+
+/// 读取自定义解码规则，规则文件不存在时返回空列表。
+fn load_rules(app: &AppHandle, settings: &Settings) -> Result<Vec<DecodeRule>, AppError> {
+    let path = match &settings.rules_file {
+        Some(file) => PathBuf::from(file),
+        None => {
+            let dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| AppError::InternalError(format!("Failed to get app data dir: {e}")))?;
+            dir.join("decode-rules.toml")
+        }
+    };
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    decode_rules::load_rules(&path)
+}
+
+/// 已读取并校验的解码器设置与规则，启动时加载、`settings_save` 后刷新，
+/// 解析时不再读取磁盘。设置或规则文件有误时回退到默认值，错误作为警告附加到解析结果。
+#[derive(Default)]
+pub struct DecoderState {
+    loaded: RwLock<LoadedDecoders>,
+}
+
+#[derive(Default)]
+struct LoadedDecoders {
+    config: DecoderConfig,
+    rules: Vec<DecodeRule>,
+    warnings: Vec<String>,
+}
+
+impl DecoderState {
+    pub fn load(app: &AppHandle) -> Self {
+        let state = DecoderState::default();
+        state.refresh(app);
+        state
+    }
+
+    /// 重新读取 settings.json 与规则文件。
+    pub fn refresh(&self, app: &AppHandle) {
+        let mut warnings = Vec::new();
+        let settings = load_settings(app).unwrap_or_else(|e| {
+            warnings.push(format!("Using default decoder settings: {e}"));
+            Settings::default()
+        });
+        let rules = load_rules(app, &settings).unwrap_or_else(|e| {
+            warnings.push(format!("Custom decode rules ignored: {e}"));
+            Vec::new()
+        });
+        let config = checked_config(settings.decoders, &mut warnings);
+        let loaded = LoadedDecoders {
+            config,
+            rules,
+            warnings,
+        };
+        *self.loaded.write().unwrap_or_else(|e| e.into_inner()) = loaded;
+    }
+
+    /// 按缓存的设置（或调用方传入的配置）构建注册表，同时返回需要提示的警告。
+    pub fn registry(&self, config: Option<DecoderConfig>) -> (DecoderRegistry, Vec<String>) {
+        let loaded = self.loaded.read().unwrap_or_else(|e| e.into_inner());
+        let mut warnings = loaded.warnings.clone();
+        let mut config = config.unwrap_or_else(|| loaded.config.clone());
+        if let Err(e) = decode_rules::validate_rules(&config.rules) {
+            warnings.push(format!("Invalid decode rule skipped: {e}"));
+        }
+        config.rules.extend(loaded.rules.iter().cloned());
+        (DecoderRegistry::new(&config), warnings)
+    }
+}
+
+/// 校验 settings.json 中内联的解码规则，有误时整体丢弃并记录警告。
+fn checked_config(mut config: DecoderConfig, warnings: &mut Vec<String>) -> DecoderConfig {
+    if let Err(e) = decode_rules::validate_rules(&config.rules) {
+        warnings.push(format!("Decode rules in settings ignored: {e}"));
+        config.rules.clear();
+    }
+    config
+}
+
+/// 读取设置。与 `settings_save` 一样仅供后端调用，前端尚无设置页面。
+#[tauri::command]
+pub fn settings_get(app: AppHandle) -> Result<Settings, AppError> {
+    load_settings(&app)
+}
+
+/// 保存设置并刷新缓存；内联的解码规则与规则文件一样先经过校验。仅供后端调用。
+#[tauri::command]
+pub fn settings_save(
+    app: AppHandle,
+    state: State<'_, DecoderState>,
+    settings: Settings,
+) -> Result<(), AppError> {
+    decode_rules::validate_rules(&settings.decoders.rules).map_err(AppError::ParseError)?;
+    let path = settings_path(&app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::InternalError(format!("Failed to create dir: {e}")))?;
+    }
+    let data = serde_json::to_string_pretty(&settings)
+        .map_err(|e| AppError::InternalError(format!("Failed to serialize settings: {e}")))?;
+    fs::write(&path, data)
+        .map_err(|e| AppError::InternalError(format!("Failed to write settings: {e}")))?;
+    state.refresh(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_defaults_for_missing_fields() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert!(settings.decoders.decoders.is_empty());
+
+        let settings: Settings = serde_json::from_str(
+            r#"{"decoders":{"decoders":[{"id":"timestamp","excludeKeys":["orderId"]}]}}"#,
+        )
+        .unwrap();
+        let timestamp = &settings.decoders.decoders[0];
+        assert_eq!(timestamp.exclude_keys, ["orderId"]);
+        assert!(timestamp.enabled.is_none());
+        assert!(settings.rules_file.is_none());
+    }
+
+    #[test]
+    fn test_decoder_state_warnings() {
+        let state = DecoderState::default();
+        state.loaded.write().unwrap().warnings = vec!["Using default decoder settings".to_string()];
+
+        let (_, warnings) = state.registry(None);
+        assert_eq!(warnings, ["Using default decoder settings"]);
+
+        let config: DecoderConfig =
+            serde_json::from_str(r#"{"rules":[{"name":"r","key":"k","decoders":["r"]}]}"#).unwrap();
+        let (_, warnings) = state.registry(Some(config));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[1].contains("unknown decoder `r`"));
+    }
+
+    #[test]
+    fn test_inline_rules_are_validated() {
+        let settings: Settings = serde_json::from_str(
+            r#"{"decoders":{"rules":[{"name":"base64","key":"k","decoders":["json"]}]}}"#,
+        )
+        .unwrap();
+        let mut warnings = Vec::new();
+        let config = checked_config(settings.decoders, &mut warnings);
+        assert!(config.rules.is_empty());
+        assert_eq!(warnings.len(), 1);
+
+        let settings: Settings = serde_json::from_str(
+            r#"{"decoders":{"rules":[{"name":"sig","key":"k","decoders":["base64"]}]}}"#,
+        )
+        .unwrap();
+        let mut warnings = Vec::new();
+        let config = checked_config(settings.decoders, &mut warnings);
+        assert_eq!(config.rules.len(), 1);
+        assert!(warnings.is_empty());
+    }
+}
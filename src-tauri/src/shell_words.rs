@@ -0,0 +1,743 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// 命令行的转义风格：Chrome 的 "Copy as cURL" 分为 bash 与 cmd 两种，
+/// PowerShell 中粘贴的 `curl.exe` 使用反引号续行。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShellStyle {
+    Posix,
+    Cmd,
+    PowerShell,
+}
+
+impl ShellStyle {
+    pub fn name(self) -> &'static str {
+        match self {
+            ShellStyle::Posix => "posix",
+            ShellStyle::Cmd => "cmd",
+            ShellStyle::PowerShell => "powershell",
+        }
+    }
+}
+
+/// 根据转义风格推断 shell：`^"` 或行尾 `^` 为 cmd，行尾反引号或 `curl.exe` 开头为 PowerShell。
+pub fn detect_shell_style(text: &str) -> ShellStyle {
+    let trimmed = text.trim();
+    let line_ends_with = |c: char| trimmed.lines().any(|line| line.trim_end().ends_with(c));
+
+    if has_cmd_caret(trimmed) {
+        ShellStyle::Cmd
+    } else if line_ends_with('`') || starts_with_ignore_case(trimmed, "curl.exe") {
+        ShellStyle::PowerShell
+    } else {
+        ShellStyle::Posix
+    }
+}
+
+/// 在 bash 引号之外查找 `^"` 或行尾的 `^`；引号内的（如 JSON body 中的 `^"`）不算。
+fn has_cmd_caret(text: &str) -> bool {
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '^' => {
+                if matches!(chars.peek(), Some('"' | '\n' | '\r')) {
+                    return true;
+                }
+                // 行尾空白后的 `^` 同样视为续行
+                let mut rest = chars.clone();
+                while rest.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+                if matches!(rest.peek(), None | Some('\n' | '\r')) {
+                    return true;
+                }
+            }
+            '\'' => {
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                skip_quoted(&mut chars, '\'');
+            }
+            '"' => skip_quoted(&mut chars, '"'),
+            '\\' => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// 跳过到未转义的 `quote` 为止（反斜杠转义下一个字符）。
+fn skip_quoted(chars: &mut Peekable<Chars>, quote: char) {
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == quote {
+            break;
+        }
+    }
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+}
+
+/// 按指定风格将命令行拆分为参数。
+pub fn tokenize(input: &str, style: ShellStyle) -> Vec<String> {
+    match style {
+        ShellStyle::Posix => shell_tokenize(&merge_continuation_lines(input)),
+        ShellStyle::Cmd => cmd_tokenize(input),
+        ShellStyle::PowerShell => powershell_tokenize(input),
+    }
+}
+
+// ── POSIX shell ──
+
+/// 合并反斜杠续行（`\` + 换行）为单行。
+fn merge_continuation_lines(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.peek() {
+                Some('\n') => {
+                    chars.next();
+                    // 跳过续行后的前导空白
+                    while let Some(&ws) = chars.peek() {
+                        if ws == ' ' || ws == '\t' {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                Some('\r') => {
+                    chars.next();
+                    if chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    while let Some(&ws) = chars.peek() {
+                        if ws == ' ' || ws == '\t' {
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                _ => {
+                    result.push(ch);
+                }
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+/// Shell 风格 token 化。
+/// 按空格分割，但尊重单引号、双引号和 `$'...'` 内的内容。
+fn shell_tokenize(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    // 引号可以产生空参数（`-d ''`），因此单独记录当前是否处于一个 token 中
+    let mut in_token = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            ' ' | '\t' | '\n' | '\r' => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+                chars.next();
+            }
+            '\'' => {
+                // 单引号：原样保留内容，直到下一个 '
+                chars.next();
+                in_token = true;
+                while let Some(&c) = chars.peek() {
+                    if c == '\'' {
+                        chars.next();
+                        break;
+                    }
+                    current.push(c);
+                    chars.next();
+                }
+            }
+            '"' => {
+                // 双引号：处理转义
+                chars.next();
+                in_token = true;
+                while let Some(&c) = chars.peek() {
+                    if c == '"' {
+                        chars.next();
+                        break;
+                    }
+                    if c == '\\' {
+                        chars.next();
+                        if let Some(&escaped) = chars.peek() {
+                            match escaped {
+                                '"' | '\\' | '$' | '`' => {
+                                    current.push(escaped);
+                                    chars.next();
+                                }
+                                'n' => {
+                                    current.push('\n');
+                                    chars.next();
+                                }
+                                't' => {
+                                    current.push('\t');
+                                    chars.next();
+                                }
+                                _ => {
+                                    current.push('\\');
+                                    current.push(escaped);
+                                    chars.next();
+                                }
+                            }
+                        }
+                    } else {
+                        current.push(c);
+                        chars.next();
+                    }
+                }
+            }
+            '$' => {
+                chars.next();
+                in_token = true;
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                    current.push_str(&read_ansi_c_quoted(&mut chars));
+                } else {
+                    current.push('$');
+                }
+            }
+            '\\' => {
+                // 引号外的反斜杠转义下一个字符
+                chars.next();
+                in_token = true;
+                match chars.next() {
+                    Some(escaped) => current.push(escaped),
+                    None => current.push('\\'),
+                }
+            }
+            _ => {
+                in_token = true;
+                current.push(ch);
+                chars.next();
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// 读取 `$'...'` 的内容（开头的 `$'` 已消费），按 bash 的 ANSI-C 规则处理转义：
+/// `\a \b \e \E \f \n \r \t \v \\ \' \" \?`、八进制 `\nnn`、`\xHH`、`\uHHHH`、`\UHHHHHHHH` 与 `\cX`。
+/// `\xHH` 与八进制产生的是字节，连续字节按 UTF-8 组合（如 `\xe4\xb8\xad` → `中`）。
+fn read_ansi_c_quoted(chars: &mut Peekable<Chars>) -> String {
+    let mut bytes: Vec<u8> = Vec::new();
+    let push_char = |bytes: &mut Vec<u8>, c: char| {
+        let mut buf = [0u8; 4];
+        bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => break,
+            '\\' => {
+                let Some(escaped) = chars.next() else {
+                    bytes.push(b'\\');
+                    break;
+                };
+                match escaped {
+                    'a' => bytes.push(0x07),
+                    'b' => bytes.push(0x08),
+                    'e' | 'E' => bytes.push(0x1b),
+                    'f' => bytes.push(0x0c),
+                    'n' => bytes.push(b'\n'),
+                    'r' => bytes.push(b'\r'),
+                    't' => bytes.push(b'\t'),
+                    'v' => bytes.push(0x0b),
+                    '\\' | '\'' | '"' | '?' => push_char(&mut bytes, escaped),
+                    '0'..='7' => {
+                        let mut value = escaped.to_digit(8).unwrap_or(0);
+                        for _ in 0..2 {
+                            match chars.peek().and_then(|d| d.to_digit(8)) {
+                                Some(digit) => {
+                                    value = value * 8 + digit;
+                                    chars.next();
+                                }
+                                None => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    'x' => match read_hex_digits(chars, 2) {
+                        Some(value) => bytes.push(value as u8),
+                        None => bytes.extend_from_slice(b"\\x"),
+                    },
+                    'u' | 'U' => {
+                        let max = if escaped == 'u' { 4 } else { 8 };
+                        match read_hex_digits(chars, max).and_then(char::from_u32) {
+                            Some(decoded) => push_char(&mut bytes, decoded),
+                            None => {
+                                bytes.push(b'\\');
+                                push_char(&mut bytes, escaped);
+                            }
+                        }
+                    }
+                    'c' => match chars.next() {
+                        // 控制字符：`\cA` → 0x01，`\c?` → 0x7f
+                        Some('?') => bytes.push(0x7f),
+                        Some(ctrl) => bytes.push((ctrl.to_ascii_uppercase() as u8) & 0x1f),
+                        None => bytes.extend_from_slice(b"\\c"),
+                    },
+                    other => {
+                        bytes.push(b'\\');
+                        push_char(&mut bytes, other);
+                    }
+                }
+            }
+            _ => push_char(&mut bytes, c),
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// 读取至多 `max` 个十六进制数字，一个都没有时返回 None。
+fn read_hex_digits(chars: &mut Peekable<Chars>, max: usize) -> Option<u32> {
+    let mut value: u32 = 0;
+    let mut count = 0;
+    while count < max {
+        match chars.peek().and_then(|d| d.to_digit(16)) {
+            Some(digit) => {
+                value = value.wrapping_mul(16).wrapping_add(digit);
+                chars.next();
+                count += 1;
+            }
+            None => break,
+        }
+    }
+    (count > 0).then_some(value)
+}
+
+// ── Windows cmd ──
+
+/// cmd 风格 token 化：先按 cmd 规则去掉 `^` 转义（`^` + 换行为续行，续行后的首个字符按字面处理，
+/// 因此 Chrome 输出的 `^` + 两个换行表示字符串中的换行），再按 MSVCRT 规则拆分参数。
+/// cmd 自身的双引号区间内 `^` 不是转义符。
+fn cmd_tokenize(s: &str) -> Vec<String> {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut in_quote = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                in_quote = !in_quote;
+                unescaped.push(ch);
+            }
+            '^' if !in_quote => match chars.next() {
+                Some(newline @ ('\r' | '\n')) => {
+                    if newline == '\r' && chars.peek() == Some(&'\n') {
+                        chars.next();
+                    }
+                    if let Some(literal) = chars.next() {
+                        unescaped.push(literal);
+                    }
+                }
+                Some(literal) => unescaped.push(literal),
+                None => {}
+            },
+            _ => unescaped.push(ch),
+        }
+    }
+
+    msvcrt_split(&unescaped)
+}
+
+/// MSVCRT 命令行拆分：空白分隔参数，双引号切换引用状态，引号内 `""` 为字面引号；
+/// `\` 只在双引号前有转义作用（2n 个 → n 个且引号生效，2n+1 个 → n 个加字面引号）。
+fn msvcrt_split(s: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut in_quote = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ' ' | '\t' | '\r' | '\n' if !in_quote => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\\' => {
+                let mut count = 1;
+                while chars.peek() == Some(&'\\') {
+                    chars.next();
+                    count += 1;
+                }
+                in_arg = true;
+                if chars.peek() == Some(&'"') {
+                    current.push_str(&"\\".repeat(count / 2));
+                    if count % 2 == 1 {
+                        chars.next();
+                        current.push('"');
+                    }
+                } else {
+                    current.push_str(&"\\".repeat(count));
+                }
+            }
+            '"' => {
+                in_arg = true;
+                if in_quote && chars.peek() == Some(&'"') {
+                    chars.next();
+                    current.push('"');
+                } else {
+                    in_quote = !in_quote;
+                }
+            }
+            _ => {
+                in_arg = true;
+                current.push(ch);
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+// ── PowerShell ──
+
+/// PowerShell 风格 token 化：反引号为转义符（行尾反引号为续行）；单引号内容原样保留，`''` 为字面单引号；
+/// 双引号内支持 `` `n `` 等转义与 `""`。弯引号与直引号等价。
+fn powershell_tokenize(s: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ' ' | '\t' | '\r' | '\n' => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            '`' => match chars.next() {
+                // 续行
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some('\r') | Some('\n') => {}
+                Some(escaped) => {
+                    current.push(escaped);
+                    in_token = true;
+                }
+                None => {}
+            },
+            c if is_ps_single_quote(c) => {
+                in_token = true;
+                while let Some(c) = chars.next() {
+                    if is_ps_single_quote(c) {
+                        if chars.peek().is_some_and(|&n| is_ps_single_quote(n)) {
+                            chars.next();
+                            current.push('\'');
+                        } else {
+                            break;
+                        }
+                    } else {
+                        current.push(c);
+                    }
+                }
+            }
+            c if is_ps_double_quote(c) => {
+                in_token = true;
+                while let Some(c) = chars.next() {
+                    if is_ps_double_quote(c) {
+                        if chars.peek().is_some_and(|&n| is_ps_double_quote(n)) {
+                            chars.next();
+                            current.push('"');
+                        } else {
+                            break;
+                        }
+                    } else if c == '`' {
+                        if let Some(escaped) = chars.next() {
+                            push_ps_escape(&mut current, escaped, &mut chars);
+                        }
+                    } else {
+                        current.push(c);
+                    }
+                }
+            }
+            _ => {
+                in_token = true;
+                current.push(ch);
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// 读取以引号开头的 PowerShell 字符串字面量（规则同 `powershell_tokenize`），
+/// 返回内容与消耗的字节数；不以引号开头时返回 None。供 PowerShell 脚本解析使用。
+pub fn read_powershell_string(input: &str) -> Option<(String, usize)> {
+    let mut chars = input.chars().peekable();
+    let quote = chars.next()?;
+    let single = is_ps_single_quote(quote);
+    if !single && !is_ps_double_quote(quote) {
+        return None;
+    }
+    let is_quote: fn(char) -> bool = if single {
+        is_ps_single_quote
+    } else {
+        is_ps_double_quote
+    };
+
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        if is_quote(c) {
+            if chars.peek().is_some_and(|&n| is_quote(n)) {
+                chars.next();
+                out.push(if single { '\'' } else { '"' });
+            } else {
+                break;
+            }
+        } else if c == '`' && !single {
+            if let Some(escaped) = chars.next() {
+                push_ps_escape(&mut out, escaped, &mut chars);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    let remaining: usize = chars.map(char::len_utf8).sum();
+    Some((out, input.len() - remaining))
+}
+
+fn is_ps_single_quote(c: char) -> bool {
+    matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}')
+}
+
+fn is_ps_double_quote(c: char) -> bool {
+    matches!(c, '"' | '\u{201C}' | '\u{201D}' | '\u{201E}')
+}
+
+/// 双引号内的反引号转义（`` `0 `a `b `e `f `n `r `t `v `u{XXXX} ``），其余字符按字面处理。
+fn push_ps_escape(out: &mut String, escaped: char, chars: &mut Peekable<Chars>) {
+    let mapped = match escaped {
+        '0' => '\0',
+        'a' => '\u{7}',
+        'b' => '\u{8}',
+        'e' => '\u{1B}',
+        'f' => '\u{C}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'v' => '\u{B}',
+        'u' if chars.peek() == Some(&'{') => {
+            chars.next();
+            let hex: String = chars.by_ref().take_while(|c| *c != '}').collect();
+            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                Some(c) => c,
+                None => {
+                    out.push_str(&format!("u{{{hex}}}"));
+                    return;
+                }
+            }
+        }
+        other => other,
+    };
+    out.push(mapped);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_shell_style() {
+        assert_eq!(
+            detect_shell_style("curl 'https://example.com' \\\n  -H 'a: b'"),
+            ShellStyle::Posix
+        );
+        assert_eq!(
+            detect_shell_style("curl ^\"https://example.com/^\" ^\n  -H ^\"a: b^\""),
+            ShellStyle::Cmd
+        );
+        assert_eq!(
+            detect_shell_style("curl.exe 'https://example.com' `\n  -H 'a: b'"),
+            ShellStyle::PowerShell
+        );
+        assert_eq!(
+            detect_shell_style("curl.exe https://example.com"),
+            ShellStyle::PowerShell
+        );
+        // bash 引号内的 `^"` 与行尾 `^` 属于 body 内容
+        assert_eq!(
+            detect_shell_style(
+                r#"curl 'https://example.com' --data-raw '{"re":"a^","x":"b^
+"}'"#
+            ),
+            ShellStyle::Posix
+        );
+        assert_eq!(
+            detect_shell_style("curl \"https://example.com/?q=^\" -d \"a=1\""),
+            ShellStyle::Posix
+        );
+        assert_eq!(
+            detect_shell_style("curl \"https://example.com\" ^\n  -H \"a: b\""),
+            ShellStyle::Cmd
+        );
+    }
+
+    #[test]
+    fn test_posix_tokenize() {
+        let tokens = tokenize("'a b' \"c\\\"d\" \\\n  e", ShellStyle::Posix);
+        assert_eq!(tokens, vec!["a b", "c\"d", "e"]);
+    }
+
+    #[test]
+    fn test_posix_empty_and_adjacent_segments() {
+        let tokens = tokenize("-d '' -H 'x: '\"$v\"'!' a\\ b", ShellStyle::Posix);
+        assert_eq!(tokens, vec!["-d", "", "-H", "x: $v!", "a b"]);
+    }
+
+    #[test]
+    fn test_ansi_c_escapes() {
+        let tokens = tokenize(
+            r#"$'{"a":"\u4e2d"}\r\n' $'it\'s' $'\x41\102\x43' $'\xe4\xb8\xad' $'\t\a\e\cA\?\\' $'\U0001F600'"#,
+            ShellStyle::Posix,
+        );
+        assert_eq!(
+            tokens,
+            vec![
+                "{\"a\":\"中\"}\r\n",
+                "it's",
+                "ABC",
+                "中",
+                "\t\u{7}\u{1b}\u{1}?\\",
+                "😀",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ansi_c_concatenated_with_quotes() {
+        let tokens = tokenize(r#"$'line1\n'"line2"'!'$'\x'"#, ShellStyle::Posix);
+        assert_eq!(tokens, vec!["line1\nline2!\\x"]);
+    }
+
+    #[test]
+    fn test_ansi_c_invalid_utf8_bytes() {
+        let tokens = tokenize(r"$'\x89PNG'", ShellStyle::Posix);
+        assert_eq!(tokens, vec!["\u{FFFD}PNG"]);
+    }
+
+    #[test]
+    fn test_cmd_chrome_escapes() {
+        let input = "^\"https://example.com/api?a=1^&b=2^\" ^\n  -H ^\"accept: */*^\" ^\n  --data-raw ^\"^{^\\^\"name^\\^\":^\\^\"a b^\\^\"^}^\"";
+        let tokens = tokenize(input, ShellStyle::Cmd);
+        assert_eq!(
+            tokens,
+            vec![
+                "https://example.com/api?a=1&b=2",
+                "-H",
+                "accept: */*",
+                "--data-raw",
+                "{\"name\":\"a b\"}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cmd_newline_and_percent() {
+        // Chrome 将换行编码为 `^` + 两个换行，`%` 后接字母时插入 `^`
+        let input = "--data-raw ^\"line1^\n\nline2 100%^done^\"";
+        let tokens = tokenize(input, ShellStyle::Cmd);
+        assert_eq!(tokens, vec!["--data-raw", "line1\nline2 100%done"]);
+    }
+
+    #[test]
+    fn test_cmd_plain_quotes() {
+        let tokens = tokenize("\"https://x/?a=1^&b\" -H \"X: \"\"q\"\"\"", ShellStyle::Cmd);
+        assert_eq!(tokens, vec!["https://x/?a=1^&b", "-H", "X: \"q\""]);
+    }
+
+    #[test]
+    fn test_msvcrt_backslashes() {
+        assert_eq!(
+            msvcrt_split(r#"a\\b "c\\" d\"e"#),
+            vec![r"a\\b", "c\\", "d\"e"]
+        );
+    }
+
+    #[test]
+    fn test_powershell_tokenize() {
+        let input = "'https://example.com/' `\n  -H 'accept: */*' `\r\n  -H \"x-note: it''s `\"ok`\"\" `\n  --data-raw '{\"a\":\"it''s\"}'";
+        let tokens = tokenize(input, ShellStyle::PowerShell);
+        assert_eq!(
+            tokens,
+            vec![
+                "https://example.com/",
+                "-H",
+                "accept: */*",
+                "-H",
+                "x-note: it''s \"ok\"",
+                "--data-raw",
+                "{\"a\":\"it's\"}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_powershell_double_quote_escapes() {
+        let tokens = tokenize(
+            "\"a`tb`n`u{4E2D}\" \u{201C}smart\u{201D}",
+            ShellStyle::PowerShell,
+        );
+        assert_eq!(tokens, vec!["a\tb\n中", "smart"]);
+    }
+
+    #[test]
+    fn test_read_powershell_string() {
+        assert_eq!(
+            read_powershell_string("\"a`\"b\"\"c`n\" rest"),
+            Some(("a\"b\"c\n".to_string(), 11))
+        );
+        assert_eq!(
+            read_powershell_string("'it''s `n' -x"),
+            Some(("it's `n".to_string(), 10))
+        );
+        assert_eq!(read_powershell_string("plain"), None);
+    }
+
+    #[test]
+    fn test_powershell_empty_argument() {
+        let tokens = tokenize("-d '' -v", ShellStyle::PowerShell);
+        assert_eq!(tokens, vec!["-d", "", "-v"]);
+    }
+}
@@ -0,0 +1,314 @@
+use base64::engine::general_purpose;
+use base64::Engine;
+
+use crate::models::{HttpContentType, ParseNode, ParseResult};
+use crate::parse_utils::{self, empty_result, header_node, metadata_node};
+use crate::query_tree;
+use crate::shell_words::{self, ShellStyle};
+
+/// 需要参数的短选项（可与参数连写，如 `-Ofile`）
+const SHORT_VALUE_FLAGS: &[char] = &[
+    'O', 'o', 'a', 'U', 'T', 't', 'P', 'Q', 'e', 'D', 'i', 'w', 'l', 'A', 'R', 'I', 'X', 'B',
+];
+
+/// 需要参数的长选项（可写作 `--flag value` 或 `--flag=value`）
+const LONG_VALUE_FLAGS: &[&str] = &[
+    "--header",
+    "--method",
+    "--post-data",
+    "--post-file",
+    "--body-data",
+    "--body-file",
+    "--user-agent",
+    "--referer",
+    "--user",
+    "--password",
+    "--http-user",
+    "--http-password",
+    "--load-cookies",
+    "--save-cookies",
+    "--output-document",
+    "--output-file",
+    "--append-output",
+    "--directory-prefix",
+    "--timeout",
+    "--connect-timeout",
+    "--read-timeout",
+    "--dns-timeout",
+    "--tries",
+    "--wait",
+    "--max-redirect",
+    "--execute",
+    "--ca-certificate",
+    "--certificate",
+    "--private-key",
+    "--bind-address",
+    "--limit-rate",
+    "--compression",
+    "--input-file",
+    "--base",
+    "--accept",
+    "--reject",
+    "--level",
+];
+
+/// 不影响请求本身、但值得保留的选项：（flag, metadata key, 固定值）
+const TRANSPORT_OPTIONS: &[(&str, &str, Option<&str>)] = &[
+    ("--no-check-certificate", "insecure", Some("true")),
+    ("--ca-certificate", "cacert", None),
+    ("--certificate", "cert", None),
+    ("--private-key", "key", None),
+    ("-T", "timeout", None),
+    ("--timeout", "timeout", None),
+    ("--connect-timeout", "connect-timeout", None),
+    ("--read-timeout", "read-timeout", None),
+    ("-t", "tries", None),
+    ("--tries", "tries", None),
+    ("--max-redirect", "max-redirect", None),
+    ("--compression", "compression", None),
+    ("--load-cookies", "cookie-file", None),
+    ("-O", "output", None),
+    ("--output-document", "output", None),
+    ("--bind-address", "interface", None),
+    ("-4", "ip-version", Some("IPv4")),
+    ("--inet4-only", "ip-version", Some("IPv4")),
+    ("-6", "ip-version", Some("IPv6")),
+    ("--inet6-only", "ip-version", Some("IPv6")),
+];
+
+/// 解析 wget 命令：`--header`、`--method`、`--post-data` / `--body-data`、
+/// `--user` / `--password`、`--user-agent`、`--referer` 以及常见的传输选项。
+pub fn parse_wget(input: &str) -> ParseResult {
+    let raw_text = input.to_string();
+    let trimmed = input.trim();
+
+    let shell = shell_words::detect_shell_style(trimmed);
+    let tokens: Vec<String> = shell_words::tokenize(trimmed, shell)
+        .into_iter()
+        .skip(1)
+        .collect();
+
+    let mut method: Option<String> = None;
+    let mut url_str: Option<String> = None;
+    let mut headers: Vec<ParseNode> = Vec::new();
+    let mut metadata: Vec<ParseNode> = Vec::new();
+    let mut body: Option<String> = None;
+    let mut user: Option<String> = None;
+    let mut password: Option<String> = None;
+    let mut user_agent: Option<String> = None;
+    let mut referer: Option<String> = None;
+    let mut post = false;
+
+    if shell != ShellStyle::Posix {
+        metadata.push(metadata_node("shell", shell.name()));
+    }
+
+    for (flag, value) in normalize_options(&tokens) {
+        let value = value.unwrap_or_default();
+        match flag.as_str() {
+            "" => {
+                if url_str.is_none() {
+                    url_str = Some(value);
+                }
+            }
+            "--header" => {
+                if let Some((key, val)) = value.split_once(':') {
+                    headers.push(header_node(key.trim(), val.trim()));
+                }
+            }
+            "--method" => method = Some(value.to_uppercase()),
+            "--post-data" => {
+                body = Some(value);
+                post = true;
+            }
+            "--body-data" => body = Some(value),
+            "--post-file" | "--body-file" => {
+                // 文件内容无法读取，只记录路径
+                post |= flag == "--post-file";
+                metadata.push(metadata_node("upload-file", &value));
+            }
+            "--user" | "--http-user" => user = Some(value),
+            "--password" | "--http-password" => password = Some(value),
+            "-U" | "--user-agent" => user_agent = Some(value),
+            "--referer" => referer = Some(value),
+            _ => {
+                if let Some((_, key, fixed)) = TRANSPORT_OPTIONS.iter().find(|(f, _, _)| *f == flag)
+                {
+                    let value = fixed.unwrap_or(if value.is_empty() { "true" } else { &value });
+                    metadata.push(metadata_node(key, value));
+                }
+            }
+        }
+    }
+
+    // wget 不带 scheme 时默认为 http
+    let url_str = url_str.map(|u| {
+        if u.contains("://") {
+            u
+        } else {
+            format!("http://{u}")
+        }
+    });
+    let Some(url_str) = url_str else {
+        return empty_result(raw_text);
+    };
+
+    let has_header = |headers: &[ParseNode], name: &str| {
+        headers.iter().any(|h| h.key.eq_ignore_ascii_case(name))
+    };
+    if let Some(user) = &user {
+        if !has_header(&headers, "authorization") {
+            let credentials = format!("{user}:{}", password.as_deref().unwrap_or(""));
+            let encoded = general_purpose::STANDARD.encode(credentials);
+            headers.push(header_node("Authorization", &format!("Basic {encoded}")));
+        }
+    }
+    if let Some(agent) = &user_agent {
+        if !has_header(&headers, "user-agent") {
+            headers.push(header_node("User-Agent", agent));
+        }
+    }
+    if let Some(referer) = &referer {
+        if !has_header(&headers, "referer") {
+            headers.push(header_node("Referer", referer));
+        }
+    }
+    // --post-data 未指定类型时，wget 使用表单编码
+    if post && body.is_some() && !has_header(&headers, "content-type") {
+        headers.push(header_node(
+            "Content-Type",
+            "application/x-www-form-urlencoded",
+        ));
+    }
+
+    let method = method.or_else(|| Some(if post { "POST" } else { "GET" }.to_string()));
+
+    // 解析 URL query params
+    let query_params = parse_utils::parse_query_params(&url_str);
+    let query_tree = query_params
+        .as_deref()
+        .and_then(query_tree::build_query_tree);
+    let url_nodes = parse_utils::parse_url_nodes(&url_str);
+
+    ParseResult {
+        content_type: HttpContentType::Request,
+        method,
+        url: Some(url_str),
+        url_nodes,
+        headers,
+        query_params,
+        query_tree,
+        body,
+        metadata: if metadata.is_empty() {
+            None
+        } else {
+            Some(metadata)
+        },
+        raw_text,
+        ..Default::default()
+    }
+}
+
+/// 将 token 规范化为（flag, 参数）列表，URL 等非选项参数的 flag 为空字符串。
+fn normalize_options(tokens: &[String]) -> Vec<(String, Option<String>)> {
+    let mut options = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let tok = &tokens[i];
+        i += 1;
+
+        if tok == "--" {
+            for rest in &tokens[i..] {
+                options.push((String::new(), Some(rest.clone())));
+            }
+            break;
+        }
+
+        if tok.starts_with("--") {
+            if let Some((name, value)) = tok.split_once('=') {
+                options.push((name.to_string(), Some(value.to_string())));
+            } else if LONG_VALUE_FLAGS.contains(&tok.as_str()) {
+                options.push((tok.clone(), tokens.get(i).cloned()));
+                i += 1;
+            } else {
+                options.push((tok.clone(), None));
+            }
+            continue;
+        }
+
+        if tok.len() > 1 && tok.starts_with('-') {
+            let flags = &tok[1..];
+            for (pos, c) in flags.char_indices() {
+                let flag = format!("-{c}");
+                if SHORT_VALUE_FLAGS.contains(&c) {
+                    let attached = &flags[pos + c.len_utf8()..];
+                    let value = if attached.is_empty() {
+                        i += 1;
+                        tokens.get(i - 1).cloned()
+                    } else {
+                        Some(attached.to_string())
+                    };
+                    options.push((flag, value));
+                    break;
+                }
+                options.push((flag, None));
+            }
+            continue;
+        }
+
+        options.push((String::new(), Some(tok.clone())));
+    }
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_data_with_headers() {
+        let input = r#"wget --header='Accept: application/json' --header "X-Token: abc" \
+  --post-data='name=foo&age=3' -qO- https://example.com/api?v=1"#;
+        let result = parse_wget(input);
+
+        assert_eq!(result.method.as_deref(), Some("POST"));
+        assert_eq!(result.url.as_deref(), Some("https://example.com/api?v=1"));
+        assert_eq!(result.headers.len(), 3);
+        assert_eq!(result.headers[1].value, "abc");
+        assert_eq!(result.headers[2].value, "application/x-www-form-urlencoded");
+        assert_eq!(result.body.as_deref(), Some("name=foo&age=3"));
+        assert_eq!(result.metadata.unwrap()[0].value, "-");
+    }
+
+    #[test]
+    fn test_method_and_body_data() {
+        let input = "wget --method=PUT --body-data='{\"a\":1}' --header='Content-Type: application/json' example.com/items/1";
+        let result = parse_wget(input);
+
+        assert_eq!(result.method.as_deref(), Some("PUT"));
+        assert_eq!(result.url.as_deref(), Some("http://example.com/items/1"));
+        assert_eq!(result.headers.len(), 1);
+        assert_eq!(result.body.as_deref(), Some(r#"{"a":1}"#));
+    }
+
+    #[test]
+    fn test_auth_agent_and_transport() {
+        let input = "wget --user=alice --password=secret -U 'Bot/1.0' --referer=https://a.example/ --no-check-certificate -T 10 https://example.com/file.zip";
+        let result = parse_wget(input);
+
+        assert_eq!(result.method.as_deref(), Some("GET"));
+        assert_eq!(result.headers[0].value, "Basic YWxpY2U6c2VjcmV0");
+        assert_eq!(result.headers[1].value, "Bot/1.0");
+        assert_eq!(result.headers[2].key, "Referer");
+        let metadata = result.metadata.unwrap();
+        assert_eq!(metadata[0].key, "insecure");
+        assert_eq!(metadata[1].key, "timeout");
+        assert_eq!(metadata[1].value, "10");
+    }
+
+    #[test]
+    fn test_missing_url() {
+        let result = parse_wget("wget -q");
+        assert!(matches!(result.content_type, HttpContentType::Unknown));
+    }
+}
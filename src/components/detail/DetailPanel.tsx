@@ -92,6 +92,11 @@ export function DetailPanel() {
               {selectedPath.join(" > ")}
             </p>
           )}
+          {selectedNode.provenance && selectedNode.provenance.length > 0 && (
+            <p className="text-xs text-muted-foreground mt-1 font-mono">
+              {selectedNode.provenance.join(" → ")}
+            </p>
+          )}
         </div>
         <Button
           variant="ghost"
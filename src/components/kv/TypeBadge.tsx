@@ -26,6 +26,10 @@ const TYPE_STYLES: Record<string, { label: string; className: string }> = {
     label: "ENC",
     className: "text-[var(--type-encoded)] bg-[var(--type-encoded-bg)]",
   },
+  custom: {
+    label: "RULE",
+    className: "text-[var(--type-encoded)] bg-[var(--type-encoded-bg)]",
+  },
 };
 
 interface TypeBadgeProps {
@@ -29,6 +29,15 @@ export function StatusBar() {
         <span>共 {itemCount} 个字段</span>
       )}
 
+      {state.parseResult?.warnings && state.parseResult.warnings.length > 0 && (
+        <span
+          className="text-amber-500 truncate"
+          title={state.parseResult.warnings.join("\n")}
+        >
+          {state.parseResult.warnings.length} 条警告
+        </span>
+      )}
+
       <div className="flex-1" />
       <span>v0.1.0</span>
     </div>
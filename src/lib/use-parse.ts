@@ -1,7 +1,7 @@
 import { useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useApp } from "@/lib/app-context";
-import type { HistoryEntrySummary, ParseResult } from "@/types";
+import type { HistoryEntrySummary, HttpExchange } from "@/types";
 
 export function useParse() {
   const { state, dispatch } = useApp();
@@ -23,12 +23,14 @@ export function useParse() {
       dispatch({ type: "PARSE_START" });
       const start = performance.now();
       try {
-        const result = await invoke<ParseResult>("parse_text", { rawText: raw });
+        const exchanges = await invoke<HttpExchange[]>("parse_exchanges", { rawText: raw });
+        const result = exchanges.map((e) => e.request ?? e.response).find((r) => r !== undefined);
+        if (!result) throw new Error("No HTTP message found");
         const time = Math.round(performance.now() - start);
         dispatch({ type: "PARSE_SUCCESS", payload: result, time });
 
-        // fire-and-forget: save to history and refresh list
-        invoke("history_save", { rawText: raw, parseResult: result })
+        // fire-and-forget: save the parsed exchanges to history and refresh list
+        invoke("history_save", { rawText: raw, parseResult: result, exchanges })
           .then(() => refreshHistory())
           .catch(() => {});
       } catch (e) {
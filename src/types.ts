@@ -5,6 +5,7 @@ export interface ParseNode {
   description?: string;
   decodedValue?: string;
   valueType?: string;
+  provenance?: string[];
 }
 
 export type HttpContentType = "request" | "response" | "headersOnly" | "unknown";
@@ -13,20 +14,44 @@ export interface ParseResult {
   contentType: HttpContentType;
   method?: string;
   url?: string;
+  requestTarget?: string;
+  urlNodes?: ParseNode[];
   statusCode?: number;
   statusText?: string;
   protocol?: string;
   headers: ParseNode[];
+  pseudoHeaders?: ParseNode[];
   queryParams?: ParseNode[];
+  queryTree?: ParseNode[];
   body?: string;
+  bodyNodes?: ParseNode[];
+  wireBody?: string;
+  bodyProvenance?: ParseNode[];
+  trailers?: ParseNode[];
+  metadata?: ParseNode[];
+  warnings?: string[];
   rawText: string;
 }
 
+export interface CollectionRequest {
+  folder: string;
+  name: string;
+  request: ParseResult;
+}
+
+export interface HttpExchange {
+  request?: ParseResult;
+  response?: ParseResult;
+  startedAt?: string;
+  durationMs?: number;
+}
+
 export interface HistoryEntrySummary {
   id: string;
   title: string;
   method?: string;
   url?: string;
+  statusCode?: number;
   createdAt: string;
 }
 
@@ -35,5 +60,38 @@ export interface HistoryEntry {
   title: string;
   rawText: string;
   parseResult: ParseResult;
+  exchanges: HttpExchange[];
   createdAt: string;
 }
+
+export interface DecoderSettings {
+  id: string;
+  enabled?: boolean;
+  priority?: number;
+  keys?: string[];
+  excludeKeys?: string[];
+  headers?: string[];
+  excludeHeaders?: string[];
+}
+
+export interface DecodeRule {
+  name: string;
+  key: string;
+  pattern?: string;
+  split?: string;
+  fields?: string[];
+  decoders?: string[];
+  priority?: number;
+}
+
+export interface DecoderConfig {
+  decoders: DecoderSettings[];
+  rules?: DecodeRule[];
+  maxDepth?: number;
+  maxOutputBytes?: number;
+}
+
+export interface Settings {
+  decoders: DecoderConfig;
+  rulesFile?: string;
+}